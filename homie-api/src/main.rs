@@ -2,17 +2,30 @@
 use std::sync::{Arc, OnceLock};
 
 use axum::extract::{MatchedPath, Query, Request, State};
+use axum::response::{IntoResponse, Response};
 use axum::routing::{get, post};
 use axum::{Json, Router};
 use axum_extra::extract::Form;
 use error::AppError;
 use homie_core::adapter::config::Config;
 use homie_core::adapter::repository::Repository;
+use homie_core::domain::affordability::{Affordabilities, Affordability};
+use homie_core::domain::aggregate::{Aggregator, Derived};
+use homie_core::domain::analytics::{analyze_hpis, analyze_t_yields, analyze_zhvis};
 use homie_core::domain::common::RegionType;
+use homie_core::domain::fair_value::{FairValue, FairValueQuery, FairValues};
+use homie_core::domain::forecast::{
+    Backtest, ForecastFit, ForecastModel, ForecastPoint, ForecastQuery, Smoothing, ZhviForecast,
+    ZhviForecasts,
+};
 use homie_core::domain::hpi::{Hpi, Hpis};
+use homie_core::domain::mortgage::{Amortization, MortgageSummary};
+use homie_core::domain::mortgage_rate::{MortgageRate, MortgageRates};
 use homie_core::domain::region::{Region, Regions};
-use homie_core::domain::t_yield::{TYield, TYields};
-use homie_core::domain::zhvi::{HomeType, Percentile, Zhvi, ZhviPrice, Zhvis};
+use homie_core::domain::spread::SpreadAnalysis;
+use homie_core::domain::t_yield::TYield;
+use homie_core::domain::zhvi::{HomeType, Percentile, Zhvi, ZhviPrice};
+use homie_core::error::DomainError;
 use tower_http::cors::CorsLayer;
 use tower_http::trace::TraceLayer;
 use utoipa::OpenApi;
@@ -28,8 +41,21 @@ static CONFIG: OnceLock<Config> = OnceLock::new();
 const ZHVI_TAG: &str = "zhvis";
 #[derive(OpenApi)]
 #[openapi(
-        paths(read_zhvis),
-        components(schemas(Region, RegionType, HomeType, Percentile, ZhviPrice, Zhvi)),
+        paths(read_zhvis, read_zhvi_forecasts),
+        components(schemas(
+            Region,
+            RegionType,
+            HomeType,
+            Percentile,
+            ZhviPrice,
+            Zhvi,
+            Backtest,
+            ForecastFit,
+            ForecastModel,
+            ForecastPoint,
+            Smoothing,
+            ZhviForecast
+        )),
         tags(
             (name = "zhvis", description = "ZHVI endpoints.")
         ),
@@ -38,7 +64,8 @@ struct ApiDoc;
 
 #[tokio::main]
 async fn main() -> Result<(), AppError> {
-    let config = CONFIG.get_or_init(Config::load_config);
+    let config = Config::load_config().map_err(|e| AppError::Start(e.to_string()))?;
+    let config = CONFIG.get_or_init(|| config);
     init_tracing()?;
 
     let repo = Repository::new(config).await.map_err(|e| {
@@ -49,11 +76,20 @@ async fn main() -> Result<(), AppError> {
 
     let app = Router::new()
         .merge(SwaggerUi::new("/swagger-ui").url("/api-docs/openapi.json", ApiDoc::openapi()))
+        .route("/affordability", get(read_affordability))
+        .route("/fair-value", get(read_fair_value))
         .route("/health", get(health))
         .route("/hpis", get(read_hpis))
+        .route("/hpis/derived", get(read_derived_hpis))
+        .route("/mortgage/payment", get(read_mortgage_payment))
+        .route("/mortgage/schedule", get(read_mortgage_schedule))
+        .route("/mortgage-rates", get(read_mortgage_rates))
         .route("/regions", post(read_regions))
+        .route("/spreads", get(read_spreads))
         .route("/tyields", get(read_tyields))
         .route("/zhvis", get(read_zhvis))
+        .route("/zhvis/derived", get(read_derived_zhvis))
+        .route("/zhvis/forecast", get(read_zhvi_forecasts))
         .with_state(state)
         // .layer(CorsLayer::n)
         .layer(CorsLayer::very_permissive())
@@ -80,14 +116,141 @@ async fn health() -> &'static str {
     "Service is running."
 }
 
+// Payment-to-income of homes at each ZHVI tier, against the local median
+// household income
+async fn read_affordability(
+    State(state): State<Arc<AppState>>,
+    Query(param): Query<AffordabilityParam>,
+) -> Result<Json<Affordabilities>, AppError> {
+    tracing::debug!(
+        "Reading Affordability with {:?}",
+        serde_json::to_string(&param)?
+    );
+    let query = param.try_into()?;
+    let affordabilities = Affordability::read_by_query(state.session(), &query)
+        .await
+        .map_err(|e| match e {
+            DomainError::ConvertDomain(_) => AppError::Request(e.to_string()),
+            e => e.into(),
+        })?;
+    Ok(Json(affordabilities))
+}
+
+// Answers "what value of a home is fair?" from the region's price-to-income
+// history
+async fn read_fair_value(
+    State(state): State<Arc<AppState>>,
+    Query(param): Query<AffordabilityParam>,
+    Query(model): Query<FairValueParam>,
+) -> Result<Json<FairValues>, AppError> {
+    tracing::debug!(
+        "Reading FairValue with {:?} {:?}",
+        serde_json::to_string(&param)?,
+        serde_json::to_string(&model)?
+    );
+    let query = FairValueQuery::new(param.try_into()?, model.try_into()?);
+    let fair_values = FairValue::read_by_query(state.session(), &query)
+        .await
+        .map_err(|e| match e {
+            DomainError::ConvertDomain(_) => AppError::Request(e.to_string()),
+            e => e.into(),
+        })?;
+    Ok(Json(fair_values))
+}
+
 async fn read_hpis(
     State(state): State<Arc<AppState>>,
     Query(param): Query<HpiParam>,
-) -> Result<Json<Hpis>, AppError> {
-    tracing::debug!("Reading HPIs with {:?}", serde_json::to_string(&param)?);
+    Query(transform): Query<TransformParam>,
+) -> Result<Response, AppError> {
+    tracing::debug!(
+        "Reading HPIs with {:?} {:?}",
+        serde_json::to_string(&param)?,
+        serde_json::to_string(&transform)?
+    );
+    let transform = transform.transform()?;
     let query = param.try_into()?;
-    let hpis = Hpi::read_by_query(state.session(), &query).await?;
-    Ok(Json(hpis))
+    let hpis = Hpi::read_by_query(state.session(), &query)
+        .await
+        .map_err(|e| match e {
+            DomainError::ConvertDomain(_) => AppError::Request(e.to_string()),
+            e => e.into(),
+        })?;
+    Ok(match transform {
+        Some((transform, window)) => Json(analyze_hpis(&hpis, &transform, window)).into_response(),
+        None => Json(hpis).into_response(),
+    })
+}
+
+// Builds a city or county HPI from the five-zip HPIs inside it, for places
+// FHFA publishes none for and to cross-check the published counties
+async fn read_derived_hpis(
+    State(state): State<Arc<AppState>>,
+    Query(param): Query<HpiParam>,
+    Query(derived): Query<DerivedParam>,
+) -> Result<Json<Vec<Derived<Hpis>>>, AppError> {
+    tracing::debug!(
+        "Reading derived HPIs with {:?} {:?}",
+        serde_json::to_string(&param)?,
+        serde_json::to_string(&derived)?
+    );
+    let (regions, region_type) = derived.hpi_regions(&param)?;
+    let aggregation = derived.aggregation()?;
+    let query = param.try_into()?;
+    let derived = async {
+        Aggregator::read(state.session(), &regions, region_type)
+            .await?
+            .read_hpis(state.session(), &query, aggregation)
+            .await
+    }
+    .await
+    .map_err(|e| match e {
+        DomainError::ConvertDomain(_) => AppError::Request(e.to_string()),
+        e => e.into(),
+    })?;
+    Ok(Json(derived))
+}
+
+async fn read_mortgage_payment(
+    State(state): State<Arc<AppState>>,
+    Query(param): Query<MortgageParam>,
+) -> Result<Json<MortgageSummary>, AppError> {
+    tracing::debug!(
+        "Reading mortgage payment with {:?}",
+        serde_json::to_string(&param)?
+    );
+    let mortgage = param.mortgage(state.session()).await?;
+    Ok(Json(mortgage.summary(param.months_paid())))
+}
+
+async fn read_mortgage_schedule(
+    State(state): State<Arc<AppState>>,
+    Query(param): Query<MortgageParam>,
+) -> Result<Json<Amortization>, AppError> {
+    tracing::debug!(
+        "Reading mortgage schedule with {:?}",
+        serde_json::to_string(&param)?
+    );
+    let mortgage = param.mortgage(state.session()).await?;
+    Ok(Json(mortgage.amortization()))
+}
+
+async fn read_mortgage_rates(
+    State(state): State<Arc<AppState>>,
+    Query(param): Query<MortgageRateParam>,
+) -> Result<Json<MortgageRates>, AppError> {
+    tracing::debug!(
+        "Reading MortgageRates with {:?}",
+        serde_json::to_string(&param)?
+    );
+    let query = param.try_into()?;
+    let mortgage_rates = MortgageRate::read_by_query(state.session(), &query)
+        .await
+        .map_err(|e| match e {
+            DomainError::ConvertDomain(_) => AppError::Request(e.to_string()),
+            e => e.into(),
+        })?;
+    Ok(Json(mortgage_rates))
 }
 
 async fn read_regions(
@@ -100,23 +263,119 @@ async fn read_regions(
     Ok(Json(regions))
 }
 
+// Answers "what mortgage rate is fair?" from the mortgage rate to 10-year
+// Treasury spread
+async fn read_spreads(
+    State(state): State<Arc<AppState>>,
+    Query(param): Query<SpreadParam>,
+) -> Result<Json<SpreadAnalysis>, AppError> {
+    tracing::debug!("Reading Spreads with {:?}", serde_json::to_string(&param)?);
+    let query = param.try_into()?;
+    let analysis = SpreadAnalysis::read_by_query(state.session(), &query)
+        .await
+        .map_err(|e| match e {
+            DomainError::ConvertDomain(_) => AppError::Request(e.to_string()),
+            e => e.into(),
+        })?;
+    Ok(Json(analysis))
+}
+
 async fn read_tyields(
     State(state): State<Arc<AppState>>,
     Query(param): Query<TYieldParam>,
-) -> Result<Json<TYields>, AppError> {
-    tracing::debug!("Reading TYields with {:?}", serde_json::to_string(&param)?);
+    Query(transform): Query<TransformParam>,
+) -> Result<Response, AppError> {
+    tracing::debug!(
+        "Reading TYields with {:?} {:?}",
+        serde_json::to_string(&param)?,
+        serde_json::to_string(&transform)?
+    );
+    let transform = transform.transform()?;
     let query = param.try_into()?;
     let t_yields = TYield::read_by_query(state.session(), &query).await?;
-    Ok(Json(t_yields))
+    Ok(match transform {
+        Some((transform, window)) => {
+            Json(analyze_t_yields(&t_yields, &transform, window)).into_response()
+        }
+        None => Json(t_yields).into_response(),
+    })
 }
 
-#[utoipa::path(get, path = "/zhvis", params(ZhviParam),responses((status = 200, description = "Read Zhvis by query", body = [Zhvi])), tag = ZHVI_TAG)]
+#[utoipa::path(get, path = "/zhvis", params(ZhviParam, TransformParam),responses((status = 200, description = "Read Zhvis by query", body = [Zhvi])), tag = ZHVI_TAG)]
 async fn read_zhvis(
     State(state): State<Arc<AppState>>,
     Query(param): Query<ZhviParam>,
-) -> Result<Json<Zhvis>, AppError> {
-    tracing::debug!("Reading Zhvis with {:?}", serde_json::to_string(&param)?);
+    Query(transform): Query<TransformParam>,
+) -> Result<Response, AppError> {
+    tracing::debug!(
+        "Reading Zhvis with {:?} {:?}",
+        serde_json::to_string(&param)?,
+        serde_json::to_string(&transform)?
+    );
+    let transform = transform.transform()?;
+    let query = param.try_into()?;
+    let zhvis = Zhvi::read_by_query(state.session(), &query)
+        .await
+        .map_err(|e| match e {
+            DomainError::ConvertDomain(_) => AppError::Request(e.to_string()),
+            e => e.into(),
+        })?;
+    Ok(match transform {
+        Some((transform, window)) => {
+            Json(analyze_zhvis(&zhvis, &transform, window)).into_response()
+        }
+        None => Json(zhvis).into_response(),
+    })
+}
+
+// Projects each series `/zhvis` returns for the same params `horizon`
+// intervals ahead, flagging the forecast points
+#[utoipa::path(get, path = "/zhvis/forecast", params(ZhviParam, ForecastParam),responses((status = 200, description = "Forecast Zhvis by query", body = [ZhviForecast])), tag = ZHVI_TAG)]
+async fn read_zhvi_forecasts(
+    State(state): State<Arc<AppState>>,
+    Query(param): Query<ZhviParam>,
+    Query(forecast): Query<ForecastParam>,
+) -> Result<Json<ZhviForecasts>, AppError> {
+    tracing::debug!(
+        "Reading ZhviForecasts with {:?} {:?}",
+        serde_json::to_string(&param)?,
+        serde_json::to_string(&forecast)?
+    );
+    let query = ForecastQuery::new(param.try_into()?, forecast.horizon()?, forecast.model()?);
+    let forecasts = ZhviForecast::read_by_query(state.session(), &query)
+        .await
+        .map_err(|e| match e {
+            DomainError::ConvertDomain(_) => AppError::Request(e.to_string()),
+            e => e.into(),
+        })?;
+    Ok(Json(forecasts))
+}
+
+// Builds a city or county ZHVI from the five-zip ZHVIs inside it, for places
+// Zillow publishes none for and to cross-check the published counties
+async fn read_derived_zhvis(
+    State(state): State<Arc<AppState>>,
+    Query(param): Query<ZhviParam>,
+    Query(derived): Query<DerivedParam>,
+) -> Result<Json<Vec<Derived<Zhvi>>>, AppError> {
+    tracing::debug!(
+        "Reading derived Zhvis with {:?} {:?}",
+        serde_json::to_string(&param)?,
+        serde_json::to_string(&derived)?
+    );
+    let (regions, region_type) = derived.zhvi_regions(&param)?;
+    let aggregation = derived.aggregation()?;
     let query = param.try_into()?;
-    let zhvis = Zhvi::read_by_query(state.session(), &query).await?;
-    Ok(Json(zhvis))
+    let derived = async {
+        Aggregator::read(state.session(), &regions, region_type)
+            .await?
+            .read_zhvis(state.session(), &query, aggregation)
+            .await
+    }
+    .await
+    .map_err(|e| match e {
+        DomainError::ConvertDomain(_) => AppError::Request(e.to_string()),
+        e => e.into(),
+    })?;
+    Ok(Json(derived))
 }
@@ -2,11 +2,22 @@ use std::fmt::Debug;
 
 use chrono::{Datelike, NaiveDate};
 use homie_core::adapter::repository::{Persist, Repository};
+use homie_core::domain::affordability::{AffordabilityQuery, Financing};
+use homie_core::domain::aggregate::Aggregation;
+use homie_core::domain::analytics::{Transform, DEFAULT_WINDOW};
 use homie_core::domain::common::{DateInterval, RegionType};
+use homie_core::domain::cpi::CpiBase;
+use homie_core::domain::fair_value::FairValueModel;
+use homie_core::domain::forecast::ForecastModel;
 use homie_core::domain::hpi::HpiQuery;
+use homie_core::domain::mortgage::{read_rate, Mortgage, RateSource};
+use homie_core::domain::mortgage_rate::{MortgageRateQuery, MortgageTerm};
 use homie_core::domain::region::RegionQuery;
-use homie_core::domain::t_yield::TYieldQuery;
-use homie_core::domain::zhvi::{HomeType, Percentile, ZhviQuery};
+use homie_core::domain::resample::Resampling;
+use homie_core::domain::spread::SpreadQuery;
+use homie_core::domain::t_yield::{TYieldQuery, Term};
+use homie_core::domain::zhvi::{GapFill, HomeType, Percentile, ZhviQuery};
+use homie_core::error::DomainError;
 use serde::{Deserialize, Serialize};
 use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::util::SubscriberInitExt;
@@ -29,11 +40,191 @@ impl AppState {
 }
 
 #[derive(Debug, Deserialize, Serialize)]
-pub(crate) struct HpiParam {
-    // region_type: String,
+pub(crate) struct AffordabilityParam {
+    start_date: String,
+    end_date: String,
+    home_type: String,
+    region_type: String,
     region_name: String,
+    // Every tier when unset
+    percentile: Option<String>,
+    // Income region id or name, when it differs from the ZHVI region name
+    income_region: Option<String>,
+    #[serde(default = "default_down_payment_percent")]
+    down_payment_percent: f64,
+    #[serde(default = "default_term_years")]
+    term_years: u32,
+    // `mortgage` (PMMS, default) or `tyield`
+    rate_source: Option<String>,
+    // Treasury term and spread added to it, for the `tyield` source
+    term: Option<String>,
+    #[serde(default)]
+    spread: f64,
+}
+
+fn default_down_payment_percent() -> f64 {
+    20.0
+}
+
+impl TryFrom<AffordabilityParam> for AffordabilityQuery {
+    type Error = AppError;
+
+    fn try_from(param: AffordabilityParam) -> Result<Self, Self::Error> {
+        let start_date = parse_naive_date(&param.start_date)?;
+        let end_date = parse_naive_date(&param.end_date)?;
+        let home_type = parse_home_type(&param.home_type)?;
+        let region_type = parse_region_type(&param.region_type)?;
+        let percentile = param
+            .percentile
+            .as_deref()
+            .map(parse_percentile)
+            .transpose()?;
+        let rate_source = parse_rate_source(
+            param.rate_source.as_deref(),
+            param.term.as_deref(),
+            param.spread,
+        )?;
+        let financing = Financing::new(rate_source, param.term_years, param.down_payment_percent)
+            .map_err(|e| AppError::Request(e.to_string()))?;
+        let query = AffordabilityQuery::new(
+            param.region_name,
+            region_type,
+            home_type,
+            percentile,
+            start_date,
+            end_date,
+            financing,
+        );
+        Ok(match param.income_region {
+            Some(income_region) => query.with_income_region(income_region),
+            None => query,
+        })
+    }
+}
+
+// Read alongside `ZhviParam` or `HpiParam`, whose region is the city name or
+// county FIPS code to build from its five-zip series
+#[derive(Debug, Deserialize, Serialize)]
+pub(crate) struct DerivedParam {
+    // USPS code, to tell apart same-named cities
+    state: Option<String>,
+    // `mean` (default) or `median` of the zips, weighted by their residential
+    // address shares
+    zip_aggregation: Option<String>,
+}
+
+impl DerivedParam {
+    pub(crate) fn zhvi_regions(
+        &self,
+        param: &ZhviParam,
+    ) -> Result<(RegionQuery, RegionType), AppError> {
+        self.regions(&param.region_type, &param.region_name)
+    }
+
+    pub(crate) fn hpi_regions(
+        &self,
+        param: &HpiParam,
+    ) -> Result<(RegionQuery, RegionType), AppError> {
+        self.regions(&param.region_type, &param.region_id)
+    }
+
+    fn regions(
+        &self,
+        region_type: &str,
+        name: &str,
+    ) -> Result<(RegionQuery, RegionType), AppError> {
+        let region_type = parse_region_type(region_type)?;
+        let states = self
+            .state
+            .iter()
+            .map(|state| state.to_ascii_uppercase())
+            .collect();
+        let query = match region_type {
+            RegionType::City => {
+                RegionQuery::new(vec![name.to_ascii_lowercase()], vec![], vec![], states)
+            }
+            RegionType::County => RegionQuery::new(vec![], vec![], vec![name.to_string()], states),
+            _ => {
+                return Err(AppError::Request(
+                    "Derived series are built for a city or county".to_string(),
+                ))
+            }
+        };
+        Ok((query, region_type))
+    }
+
+    pub(crate) fn aggregation(&self) -> Result<Aggregation, AppError> {
+        match self.zip_aggregation.as_deref() {
+            Some(aggregation) => Aggregation::try_from(aggregation.to_ascii_lowercase().as_str())
+                .map_err(|_| AppError::Request("Failed to read zip_aggregation".to_string())),
+            None => Ok(Aggregation::default()),
+        }
+    }
+}
+
+// Read alongside `AffordabilityParam`, which selects the prices, rates and
+// incomes to fit
+#[derive(Debug, Deserialize, Serialize)]
+pub(crate) struct FairValueParam {
+    // `pricetoincome` (default) or `rateadjusted`
+    model: Option<String>,
+}
+
+impl TryFrom<FairValueParam> for FairValueModel {
+    type Error = AppError;
+
+    fn try_from(param: FairValueParam) -> Result<Self, Self::Error> {
+        match param.model {
+            Some(model) => FairValueModel::try_from(model.to_ascii_lowercase().as_str())
+                .map_err(|_| AppError::Request("Failed to read fair value model".to_string())),
+            None => Ok(FairValueModel::default()),
+        }
+    }
+}
+
+// Read alongside `ZhviParam`, which selects the series to project
+#[derive(Debug, Deserialize, Serialize, IntoParams)]
+pub(crate) struct ForecastParam {
+    // Intervals to project past the last price
+    horizon: usize,
+    // `holtwinters` (default) or `seasonalnaive`
+    model: Option<String>,
+}
+
+pub(crate) const MAX_HORIZON: usize = 120;
+
+impl ForecastParam {
+    pub(crate) fn horizon(&self) -> Result<usize, AppError> {
+        if self.horizon == 0 || self.horizon > MAX_HORIZON {
+            return Err(AppError::Request(format!(
+                "Horizon must be between 1 and {}",
+                MAX_HORIZON
+            )));
+        }
+        Ok(self.horizon)
+    }
+
+    pub(crate) fn model(&self) -> Result<ForecastModel, AppError> {
+        match self.model.as_deref() {
+            Some(model) => ForecastModel::try_from(model.to_ascii_lowercase().as_str())
+                .map_err(|_| AppError::Request("Failed to read forecast model".to_string())),
+            None => Ok(ForecastModel::default()),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub(crate) struct HpiParam {
+    region_type: String,
+    // Zip code, county FIPS, state abbreviation or CBSA code
+    #[serde(alias = "region_name")]
+    region_id: String,
     start_date: String,
     end_date: String,
+    // Deflates by the CPI to dollars of `base_month`
+    real: Option<bool>,
+    // `YYYY-MM`, the latest month with a CPI by default
+    base_month: Option<String>,
     // annual_change: bool,
     // base_2000: bool,
 }
@@ -42,14 +233,99 @@ impl TryFrom<HpiParam> for HpiQuery {
     type Error = AppError;
 
     fn try_from(param: HpiParam) -> Result<Self, Self::Error> {
-        let region_name = param.region_name.clone();
+        let region_type = parse_region_type(&param.region_type)?;
+        let region_id = param.region_id.clone();
         let start_date = parse_naive_date(&param.start_date)?;
         let end_date = parse_naive_date(&param.end_date)?;
-        Ok(HpiQuery::new(
-            region_name,
-            start_date.year(),
-            end_date.year(),
-        ))
+        let real = parse_cpi_base(param.real, param.base_month.as_deref())?;
+        let query = HpiQuery::new(region_type, region_id, start_date.year(), end_date.year());
+        Ok(match real {
+            Some(base) => query.with_real(base),
+            None => query,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub(crate) struct MortgageParam {
+    price: f64,
+    #[serde(default)]
+    down_payment: f64,
+    #[serde(default = "default_term_years")]
+    term_years: u32,
+    // Annual rate in percent, otherwise looked up on `date`
+    rate: Option<f64>,
+    // `mortgage` (PMMS, default) or `tyield`
+    rate_source: Option<String>,
+    date: Option<String>,
+    // Treasury term and spread added to it, for the `tyield` source
+    term: Option<String>,
+    #[serde(default)]
+    spread: f64,
+    // Payments made, to report the balance left
+    months_paid: Option<u32>,
+}
+
+fn default_term_years() -> u32 {
+    30
+}
+
+impl MortgageParam {
+    pub(crate) fn months_paid(&self) -> Option<u32> {
+        self.months_paid
+    }
+
+    /// Takes the given rate, or the latest stored one on `date`
+    pub(crate) async fn mortgage(&self, client: &dyn Persist) -> Result<Mortgage, AppError> {
+        let rate = match self.rate {
+            Some(rate) => rate,
+            None => {
+                let date = self.date.as_deref().ok_or_else(|| {
+                    AppError::Request("Either rate or date is required".to_string())
+                })?;
+                let date = parse_naive_date(date)?;
+                let rate_source = parse_rate_source(
+                    self.rate_source.as_deref(),
+                    self.term.as_deref(),
+                    self.spread,
+                )?;
+                read_rate(client, &rate_source, self.term_years, date)
+                    .await
+                    .map_err(|e| match e {
+                        DomainError::ConvertDomain(_) => AppError::Request(e.to_string()),
+                        e => e.into(),
+                    })?
+            }
+        };
+        Mortgage::new(self.price, self.down_payment, rate, self.term_years)
+            .map_err(|e| AppError::Request(e.to_string()))
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub(crate) struct MortgageRateParam {
+    // Term such as `30y` or `fifteenyear`, all terms when unset
+    term: Option<String>,
+    start_date: String,
+    end_date: String,
+    date_interval: String,
+    // `first`, `last`, `mean` (default), `median`, `min` or `max` per interval
+    aggregation: Option<String>,
+}
+
+impl TryFrom<MortgageRateParam> for MortgageRateQuery {
+    type Error = AppError;
+
+    fn try_from(param: MortgageRateParam) -> Result<Self, Self::Error> {
+        let start_date = parse_naive_date(&param.start_date)?;
+        let end_date = parse_naive_date(&param.end_date)?;
+        let date_interval = parse_date_interval(&param.date_interval)?;
+        let term = param.term.as_deref().map(parse_mortgage_term).transpose()?;
+        let resampling = parse_resampling(param.aggregation.as_deref())?;
+        Ok(
+            MortgageRateQuery::new(term, start_date, end_date, date_interval)
+                .with_resampling(resampling),
+        )
     }
 }
 
@@ -59,6 +335,10 @@ pub(crate) struct RegionParam {
     cities: Vec<String>,
     #[serde(default)]
     zipcodes: Vec<String>,
+    #[serde(default)]
+    county_fips: Vec<String>,
+    #[serde(default)]
+    states: Vec<String>,
 }
 
 impl From<RegionParam> for RegionQuery {
@@ -72,15 +352,79 @@ impl From<RegionParam> for RegionQuery {
             })
             .collect();
         let zipcodes = param.zipcodes;
-        RegionQuery::new(cities, zipcodes)
+        let states = param
+            .states
+            .into_iter()
+            .map(|mut state| {
+                state.make_ascii_uppercase();
+                state
+            })
+            .collect();
+        RegionQuery::new(cities, zipcodes, param.county_fips, states)
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub(crate) struct SpreadParam {
+    // Mortgage term, 30-year unless set
+    term: Option<String>,
+    start_date: String,
+    end_date: String,
+    date_interval: String,
+}
+
+impl TryFrom<SpreadParam> for SpreadQuery {
+    type Error = AppError;
+
+    fn try_from(param: SpreadParam) -> Result<Self, Self::Error> {
+        let start_date = parse_naive_date(&param.start_date)?;
+        let end_date = parse_naive_date(&param.end_date)?;
+        let date_interval = parse_date_interval(&param.date_interval)?;
+        let term = param
+            .term
+            .as_deref()
+            .map(parse_mortgage_term)
+            .transpose()?
+            .unwrap_or_default();
+        Ok(SpreadQuery::new(term, start_date, end_date, date_interval))
+    }
+}
+
+// Read alongside the `/zhvis`, `/hpis` and `/tyields` params, which select the
+// series to transform
+#[derive(Debug, Deserialize, Serialize, IntoParams)]
+pub(crate) struct TransformParam {
+    // `yoy`, `mom`, `cagr`, `rollingmean`, `rollingvolatility` or `drawdown`,
+    // the stored series when unset
+    transform: Option<String>,
+    // Observations per window, for the rolling transforms
+    window: Option<usize>,
+}
+
+impl TransformParam {
+    pub(crate) fn transform(&self) -> Result<Option<(Transform, usize)>, AppError> {
+        let Some(transform) = self.transform.as_deref() else {
+            return Ok(None);
+        };
+        let transform = Transform::try_from(transform.to_ascii_lowercase().as_str())
+            .map_err(|_| AppError::Request("Failed to read transform".to_string()))?;
+        let window = self.window.unwrap_or(DEFAULT_WINDOW);
+        if window == 0 {
+            return Err(AppError::Request("Window must be positive".to_string()));
+        }
+        Ok(Some((transform, window)))
     }
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 pub(crate) struct TYieldParam {
+    // Term such as `10y` or `tenyear`, all terms when unset
+    term: Option<String>,
     start_date: String,
     end_date: String,
     date_interval: String,
+    // `first`, `last`, `mean` (default), `median`, `min` or `max` per interval
+    aggregation: Option<String>,
 }
 
 impl TryFrom<TYieldParam> for TYieldQuery {
@@ -90,7 +434,9 @@ impl TryFrom<TYieldParam> for TYieldQuery {
         let start_date = parse_naive_date(&param.start_date)?;
         let end_date = parse_naive_date(&param.end_date)?;
         let date_interval = parse_date_interval(&param.date_interval)?;
-        Ok(TYieldQuery::new(start_date, end_date, date_interval))
+        let term = param.term.as_deref().map(parse_term).transpose()?;
+        let resampling = parse_resampling(param.aggregation.as_deref())?;
+        Ok(TYieldQuery::new(term, start_date, end_date, date_interval).with_resampling(resampling))
     }
 }
 
@@ -103,6 +449,16 @@ pub(crate) struct ZhviParam {
     region_type: String,
     region_name: String,
     percentile: String,
+    // USPS code, to tell apart same-named cities and counties
+    state: Option<String>,
+    // `first`, `last`, `mean` (default), `median`, `min` or `max` per interval
+    aggregation: Option<String>,
+    // `none` (default), `linear` or `ffill` for months missing between prices
+    fill: Option<String>,
+    // Deflates by the CPI to dollars of `base_month`
+    real: Option<bool>,
+    // `YYYY-MM`, the latest month with a CPI by default
+    base_month: Option<String>,
 }
 
 impl TryFrom<ZhviParam> for ZhviQuery {
@@ -116,7 +472,10 @@ impl TryFrom<ZhviParam> for ZhviQuery {
         let region_type = parse_region_type(&param.region_type)?;
         let home_type = parse_home_type(&param.home_type)?;
         let percentile = parse_percentile(&param.percentile)?;
-        Ok(Self::new(
+        let resampling = parse_resampling(param.aggregation.as_deref())?;
+        let gap_fill = parse_gap_fill(param.fill.as_deref())?;
+        let real = parse_cpi_base(param.real, param.base_month.as_deref())?;
+        let query = Self::new(
             start_date,
             end_date,
             date_interval,
@@ -124,7 +483,41 @@ impl TryFrom<ZhviParam> for ZhviQuery {
             region_type,
             home_type,
             percentile,
-        ))
+        )
+        .with_resampling(resampling)
+        .with_gap_fill(gap_fill);
+        let query = match param.state {
+            Some(state) => query.with_state(state.to_ascii_uppercase()),
+            None => query,
+        };
+        Ok(match real {
+            Some(base) => query.with_real(base),
+            None => query,
+        })
+    }
+}
+
+fn parse_cpi_base(
+    real: Option<bool>,
+    base_month: Option<&str>,
+) -> Result<Option<CpiBase>, AppError> {
+    match (real.unwrap_or_default(), base_month) {
+        (false, None) => Ok(None),
+        (false, Some(_)) => Err(AppError::Request(
+            "base_month requires real=true".to_string(),
+        )),
+        (true, None) => Ok(Some(CpiBase::Latest)),
+        (true, Some(month)) => NaiveDate::parse_from_str(&format!("{}-01", month), "%Y-%m-%d")
+            .map(|month| Some(CpiBase::Month(month)))
+            .map_err(|_| AppError::Request("Failed to read base month".to_string())),
+    }
+}
+
+fn parse_gap_fill(input: Option<&str>) -> Result<GapFill, AppError> {
+    match input {
+        Some(input) => GapFill::try_from(input.to_ascii_lowercase().as_str())
+            .map_err(|_| AppError::Request("Failed to read fill".to_string())),
+        None => Ok(GapFill::default()),
     }
 }
 
@@ -138,6 +531,11 @@ fn parse_date_interval(input: &str) -> Result<DateInterval, AppError> {
         .map_err(|_| AppError::Request("Failed to read date interval".to_string()))
 }
 
+fn parse_mortgage_term(input: &str) -> Result<MortgageTerm, AppError> {
+    MortgageTerm::try_from(input.to_ascii_lowercase().as_str())
+        .map_err(|_| AppError::Request("Failed to read mortgage term".to_string()))
+}
+
 fn parse_naive_date(input: &str) -> Result<NaiveDate, AppError> {
     Ok(NaiveDate::parse_from_str(input, "%Y-%m-%d")?)
 }
@@ -147,11 +545,42 @@ fn parse_percentile(input: &str) -> Result<Percentile, AppError> {
         .map_err(|_| AppError::Request("Failed to read percentile".to_string()))
 }
 
+fn parse_rate_source(
+    rate_source: Option<&str>,
+    term: Option<&str>,
+    spread: f64,
+) -> Result<RateSource, AppError> {
+    match rate_source.map(str::to_ascii_lowercase).as_deref() {
+        None | Some("mortgage") => Ok(RateSource::MortgageRate),
+        Some("tyield") => {
+            let term = term.map(parse_term).transpose()?;
+            Ok(RateSource::TYield {
+                term: term.unwrap_or_default(),
+                spread,
+            })
+        }
+        Some(_) => Err(AppError::Request("Failed to read rate source".to_string())),
+    }
+}
+
 fn parse_region_type(input: &str) -> Result<RegionType, AppError> {
     RegionType::try_from(input.to_ascii_lowercase().as_str())
         .map_err(|_| AppError::Request("Failed to read region type".to_string()))
 }
 
+fn parse_resampling(input: Option<&str>) -> Result<Resampling, AppError> {
+    match input {
+        Some(input) => Resampling::try_from(input.to_ascii_lowercase().as_str())
+            .map_err(|_| AppError::Request("Failed to read aggregation".to_string())),
+        None => Ok(Resampling::default()),
+    }
+}
+
+fn parse_term(input: &str) -> Result<Term, AppError> {
+    Term::try_from(input.to_ascii_lowercase().as_str())
+        .map_err(|_| AppError::Request("Failed to read term".to_string()))
+}
+
 pub(crate) fn init_tracing() -> Result<(), AppError> {
     tracing_subscriber::registry()
         .with(
@@ -0,0 +1,2 @@
+-- HomeType::CondoCoOps encodes as 'condocoops'
+ALTER TYPE home_type RENAME VALUE 'condococops' TO 'condocoops';
@@ -0,0 +1,7 @@
+-- Regions span every state in the HUD crosswalk; COUNTY is the 5-digit FIPS
+ALTER TABLE regions
+    ADD COLUMN state VARCHAR(2) NOT NULL DEFAULT '',
+    ADD COLUMN county_fips VARCHAR(5) NOT NULL DEFAULT '';
+
+CREATE INDEX regions_state_idx ON regions (state);
+CREATE INDEX regions_county_fips_idx ON regions (county_fips);
@@ -0,0 +1,7 @@
+-- A zip spanning several counties keeps one row per county, weighted by HUD's address ratios
+ALTER TABLE regions
+    DROP CONSTRAINT regions_pkey,
+    ADD COLUMN res_ratio FLOAT8 NOT NULL DEFAULT 1,
+    ADD COLUMN bus_ratio FLOAT8 NOT NULL DEFAULT 1,
+    ADD COLUMN tot_ratio FLOAT8 NOT NULL DEFAULT 1,
+    ADD PRIMARY KEY (zipcode, county_fips);
@@ -0,0 +1,20 @@
+-- FHFA state, metro and national HPIs
+ALTER TYPE region_type ADD VALUE 'state';
+ALTER TYPE region_type ADD VALUE 'msa';
+ALTER TYPE region_type ADD VALUE 'national';
+
+-- HPIs are keyed by region id (zip, county FIPS, state, CBSA) so same-named counties no
+-- longer collide. County rows were keyed by name only; re-import them to restore with FIPS.
+DELETE FROM hpis WHERE region_type = 'county';
+
+ALTER TABLE hpis
+    DROP CONSTRAINT hpis_pkey,
+    ADD COLUMN region_id VARCHAR(50),
+    ADD COLUMN state VARCHAR(2),
+    ALTER COLUMN region_name TYPE TEXT;
+
+UPDATE hpis SET region_id = region_name;
+
+ALTER TABLE hpis
+    ALTER COLUMN region_id SET NOT NULL,
+    ADD PRIMARY KEY (region_type, region_id, year);
@@ -0,0 +1,13 @@
+-- Every Treasury constant maturity in the H.15 release, kept in maturity order
+ALTER TYPE term ADD VALUE 'onemonth' BEFORE 'tenyear';
+ALTER TYPE term ADD VALUE 'twomonth' BEFORE 'tenyear';
+ALTER TYPE term ADD VALUE 'threemonth' BEFORE 'tenyear';
+ALTER TYPE term ADD VALUE 'fourmonth' BEFORE 'tenyear';
+ALTER TYPE term ADD VALUE 'sixmonth' BEFORE 'tenyear';
+ALTER TYPE term ADD VALUE 'oneyear' BEFORE 'tenyear';
+ALTER TYPE term ADD VALUE 'twoyear' BEFORE 'tenyear';
+ALTER TYPE term ADD VALUE 'threeyear' BEFORE 'tenyear';
+ALTER TYPE term ADD VALUE 'fiveyear' BEFORE 'tenyear';
+ALTER TYPE term ADD VALUE 'sevenyear' BEFORE 'tenyear';
+ALTER TYPE term ADD VALUE 'twentyyear' AFTER 'tenyear';
+ALTER TYPE term ADD VALUE 'thirtyyear' AFTER 'twentyyear';
@@ -0,0 +1,10 @@
+-- Freddie Mac PMMS weekly fixed rates
+CREATE TYPE mortgage_term AS ENUM ('thirtyyear', 'fifteenyear');
+
+CREATE TABLE mortgage_rates (
+    term mortgage_term NOT NULL,
+    date DATE NOT NULL,
+    rate FLOAT4,
+    points FLOAT4,
+    PRIMARY KEY (date, term)
+);
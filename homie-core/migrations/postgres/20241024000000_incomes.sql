@@ -0,0 +1,12 @@
+-- Census ACS median household income, table B19013
+CREATE TABLE incomes (
+    region_type region_type NOT NULL,
+    region_id VARCHAR(50) NOT NULL,
+    region_name TEXT NOT NULL,
+    year INTEGER NOT NULL,
+    median_income FLOAT8,
+    margin_of_error FLOAT8,
+    PRIMARY KEY (region_type, region_id, year)
+);
+
+CREATE INDEX incomes_region_name_idx ON incomes (region_type, region_name);
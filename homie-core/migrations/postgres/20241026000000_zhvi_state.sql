@@ -0,0 +1,19 @@
+-- ZHVIs are keyed by state too so same-named cities and counties no longer collide. City and
+-- county rows were keyed by name only; re-import them to restore with their state.
+DELETE FROM zhvi_prices WHERE region_type IN ('city', 'county');
+DELETE FROM zhvi_metadata WHERE region_type IN ('city', 'county');
+
+ALTER TABLE zhvi_prices
+    DROP CONSTRAINT zhvi_prices_home_type_region_type_region_name_percentile_fkey,
+    DROP CONSTRAINT zhvi_prices_pkey,
+    ADD COLUMN state TEXT NOT NULL DEFAULT '';
+
+ALTER TABLE zhvi_metadata
+    DROP CONSTRAINT zhvi_metadata_pkey,
+    ADD COLUMN state TEXT NOT NULL DEFAULT '',
+    ADD PRIMARY KEY (home_type, region_type, region_name, percentile, state);
+
+ALTER TABLE zhvi_prices
+    ADD PRIMARY KEY (home_type, region_type, region_name, percentile, state, date),
+    ADD FOREIGN KEY (home_type, region_type, region_name, percentile, state)
+        REFERENCES zhvi_metadata(home_type, region_type, region_name, percentile, state);
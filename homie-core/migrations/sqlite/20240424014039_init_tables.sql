@@ -0,0 +1,43 @@
+CREATE TABLE tyields (
+    term TEXT NOT NULL CHECK (term IN ('tenyear')),
+    date DATE NOT NULL,
+    yield_return FLOAT4,
+    PRIMARY KEY (date, term)
+);
+
+CREATE TABLE hpis (
+    region_type TEXT NOT NULL CHECK (region_type IN ('threezip', 'fivezip', 'city', 'county')),
+    region_name VARCHAR(50) NOT NULL,
+    year INTEGER NOT NULL,
+    hpi FLOAT4,
+    annual_change FLOAT4,
+    hpi_1990_base FLOAT4,
+    hpi_2000_base FLOAT4,
+    PRIMARY KEY (region_name, year)
+);
+
+CREATE TABLE regions (
+    city VARCHAR(50) NOT NULL,
+    zipcode VARCHAR(10) NOT NULL,
+    PRIMARY KEY (zipcode)
+);
+
+CREATE TABLE zhvi_metadata (
+    home_type TEXT NOT NULL CHECK (home_type IN ('allhomes', 'condocoops', 'singlefamilyhomes')),
+    region_type TEXT NOT NULL CHECK (region_type IN ('threezip', 'fivezip', 'city', 'county')),
+    region_name TEXT NOT NULL,
+    percentile TEXT NOT NULL CHECK (percentile IN ('bottom', 'middle', 'top')),
+    PRIMARY KEY (home_type, region_type, region_name, percentile)
+);
+
+CREATE TABLE zhvi_prices (
+    home_type TEXT NOT NULL,
+    region_type TEXT NOT NULL,
+    region_name TEXT NOT NULL,
+    percentile TEXT NOT NULL,
+    date DATE NOT NULL,
+    value FLOAT8 NOT NULL,
+    PRIMARY KEY (home_type, region_type, region_name, percentile, date),
+    FOREIGN KEY (home_type, region_type, region_name, percentile)
+        REFERENCES zhvi_metadata(home_type, region_type, region_name, percentile)
+);
@@ -0,0 +1,6 @@
+-- Regions span every state in the HUD crosswalk; COUNTY is the 5-digit FIPS
+ALTER TABLE regions ADD COLUMN state VARCHAR(2) NOT NULL DEFAULT '';
+ALTER TABLE regions ADD COLUMN county_fips VARCHAR(5) NOT NULL DEFAULT '';
+
+CREATE INDEX regions_state_idx ON regions (state);
+CREATE INDEX regions_county_fips_idx ON regions (county_fips);
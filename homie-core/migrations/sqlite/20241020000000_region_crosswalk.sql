@@ -0,0 +1,20 @@
+-- A zip spanning several counties keeps one row per county, weighted by HUD's address ratios
+CREATE TABLE regions_crosswalk (
+    city VARCHAR(50) NOT NULL,
+    zipcode VARCHAR(10) NOT NULL,
+    state VARCHAR(2) NOT NULL DEFAULT '',
+    county_fips VARCHAR(5) NOT NULL DEFAULT '',
+    res_ratio FLOAT8 NOT NULL DEFAULT 1,
+    bus_ratio FLOAT8 NOT NULL DEFAULT 1,
+    tot_ratio FLOAT8 NOT NULL DEFAULT 1,
+    PRIMARY KEY (zipcode, county_fips)
+);
+
+INSERT INTO regions_crosswalk (city, zipcode, state, county_fips)
+SELECT city, zipcode, state, county_fips FROM regions;
+
+DROP TABLE regions;
+ALTER TABLE regions_crosswalk RENAME TO regions;
+
+CREATE INDEX regions_state_idx ON regions (state);
+CREATE INDEX regions_county_fips_idx ON regions (county_fips);
@@ -0,0 +1,23 @@
+-- HPIs are keyed by region id (zip, county FIPS, state, CBSA) so same-named counties no
+-- longer collide. County rows were keyed by name only; re-import them to restore with FIPS.
+CREATE TABLE hpis_by_id (
+    region_type TEXT NOT NULL CHECK (region_type IN ('threezip', 'fivezip', 'city', 'county', 'state', 'msa', 'national')),
+    region_id VARCHAR(50) NOT NULL,
+    region_name TEXT NOT NULL,
+    state VARCHAR(2),
+    year INTEGER NOT NULL,
+    hpi FLOAT4,
+    annual_change FLOAT4,
+    hpi_1990_base FLOAT4,
+    hpi_2000_base FLOAT4,
+    PRIMARY KEY (region_type, region_id, year)
+);
+
+INSERT INTO hpis_by_id
+    (region_type, region_id, region_name, year, hpi, annual_change, hpi_1990_base, hpi_2000_base)
+SELECT region_type, region_name, region_name, year, hpi, annual_change, hpi_1990_base, hpi_2000_base
+FROM hpis
+WHERE region_type != 'county';
+
+DROP TABLE hpis;
+ALTER TABLE hpis_by_id RENAME TO hpis;
@@ -0,0 +1,14 @@
+-- Every Treasury constant maturity in the H.15 release
+CREATE TABLE tyields_by_term (
+    term TEXT NOT NULL CHECK (term IN ('onemonth', 'twomonth', 'threemonth', 'fourmonth', 'sixmonth', 'oneyear', 'twoyear', 'threeyear', 'fiveyear', 'sevenyear', 'tenyear', 'twentyyear', 'thirtyyear')),
+    date DATE NOT NULL,
+    yield_return FLOAT4,
+    PRIMARY KEY (date, term)
+);
+
+INSERT INTO tyields_by_term (term, date, yield_return)
+SELECT term, date, yield_return
+FROM tyields;
+
+DROP TABLE tyields;
+ALTER TABLE tyields_by_term RENAME TO tyields;
@@ -0,0 +1,8 @@
+-- Freddie Mac PMMS weekly fixed rates
+CREATE TABLE mortgage_rates (
+    term TEXT NOT NULL CHECK (term IN ('thirtyyear', 'fifteenyear')),
+    date DATE NOT NULL,
+    rate FLOAT4,
+    points FLOAT4,
+    PRIMARY KEY (date, term)
+);
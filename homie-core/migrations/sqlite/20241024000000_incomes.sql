@@ -0,0 +1,12 @@
+-- Census ACS median household income, table B19013
+CREATE TABLE incomes (
+    region_type TEXT NOT NULL CHECK (region_type IN ('threezip', 'fivezip', 'city', 'county', 'state', 'msa', 'national')),
+    region_id TEXT NOT NULL,
+    region_name TEXT NOT NULL,
+    year INTEGER NOT NULL,
+    median_income REAL,
+    margin_of_error REAL,
+    PRIMARY KEY (region_type, region_id, year)
+);
+
+CREATE INDEX incomes_region_name_idx ON incomes (region_type, region_name);
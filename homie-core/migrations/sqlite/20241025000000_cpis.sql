@@ -0,0 +1,5 @@
+-- BLS CPI-U, one row per month dated on its first day
+CREATE TABLE cpis (
+    date DATE NOT NULL PRIMARY KEY,
+    value REAL NOT NULL
+);
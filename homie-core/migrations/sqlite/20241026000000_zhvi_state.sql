@@ -0,0 +1,39 @@
+-- ZHVIs are keyed by state too so same-named cities and counties no longer collide. City and
+-- county rows were keyed by name only; re-import them to restore with their state.
+ALTER TABLE zhvi_prices RENAME TO zhvi_prices_by_name;
+ALTER TABLE zhvi_metadata RENAME TO zhvi_metadata_by_name;
+
+CREATE TABLE zhvi_metadata (
+    home_type TEXT NOT NULL CHECK (home_type IN ('allhomes', 'condocoops', 'singlefamilyhomes')),
+    region_type TEXT NOT NULL CHECK (region_type IN ('threezip', 'fivezip', 'city', 'county')),
+    region_name TEXT NOT NULL,
+    percentile TEXT NOT NULL CHECK (percentile IN ('bottom', 'middle', 'top')),
+    state TEXT NOT NULL DEFAULT '',
+    PRIMARY KEY (home_type, region_type, region_name, percentile, state)
+);
+
+CREATE TABLE zhvi_prices (
+    home_type TEXT NOT NULL,
+    region_type TEXT NOT NULL,
+    region_name TEXT NOT NULL,
+    percentile TEXT NOT NULL,
+    state TEXT NOT NULL DEFAULT '',
+    date DATE NOT NULL,
+    value FLOAT8 NOT NULL,
+    PRIMARY KEY (home_type, region_type, region_name, percentile, state, date),
+    FOREIGN KEY (home_type, region_type, region_name, percentile, state)
+        REFERENCES zhvi_metadata(home_type, region_type, region_name, percentile, state)
+);
+
+INSERT INTO zhvi_metadata (home_type, region_type, region_name, percentile)
+SELECT home_type, region_type, region_name, percentile
+FROM zhvi_metadata_by_name
+WHERE region_type NOT IN ('city', 'county');
+
+INSERT INTO zhvi_prices (home_type, region_type, region_name, percentile, date, value)
+SELECT home_type, region_type, region_name, percentile, date, value
+FROM zhvi_prices_by_name
+WHERE region_type NOT IN ('city', 'county');
+
+DROP TABLE zhvi_prices_by_name;
+DROP TABLE zhvi_metadata_by_name;
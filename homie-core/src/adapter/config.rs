@@ -1,60 +1,230 @@
 use std::env;
+use std::str::FromStr;
+use std::time::Duration;
 
+use crate::adapter::importer::ImportMode;
+use crate::domain::cpi::CpiConfig;
 use crate::domain::hpi::HpiConfig;
+use crate::domain::income::IncomeConfig;
+use crate::domain::mortgage_rate::MortgageRateConfig;
 use crate::domain::region::RegionConfig;
 use crate::domain::t_yield::TYieldConfig;
 use crate::domain::zhvi::ZhviConfig;
+use crate::error::DomainError;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum DatabaseType {
+    InMemory,
+    #[default]
+    Postgres,
+    Sqlite,
+}
+
+impl TryFrom<&str> for DatabaseType {
+    type Error = DomainError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        match s {
+            "memory" => Ok(DatabaseType::InMemory),
+            "postgres" => Ok(DatabaseType::Postgres),
+            "sqlite" => Ok(DatabaseType::Sqlite),
+            _ => Err(DomainError::Parse(
+                "Failed to parse DatabaseType".to_string(),
+            )),
+        }
+    }
+}
+
+/// Reads the env var `name` as one of `T`'s labels, in any case
+fn read_label<T>(name: &str) -> Result<Option<T>, DomainError>
+where
+    T: for<'a> TryFrom<&'a str, Error = DomainError>,
+{
+    env::var(name)
+        .ok()
+        .map(|value| {
+            T::try_from(value.to_ascii_lowercase().as_str())
+                .map_err(|_| DomainError::Parse(format!("Unrecognized {} '{}'", name, value)))
+        })
+        .transpose()
+}
+
+/// Reads the env var `name` as a `T`
+fn read_parsed<T: FromStr>(name: &str) -> Result<Option<T>, DomainError> {
+    env::var(name)
+        .ok()
+        .map(|value| {
+            value
+                .parse()
+                .map_err(|_| DomainError::Parse(format!("Invalid {} '{}'", name, value)))
+        })
+        .transpose()
+}
+
+const DEFAULT_CACHE_MAX_ENTRIES: usize = 1024;
 
 pub struct Config {
     use_zillow_api: bool,
+    zillow_api_url: Option<String>,
+    zillow_api_key: Option<String>,
+    database_type: DatabaseType,
+    run_migrations: bool,
+    import_mode: ImportMode,
+    cache_ttl: Option<Duration>,
+    cache_max_entries: usize,
+    cpi_config: CpiConfig,
     hpi_config: HpiConfig,
+    income_config: IncomeConfig,
+    mortgage_rate_config: MortgageRateConfig,
     region_config: RegionConfig,
     t_yield_config: TYieldConfig,
     zhvi_config: ZhviConfig,
 }
 
 impl Config {
-    pub fn load_config() -> Config {
-        let ten_year_yield_path = env::var("TEN_YEAR_YIELD_PATH").ok();
-        let t_yield_config = TYieldConfig::new(ten_year_yield_path);
+    /// Reads the config from the environment. An unrecognized `DATABASE_TYPE`
+    /// or `IMPORT_MODE`, or a non-numeric `CACHE_TTL_SECS` or
+    /// `CACHE_MAX_ENTRIES`, is an error rather than the default.
+    pub fn load_config() -> Result<Config, DomainError> {
+        let database_type: DatabaseType = read_label("DATABASE_TYPE")?.unwrap_or_default();
+
+        let run_migrations = env::var("RUN_MIGRATIONS").is_ok_and(|value| value == "true");
+
+        let import_mode: ImportMode = read_label("IMPORT_MODE")?.unwrap_or_default();
+
+        let cache_ttl = read_parsed("CACHE_TTL_SECS")?.map(Duration::from_secs);
+        let cache_max_entries =
+            read_parsed("CACHE_MAX_ENTRIES")?.unwrap_or(DEFAULT_CACHE_MAX_ENTRIES);
+
+        let use_zillow_api = env::var("USE_ZILLOW_API").is_ok_and(|value| value == "true");
+        // No default: the service must return the `bundle` of ZHVI records
+        // `HttpClient` reads
+        let zillow_api_url = env::var("ZILLOW_API_URL").ok();
+        if use_zillow_api && zillow_api_url.is_none() {
+            return Err(DomainError::Parse(
+                "USE_ZILLOW_API requires ZILLOW_API_URL".to_string(),
+            ));
+        }
+        let zillow_api_key = env::var("ZILLOW_API_KEY").ok();
+
+        // TEN_YEAR_YIELD_PATH predates the other maturities
+        let t_yield_path = env::var("TREASURY_YIELDS_PATH")
+            .or_else(|_| env::var("TEN_YEAR_YIELD_PATH"))
+            .ok();
+        let t_yield_config = TYieldConfig::new(t_yield_path);
+
+        let mortgage_rates_path = env::var("MORTGAGE_RATES_PATH").ok();
+        let mortgage_rate_config = MortgageRateConfig::new(mortgage_rates_path);
+
+        let cpi_path = env::var("CPI_PATH").ok();
+        // Defaults to the U.S. city average, all items, not seasonally adjusted
+        let cpi_series_id = env::var("CPI_SERIES_ID").ok();
+        let cpi_config = CpiConfig::new(cpi_path, cpi_series_id);
+
+        let acs_income_dir = env::var("ACS_INCOME_DIR").ok();
+        let income_config = IncomeConfig::new(acs_income_dir);
 
         let three_zip_hpis_path = env::var("THREE_ZIP_HPIS_PATH").ok();
         let five_zip_hpis_path = env::var("FIVE_ZIP_HPIS_PATH").ok();
         let county_hpis_path = env::var("COUNTY_HPIS_PATH").ok();
-        let hpi_config = HpiConfig::new(three_zip_hpis_path, five_zip_hpis_path, county_hpis_path);
+        let state_hpis_path = env::var("STATE_HPIS_PATH").ok();
+        let msa_hpis_path = env::var("MSA_HPIS_PATH").ok();
+        let national_hpis_path = env::var("NATIONAL_HPIS_PATH").ok();
+        let hpi_config = HpiConfig::new(
+            three_zip_hpis_path,
+            five_zip_hpis_path,
+            county_hpis_path,
+            state_hpis_path,
+            msa_hpis_path,
+            national_hpis_path,
+        );
 
         let cities_path = env::var("CITIES_PATH").ok();
         let zip_county_path = env::var("ZIP_COUNTY_PATH").ok();
-        let region_config = RegionConfig::new(cities_path, zip_county_path);
-
-        let bot_city_all_homes_path = env::var("BOT_CITY_ALL_HOMES_PATH").ok();
-        let mid_zip_all_homes_path = env::var("MID_ZIP_ALL_HOMES_PATH").ok();
-        let mid_city_all_homes_path = env::var("MID_CITY_ALL_HOMES_PATH").ok();
-        let mid_county_all_homes_path = env::var("MID_COUNTY_ALL_HOMES_PATH").ok();
-        let zhvi_config = ZhviConfig::new(
-            bot_city_all_homes_path,
-            mid_zip_all_homes_path,
-            mid_city_all_homes_path,
-            mid_county_all_homes_path,
-        );
+        // Comma separated USPS codes, e.g. "CA,NV", or "all"
+        let region_states = env::var("REGION_STATES")
+            .ok()
+            .filter(|states| !states.eq_ignore_ascii_case("all"))
+            .map(|states| {
+                states
+                    .split(',')
+                    .map(|state| state.trim().to_ascii_uppercase())
+                    .filter(|state| !state.is_empty())
+                    .collect()
+            });
+        let region_config = RegionConfig::new(cities_path, zip_county_path, region_states);
 
-        Config {
+        let zhvi_dir = env::var("ZILLOW_ZHVI_DIR").ok();
+        let zhvi_config = ZhviConfig::new(zhvi_dir);
+
+        Ok(Config {
+            cpi_config,
             hpi_config,
-            use_zillow_api: false,
+            income_config,
+            use_zillow_api,
+            zillow_api_url,
+            zillow_api_key,
+            database_type,
+            run_migrations,
+            import_mode,
+            cache_ttl,
+            cache_max_entries,
+            mortgage_rate_config,
             region_config,
             t_yield_config,
             zhvi_config,
-        }
+        })
     }
 
     pub fn is_zillow_api_enabled(&self) -> bool {
         self.use_zillow_api
     }
 
+    /// Set whenever the Zillow API is enabled
+    pub fn zillow_api_url(&self) -> &str {
+        self.zillow_api_url.as_deref().unwrap_or_default()
+    }
+
+    pub fn zillow_api_key(&self) -> Option<&str> {
+        self.zillow_api_key.as_deref()
+    }
+
+    pub fn database_type(&self) -> &DatabaseType {
+        &self.database_type
+    }
+
+    pub fn is_run_migrations_enabled(&self) -> bool {
+        self.run_migrations
+    }
+
+    pub fn import_mode(&self) -> &ImportMode {
+        &self.import_mode
+    }
+
+    pub fn cache_ttl(&self) -> Option<Duration> {
+        self.cache_ttl
+    }
+
+    pub fn cache_max_entries(&self) -> usize {
+        self.cache_max_entries
+    }
+
+    pub(crate) fn cpi_config(&self) -> CpiConfig {
+        self.cpi_config.clone()
+    }
+
     pub(crate) fn hpi_config(&self) -> HpiConfig {
         self.hpi_config.clone()
     }
 
+    pub(crate) fn income_config(&self) -> IncomeConfig {
+        self.income_config.clone()
+    }
+
+    pub(crate) fn mortgage_rate_config(&self) -> MortgageRateConfig {
+        self.mortgage_rate_config.clone()
+    }
+
     pub(crate) fn region_config(&self) -> RegionConfig {
         self.region_config.clone()
     }
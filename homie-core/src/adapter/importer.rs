@@ -1,12 +1,168 @@
+use std::collections::HashSet;
+
 use crate::adapter::config::Config;
+use crate::domain::cpi::{read_bls_cpis, CpiConfig, CpiData};
 use crate::domain::hpi::{read_fhfa_hpis, HpiConfig, HpiData};
+use crate::domain::income::{read_census_incomes, IncomeConfig, IncomeData};
+use crate::domain::mortgage_rate::{read_freddie_mac_rates, MortgageRateConfig, MortgageRateData};
 use crate::domain::region::{read_huduser_regions, RegionConfig, RegionData};
 use crate::domain::t_yield::{read_fed_yields, TYieldConfig, TYieldData};
-use crate::domain::zhvi::{read_zillow_zhvis, ZhviConfig, ZhviData};
+use crate::domain::zhvi::{
+    read_zhvi_manifest, read_zillow_zhvis, ZhviConfig, ZhviData, ZhviManifest,
+};
 use crate::error::DomainError;
 
+/// How rows that fail validation are handled
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum ImportMode {
+    /// Skip invalid rows and import the rest
+    #[default]
+    Lenient,
+    /// Refuse to import a dataset with any invalid row
+    Strict,
+}
+
+impl TryFrom<&str> for ImportMode {
+    type Error = DomainError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        match s {
+            "lenient" => Ok(ImportMode::Lenient),
+            "strict" => Ok(ImportMode::Strict),
+            _ => Err(DomainError::Parse("Failed to parse ImportMode".to_string())),
+        }
+    }
+}
+
+/// A value that could not be imported, located by file, line and column
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImportError {
+    file: String,
+    line: u64,
+    column: Option<String>,
+    message: String,
+}
+
+impl ImportError {
+    pub fn file(&self) -> &str {
+        &self.file
+    }
+
+    pub fn line(&self) -> u64 {
+        self.line
+    }
+
+    pub fn column(&self) -> Option<&str> {
+        self.column.as_deref()
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl std::fmt::Display for ImportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.column {
+            // Line 0 marks a problem with the file itself
+            None if self.line == 0 => write!(f, "{}: {}", self.file, self.message),
+            Some(column) => write!(
+                f,
+                "{}:{}: column '{}': {}",
+                self.file, self.line, column, self.message
+            ),
+            None => write!(f, "{}:{}: {}", self.file, self.line, self.message),
+        }
+    }
+}
+
+/// Rows read from a dataset, and the rows rejected with the reason why
+#[derive(Clone, Debug, Default)]
+pub struct ImportReport {
+    rows_read: usize,
+    errors: Vec<ImportError>,
+}
+
+impl ImportReport {
+    pub fn rows_read(&self) -> usize {
+        self.rows_read
+    }
+
+    pub fn rows_rejected(&self) -> usize {
+        self.errors
+            .iter()
+            .filter(|error| error.line != 0)
+            .map(|error| (&error.file, error.line))
+            .collect::<HashSet<_>>()
+            .len()
+    }
+
+    pub fn errors(&self) -> &[ImportError] {
+        &self.errors
+    }
+
+    pub fn is_clean(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    pub fn merge(&mut self, other: ImportReport) {
+        self.rows_read += other.rows_read;
+        self.errors.extend(other.errors);
+    }
+
+    /// One line of counts followed by up to `max_errors` errors
+    pub fn summary(&self, max_errors: usize) -> String {
+        let mut summary = format!(
+            "{} rows read, {} rejected, {} errors",
+            self.rows_read,
+            self.rows_rejected(),
+            self.errors.len()
+        );
+        for error in self.errors.iter().take(max_errors) {
+            summary.push_str(&format!("\n\t{}", error));
+        }
+        if self.errors.len() > max_errors {
+            summary.push_str(&format!(
+                "\n\t... and {} more",
+                self.errors.len() - max_errors
+            ));
+        }
+        summary
+    }
+
+    pub(crate) fn add_row(&mut self) {
+        self.rows_read += 1;
+    }
+
+    pub(crate) fn add_error(
+        &mut self,
+        file: &str,
+        line: u64,
+        column: Option<&str>,
+        message: String,
+    ) {
+        let error = ImportError {
+            file: file.to_string(),
+            line,
+            column: column.map(str::to_string),
+            message,
+        };
+        // Unreadable rows are found before field errors, keep each file in line order
+        let at = self
+            .errors
+            .iter()
+            .rposition(|e| e.file != error.file || e.line <= error.line)
+            .map_or(0, |i| i + 1);
+        self.errors.insert(at, error);
+    }
+}
+
 pub struct Importer {
+    mode: ImportMode,
+    cpi_config: CpiConfig,
     hpi_config: HpiConfig,
+    income_config: IncomeConfig,
+    mortgage_rate_config: MortgageRateConfig,
     region_config: RegionConfig,
     t_yield_config: TYieldConfig,
     zhvi_config: ZhviConfig,
@@ -14,41 +170,95 @@ pub struct Importer {
 
 impl Importer {
     pub fn new(config: &'static Config) -> Self {
+        let cpi_config = config.cpi_config();
         let hpi_config = config.hpi_config();
+        let income_config = config.income_config();
+        let mortgage_rate_config = config.mortgage_rate_config();
         let region_config = config.region_config();
         let t_yield_config = config.t_yield_config();
         let zhvi_config = config.zhvi_config();
 
         Importer {
+            mode: config.import_mode().clone(),
             t_yield_config,
             region_config,
+            cpi_config,
             hpi_config,
+            income_config,
+            mortgage_rate_config,
             zhvi_config,
         }
     }
 }
 
 impl Importer {
-    pub fn read_fhfa_hpis(&self) -> Result<HpiData, DomainError> {
-        read_fhfa_hpis(self.hpi_config())
+    pub fn mode(&self) -> &ImportMode {
+        &self.mode
+    }
+
+    pub fn read_bls_cpis(&self) -> Result<(CpiData, ImportReport), DomainError> {
+        let mut report = ImportReport::default();
+        let cpi_data = read_bls_cpis(self.cpi_config(), &mut report)?;
+        Ok((cpi_data, report))
+    }
+
+    pub fn read_fhfa_hpis(&self) -> Result<(HpiData, ImportReport), DomainError> {
+        let mut report = ImportReport::default();
+        let hpi_data = read_fhfa_hpis(self.hpi_config(), &mut report)?;
+        Ok((hpi_data, report))
+    }
+
+    pub fn read_census_incomes(&self) -> Result<(IncomeData, ImportReport), DomainError> {
+        let mut report = ImportReport::default();
+        let income_data = read_census_incomes(self.income_config(), &mut report)?;
+        Ok((income_data, report))
+    }
+
+    pub fn read_fed_yields(&self) -> Result<(TYieldData, ImportReport), DomainError> {
+        let mut report = ImportReport::default();
+        let t_yield_data = read_fed_yields(self.t_yield_config(), &mut report)?;
+        Ok((t_yield_data, report))
     }
 
-    pub fn read_fed_yields(&self) -> Result<TYieldData, DomainError> {
-        read_fed_yields(self.t_yield_config())
+    pub fn read_freddie_mac_rates(&self) -> Result<(MortgageRateData, ImportReport), DomainError> {
+        let mut report = ImportReport::default();
+        let mortgage_rate_data = read_freddie_mac_rates(self.mortgage_rate_config(), &mut report)?;
+        Ok((mortgage_rate_data, report))
     }
 
-    pub fn read_huduser_regions(&self) -> Result<RegionData, DomainError> {
-        read_huduser_regions(self.region_config())
+    pub fn read_huduser_regions(&self) -> Result<(RegionData, ImportReport), DomainError> {
+        let mut report = ImportReport::default();
+        let region_data = read_huduser_regions(self.region_config(), &mut report)?;
+        Ok((region_data, report))
     }
 
-    pub fn read_zillow_zhvis(&self) -> Result<ZhviData, DomainError> {
-        read_zillow_zhvis(self.zhvi_config())
+    /// The Zillow exports that `read_zillow_zhvis` would import
+    pub fn zhvi_manifest(&self) -> Result<ZhviManifest, DomainError> {
+        read_zhvi_manifest(self.zhvi_config())
+    }
+
+    pub fn read_zillow_zhvis(&self) -> Result<(ZhviData, ImportReport), DomainError> {
+        let mut report = ImportReport::default();
+        let zhvi_data = read_zillow_zhvis(self.zhvi_config(), &mut report)?;
+        Ok((zhvi_data, report))
+    }
+
+    fn cpi_config(&self) -> &CpiConfig {
+        &self.cpi_config
     }
 
     fn hpi_config(&self) -> &HpiConfig {
         &self.hpi_config
     }
 
+    fn income_config(&self) -> &IncomeConfig {
+        &self.income_config
+    }
+
+    fn mortgage_rate_config(&self) -> &MortgageRateConfig {
+        &self.mortgage_rate_config
+    }
+
     fn region_config(&self) -> &RegionConfig {
         &self.region_config
     }
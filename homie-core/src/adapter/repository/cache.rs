@@ -0,0 +1,465 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use chrono::NaiveDate;
+
+use crate::adapter::repository::Persist;
+use crate::domain::common::RegionType;
+use crate::domain::cpi::{Cpi, CpiId, CpiPersist, CpiQuery, Cpis};
+use crate::domain::hpi::{Hpi, HpiId, HpiPersist, HpiQuery, Hpis};
+use crate::domain::income::{Income, IncomeId, IncomePersist, IncomeQuery, Incomes};
+use crate::domain::mortgage_rate::{
+    MortgageRate, MortgageRateId, MortgageRatePersist, MortgageRateQuery, MortgageRates,
+    MortgageTerm,
+};
+use crate::domain::region::{Region, RegionPersist, RegionQuery, Regions, Zipcode};
+use crate::domain::t_yield::{TYield, TYieldId, TYieldPersist, TYieldQuery, TYields, Term};
+use crate::domain::zhvi::{Zhvi, ZhviId, ZhviPersist, ZhviQuery, Zhvis};
+use crate::error::DomainError;
+
+/// Memoizes query results for a fixed time, holding at most `max_entries`
+struct QueryCache<K, V> {
+    entries: Mutex<HashMap<K, (Instant, V)>>,
+    ttl: Duration,
+    max_entries: usize,
+}
+
+impl<K: Clone + Eq + Hash, V: Clone> QueryCache<K, V> {
+    fn new(ttl: Duration, max_entries: usize) -> Self {
+        QueryCache {
+            entries: Mutex::new(HashMap::new()),
+            ttl,
+            max_entries,
+        }
+    }
+
+    // A poisoned cache only holds stale reads, so keep using it
+    fn entries(&self) -> MutexGuard<'_, HashMap<K, (Instant, V)>> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn get(&self, key: &K) -> Option<V> {
+        let mut entries = self.entries();
+        match entries.get(key) {
+            Some((inserted, value)) if inserted.elapsed() < self.ttl => Some(value.clone()),
+            Some(_) => {
+                entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    fn insert(&self, key: K, value: V) {
+        if self.max_entries == 0 {
+            return;
+        }
+        let mut entries = self.entries();
+        if entries.len() >= self.max_entries && !entries.contains_key(&key) {
+            entries.retain(|_, (inserted, _)| inserted.elapsed() < self.ttl);
+        }
+        if entries.len() >= self.max_entries && !entries.contains_key(&key) {
+            let oldest = entries
+                .iter()
+                .min_by_key(|(_, (inserted, _))| *inserted)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                entries.remove(&oldest);
+            }
+        }
+        entries.insert(key, (Instant::now(), value));
+    }
+
+    fn invalidate(&self, predicate: impl Fn(&K) -> bool) {
+        self.entries().retain(|key, _| !predicate(key));
+    }
+}
+
+/// Read-through cache around another `Persist`.
+/// Query results are memoized per domain, and writes made through
+/// the cache drop the entries they could affect.
+pub struct CachedPersist<P: Persist + ?Sized> {
+    inner: Box<P>,
+    cpis: QueryCache<CpiQuery, Cpis>,
+    hpis: QueryCache<HpiQuery, Hpis>,
+    incomes: QueryCache<IncomeQuery, Incomes>,
+    mortgage_rates: QueryCache<MortgageRateQuery, MortgageRates>,
+    regions: QueryCache<RegionQuery, Regions>,
+    t_yields: QueryCache<TYieldQuery, TYields>,
+    zhvis: QueryCache<ZhviQuery, Zhvis>,
+}
+
+impl<P: Persist + ?Sized> CachedPersist<P> {
+    pub fn new(inner: Box<P>, ttl: Duration, max_entries: usize) -> Self {
+        CachedPersist {
+            inner,
+            cpis: QueryCache::new(ttl, max_entries),
+            hpis: QueryCache::new(ttl, max_entries),
+            incomes: QueryCache::new(ttl, max_entries),
+            mortgage_rates: QueryCache::new(ttl, max_entries),
+            regions: QueryCache::new(ttl, max_entries),
+            t_yields: QueryCache::new(ttl, max_entries),
+            zhvis: QueryCache::new(ttl, max_entries),
+        }
+    }
+
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+
+    fn invalidate_hpis(&self, region_type: &RegionType, region_id: &str) {
+        self.hpis.invalidate(|query| {
+            query.region_type() == region_type && query.region_id() == region_id
+        });
+    }
+
+    // Queries may select by name, which a deleted id doesn't carry
+    fn invalidate_incomes(&self, region_type: &RegionType) {
+        self.incomes
+            .invalidate(|query| query.region_type() == region_type);
+    }
+
+    // The previous city, state and county of a zipcode are unknown, so only queries
+    // selecting by zipcode alone can be kept
+    fn invalidate_regions(&self, zipcode: &str) {
+        self.regions.invalidate(|query| {
+            query.zipcodes().is_empty()
+                || !query.cities().is_empty()
+                || !query.county_fips().is_empty()
+                || query.zipcodes().iter().any(|z| z == zipcode)
+        });
+    }
+
+    fn invalidate_zhvis(&self, id: ZhviId) {
+        self.zhvis.invalidate(|query| {
+            query.region_name() == id.0
+                && query.region_type().to_string() == id.1
+                && query.home_type().to_string() == id.2
+                && query.percentile().to_string() == id.3
+                && query.includes_state(id.4)
+        });
+    }
+
+    fn invalidate_zhvi(&self, zhvi: &Zhvi) {
+        self.invalidate_zhvis((
+            zhvi.region_name(),
+            &zhvi.region_type().to_string(),
+            &zhvi.home_type().to_string(),
+            &zhvi.percentile().to_string(),
+            zhvi.state(),
+        ));
+    }
+}
+
+impl<P: Persist + ?Sized> Persist for CachedPersist<P> {}
+
+#[async_trait]
+impl<P: Persist + ?Sized> CpiPersist for CachedPersist<P> {
+    async fn create_cpi(&self, cpi: &Cpi) -> Result<CpiId, DomainError> {
+        let result = self.inner.create_cpi(cpi).await;
+        self.cpis.invalidate(|query| query.contains(cpi.date()));
+        result
+    }
+
+    async fn create_cpis(&self, cpis: &[Cpi]) -> Result<(), DomainError> {
+        let result = self.inner.create_cpis(cpis).await;
+        self.cpis
+            .invalidate(|query| cpis.iter().any(|cpi| query.contains(cpi.date())));
+        result
+    }
+
+    async fn read_cpi_by_id(&self, id: &NaiveDate) -> Result<Cpi, DomainError> {
+        self.inner.read_cpi_by_id(id).await
+    }
+
+    async fn update_cpi(&self, cpi: &Cpi) -> Result<(), DomainError> {
+        let result = self.inner.update_cpi(cpi).await;
+        self.cpis.invalidate(|query| query.contains(cpi.date()));
+        result
+    }
+
+    async fn delete_cpi_by_id(&self, id: &NaiveDate) -> Result<(), DomainError> {
+        let result = self.inner.delete_cpi_by_id(id).await;
+        self.cpis.invalidate(|query| query.contains(id));
+        result
+    }
+
+    async fn read_cpis_by_query(&self, query: &CpiQuery) -> Result<Cpis, DomainError> {
+        if let Some(cpis) = self.cpis.get(query) {
+            return Ok(cpis);
+        }
+        let cpis = self.inner.read_cpis_by_query(query).await?;
+        self.cpis.insert(query.clone(), cpis.clone());
+        Ok(cpis)
+    }
+}
+
+#[async_trait]
+impl<P: Persist + ?Sized> HpiPersist for CachedPersist<P> {
+    async fn create_hpi(&self, hpi: &Hpi) -> Result<HpiId, DomainError> {
+        let result = self.inner.create_hpi(hpi).await;
+        self.invalidate_hpis(hpi.region_type(), hpi.region_id());
+        result
+    }
+
+    async fn create_hpis(&self, hpis: &[Hpi]) -> Result<(), DomainError> {
+        let result = self.inner.create_hpis(hpis).await;
+        for hpi in hpis {
+            self.invalidate_hpis(hpi.region_type(), hpi.region_id());
+        }
+        result
+    }
+
+    async fn read_hpi_by_id(&self, id: (&RegionType, &str, i32)) -> Result<Hpi, DomainError> {
+        self.inner.read_hpi_by_id(id).await
+    }
+
+    async fn update_hpi(&self, hpi: &Hpi) -> Result<(), DomainError> {
+        let result = self.inner.update_hpi(hpi).await;
+        self.invalidate_hpis(hpi.region_type(), hpi.region_id());
+        result
+    }
+
+    async fn delete_hpi_by_id(&self, id: (&RegionType, &str, i32)) -> Result<(), DomainError> {
+        let result = self.inner.delete_hpi_by_id(id).await;
+        self.invalidate_hpis(id.0, id.1);
+        result
+    }
+
+    async fn read_hpi_by_query(&self, query: &HpiQuery) -> Result<Hpis, DomainError> {
+        if let Some(hpis) = self.hpis.get(query) {
+            return Ok(hpis);
+        }
+        let hpis = self.inner.read_hpi_by_query(query).await?;
+        self.hpis.insert(query.clone(), hpis.clone());
+        Ok(hpis)
+    }
+}
+
+#[async_trait]
+impl<P: Persist + ?Sized> IncomePersist for CachedPersist<P> {
+    async fn create_income(&self, income: &Income) -> Result<IncomeId, DomainError> {
+        let result = self.inner.create_income(income).await;
+        self.invalidate_incomes(income.region_type());
+        result
+    }
+
+    async fn create_incomes(&self, incomes: &[Income]) -> Result<(), DomainError> {
+        let result = self.inner.create_incomes(incomes).await;
+        for income in incomes {
+            self.invalidate_incomes(income.region_type());
+        }
+        result
+    }
+
+    async fn read_income_by_id(&self, id: (&RegionType, &str, i32)) -> Result<Income, DomainError> {
+        self.inner.read_income_by_id(id).await
+    }
+
+    async fn update_income(&self, income: &Income) -> Result<(), DomainError> {
+        let result = self.inner.update_income(income).await;
+        self.invalidate_incomes(income.region_type());
+        result
+    }
+
+    async fn delete_income_by_id(&self, id: (&RegionType, &str, i32)) -> Result<(), DomainError> {
+        let result = self.inner.delete_income_by_id(id).await;
+        self.invalidate_incomes(id.0);
+        result
+    }
+
+    async fn read_incomes_by_query(&self, query: &IncomeQuery) -> Result<Incomes, DomainError> {
+        if let Some(incomes) = self.incomes.get(query) {
+            return Ok(incomes);
+        }
+        let incomes = self.inner.read_incomes_by_query(query).await?;
+        self.incomes.insert(query.clone(), incomes.clone());
+        Ok(incomes)
+    }
+}
+
+#[async_trait]
+impl<P: Persist + ?Sized> MortgageRatePersist for CachedPersist<P> {
+    async fn create_mortgage_rate(
+        &self,
+        mortgage_rate: &MortgageRate,
+    ) -> Result<MortgageRateId, DomainError> {
+        let result = self.inner.create_mortgage_rate(mortgage_rate).await;
+        self.mortgage_rates
+            .invalidate(|query| query.contains(mortgage_rate.term(), mortgage_rate.date()));
+        result
+    }
+
+    async fn create_mortgage_rates(
+        &self,
+        mortgage_rates: &[MortgageRate],
+    ) -> Result<(), DomainError> {
+        let result = self.inner.create_mortgage_rates(mortgage_rates).await;
+        self.mortgage_rates.invalidate(|query| {
+            mortgage_rates
+                .iter()
+                .any(|mortgage_rate| query.contains(mortgage_rate.term(), mortgage_rate.date()))
+        });
+        result
+    }
+
+    async fn read_mortgage_rate_by_id(
+        &self,
+        id: (&MortgageTerm, &NaiveDate),
+    ) -> Result<MortgageRate, DomainError> {
+        self.inner.read_mortgage_rate_by_id(id).await
+    }
+
+    async fn update_mortgage_rate(&self, mortgage_rate: &MortgageRate) -> Result<(), DomainError> {
+        let result = self.inner.update_mortgage_rate(mortgage_rate).await;
+        self.mortgage_rates
+            .invalidate(|query| query.contains(mortgage_rate.term(), mortgage_rate.date()));
+        result
+    }
+
+    async fn delete_mortgage_rate_by_id(
+        &self,
+        id: (&MortgageTerm, &NaiveDate),
+    ) -> Result<(), DomainError> {
+        let result = self.inner.delete_mortgage_rate_by_id(id).await;
+        self.mortgage_rates
+            .invalidate(|query| query.contains(id.0, id.1));
+        result
+    }
+
+    async fn read_mortgage_rates_by_query(
+        &self,
+        query: &MortgageRateQuery,
+    ) -> Result<MortgageRates, DomainError> {
+        if let Some(mortgage_rates) = self.mortgage_rates.get(query) {
+            return Ok(mortgage_rates);
+        }
+        let mortgage_rates = self.inner.read_mortgage_rates_by_query(query).await?;
+        self.mortgage_rates
+            .insert(query.clone(), mortgage_rates.clone());
+        Ok(mortgage_rates)
+    }
+}
+
+#[async_trait]
+impl<P: Persist + ?Sized> RegionPersist for CachedPersist<P> {
+    async fn create_region(&self, region: &Region) -> Result<Zipcode, DomainError> {
+        let result = self.inner.create_region(region).await;
+        self.invalidate_regions(region.zipcode());
+        result
+    }
+
+    async fn read_region_by_id(&self, id: &str) -> Result<Region, DomainError> {
+        self.inner.read_region_by_id(id).await
+    }
+
+    async fn read_regions_by_city(&self, id: &str) -> Result<Regions, DomainError> {
+        self.inner.read_regions_by_city(id).await
+    }
+
+    async fn read_regions_by_query(&self, query: &RegionQuery) -> Result<Regions, DomainError> {
+        if let Some(regions) = self.regions.get(query) {
+            return Ok(regions);
+        }
+        let regions = self.inner.read_regions_by_query(query).await?;
+        self.regions.insert(query.clone(), regions.clone());
+        Ok(regions)
+    }
+
+    async fn delete_region_by_id(&self, id: &str) -> Result<Zipcode, DomainError> {
+        let result = self.inner.delete_region_by_id(id).await;
+        self.invalidate_regions(id);
+        result
+    }
+}
+
+#[async_trait]
+impl<P: Persist + ?Sized> TYieldPersist for CachedPersist<P> {
+    async fn create_t_yield(&self, t_yield: &TYield) -> Result<TYieldId, DomainError> {
+        let result = self.inner.create_t_yield(t_yield).await;
+        self.t_yields
+            .invalidate(|query| query.contains(t_yield.term(), t_yield.date()));
+        result
+    }
+
+    async fn create_t_yields(&self, t_yields: &[TYield]) -> Result<(), DomainError> {
+        let result = self.inner.create_t_yields(t_yields).await;
+        self.t_yields.invalidate(|query| {
+            t_yields
+                .iter()
+                .any(|t_yield| query.contains(t_yield.term(), t_yield.date()))
+        });
+        result
+    }
+
+    async fn read_t_yield_by_id(&self, id: (&Term, &NaiveDate)) -> Result<TYield, DomainError> {
+        self.inner.read_t_yield_by_id(id).await
+    }
+
+    async fn update_t_yield(&self, t_yield: &TYield) -> Result<(), DomainError> {
+        let result = self.inner.update_t_yield(t_yield).await;
+        self.t_yields
+            .invalidate(|query| query.contains(t_yield.term(), t_yield.date()));
+        result
+    }
+
+    async fn delete_t_yield_by_id(&self, id: (&Term, &NaiveDate)) -> Result<(), DomainError> {
+        let result = self.inner.delete_t_yield_by_id(id).await;
+        self.t_yields.invalidate(|query| query.contains(id.0, id.1));
+        result
+    }
+
+    async fn read_t_yields_by_query(&self, query: &TYieldQuery) -> Result<TYields, DomainError> {
+        if let Some(t_yields) = self.t_yields.get(query) {
+            return Ok(t_yields);
+        }
+        let t_yields = self.inner.read_t_yields_by_query(query).await?;
+        self.t_yields.insert(query.clone(), t_yields.clone());
+        Ok(t_yields)
+    }
+}
+
+#[async_trait]
+impl<P: Persist + ?Sized> ZhviPersist for CachedPersist<P> {
+    async fn create_zhvi(&self, zhvi: &Zhvi) -> Result<(), DomainError> {
+        let result = self.inner.create_zhvi(zhvi).await;
+        self.invalidate_zhvi(zhvi);
+        result
+    }
+
+    async fn create_zhvis(&self, zhvis: &[Zhvi]) -> Result<(), DomainError> {
+        let result = self.inner.create_zhvis(zhvis).await;
+        for zhvi in zhvis {
+            self.invalidate_zhvi(zhvi);
+        }
+        result
+    }
+
+    async fn read_zhvi_by_id(&self, id: ZhviId<'_>) -> Result<Zhvi, DomainError> {
+        self.inner.read_zhvi_by_id(id).await
+    }
+
+    async fn update_zhvi(&self, zhvi: &Zhvi) -> Result<(), DomainError> {
+        let result = self.inner.update_zhvi(zhvi).await;
+        self.invalidate_zhvi(zhvi);
+        result
+    }
+
+    async fn delete_zhvi_by_id(&self, id: ZhviId<'_>) -> Result<(), DomainError> {
+        let result = self.inner.delete_zhvi_by_id(id).await;
+        self.invalidate_zhvis(id);
+        result
+    }
+
+    async fn read_zhvi_by_query(&self, query: &ZhviQuery) -> Result<Zhvis, DomainError> {
+        if let Some(zhvis) = self.zhvis.get(query) {
+            return Ok(zhvis);
+        }
+        let zhvis = self.inner.read_zhvi_by_query(query).await?;
+        self.zhvis.insert(query.clone(), zhvis.clone());
+        Ok(zhvis)
+    }
+}
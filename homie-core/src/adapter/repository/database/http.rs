@@ -1,140 +1,409 @@
+use std::collections::BTreeMap;
+
 use async_trait::async_trait;
 use chrono::NaiveDate;
+use serde::Deserialize;
 
+use crate::adapter::config::Config;
 use crate::adapter::repository::Persist;
-use crate::domain::hpi::{Hpi, HpiPersist, HpiQuery, Hpis};
+use crate::domain::common::RegionType;
+use crate::domain::cpi::{Cpi, CpiId, CpiPersist, CpiQuery, Cpis};
+use crate::domain::hpi::{Hpi, HpiId, HpiPersist, HpiQuery, Hpis};
+use crate::domain::income::{Income, IncomeId, IncomePersist, IncomeQuery, Incomes};
+use crate::domain::mortgage_rate::{
+    MortgageRate, MortgageRateId, MortgageRatePersist, MortgageRateQuery, MortgageRates,
+    MortgageTerm,
+};
 use crate::domain::region::{Region, RegionPersist, RegionQuery, Regions, Zipcode};
-use crate::domain::t_yield::{TYield, TYieldPersist, TYieldQuery, TYields};
-use crate::domain::zhvi::{Zhvi, ZhviPersist, ZhviQuery, Zhvis};
+use crate::domain::t_yield::{TYield, TYieldId, TYieldPersist, TYieldQuery, TYields, Term};
+use crate::domain::zhvi::{
+    HomeType, Percentile, Zhvi, ZhviId, ZhviPersist, ZhviPrice, ZhviPrices, ZhviQuery, Zhvis,
+};
 use crate::error::DomainError;
 
-pub struct HttpClient;
+/// Sends GET requests and returns the response body.
+/// Swappable so tests can serve canned responses.
+#[async_trait]
+pub trait HttpTransport: Send + Sync {
+    async fn get(&self, url: &str, params: &[(&str, String)]) -> Result<String, DomainError>;
+}
+
+#[derive(Default)]
+pub struct ReqwestTransport {
+    client: reqwest::Client,
+}
 
-impl HttpClient {
+impl ReqwestTransport {
     pub fn new() -> Self {
-        HttpClient {}
+        ReqwestTransport::default()
     }
 }
 
-impl Default for HttpClient {
-    fn default() -> Self {
-        HttpClient::new()
+#[async_trait]
+impl HttpTransport for ReqwestTransport {
+    async fn get(&self, url: &str, params: &[(&str, String)]) -> Result<String, DomainError> {
+        let body = self
+            .client
+            .get(url)
+            .query(params)
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+        Ok(body)
     }
 }
 
-impl Persist for HttpClient {}
+/// The body `GET {ZILLOW_API_URL}/zhvi` must return:
+/// `{"bundle": [{"regionName", "regionType", "homeType", "percentile", "date",
+/// "dataValue"}]}`, with the enum fields in this crate's labels and an optional
+/// `"state"` on city and county records
+#[derive(Debug, Deserialize)]
+struct ZillowResponse {
+    bundle: Vec<ZillowRecord>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ZillowRecord {
+    region_name: String,
+    region_type: String,
+    home_type: String,
+    percentile: String,
+    #[serde(default)]
+    state: Option<String>,
+    date: NaiveDate,
+    data_value: Option<f64>,
+}
+
+/// Reads ZHVIs from the Zillow API.
+/// Zillow's terms forbid storing its data, so ZHVI writes are rejected.
+/// Every other dataset is served by the wrapped database client.
+pub struct HttpClient {
+    base_url: String,
+    api_key: Option<String>,
+    transport: Box<dyn HttpTransport>,
+    database: Box<dyn Persist>,
+}
+
+impl HttpClient {
+    pub fn new(config: &Config, database: Box<dyn Persist>) -> Self {
+        HttpClient::with_transport(
+            config.zillow_api_url(),
+            config.zillow_api_key(),
+            Box::new(ReqwestTransport::new()),
+            database,
+        )
+    }
+
+    pub fn with_transport(
+        base_url: &str,
+        api_key: Option<&str>,
+        transport: Box<dyn HttpTransport>,
+        database: Box<dyn Persist>,
+    ) -> Self {
+        HttpClient {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key: api_key.map(str::to_string),
+            transport,
+            database,
+        }
+    }
+
+    fn database(&self) -> &dyn Persist {
+        &*self.database
+    }
+
+    async fn fetch_zhvis(&self, mut params: Vec<(&str, String)>) -> Result<Zhvis, DomainError> {
+        if let Some(api_key) = &self.api_key {
+            params.push(("access_token", api_key.clone()));
+        }
+        let url = format!("{}/zhvi", self.base_url);
+        let body = self.transport.get(&url, &params).await?;
+        let response: ZillowResponse = serde_json::from_str(&body)?;
+        to_zhvis(response.bundle)
+    }
+}
+
+fn read_only_error(name: &str) -> DomainError {
+    DomainError::ReadOnly(format!("{} is served from the Zillow API", name))
+}
+
+// Region name, region type, home type, percentile and state of a record
+type ZillowKey = (String, String, String, String, Option<String>);
+
+fn to_zhvis(records: Vec<ZillowRecord>) -> Result<Zhvis, DomainError> {
+    let mut zhvis: BTreeMap<ZillowKey, Zhvi> = BTreeMap::new();
+    for record in records {
+        let key = (
+            record.region_name.clone(),
+            record.region_type.to_ascii_lowercase(),
+            record.home_type.to_ascii_lowercase(),
+            record.percentile.to_ascii_lowercase(),
+            record.state.clone(),
+        );
+        let zhvi = match zhvis.get_mut(&key) {
+            Some(zhvi) => zhvi,
+            None => {
+                let zhvi = Zhvi {
+                    region_name: record.region_name,
+                    region_type: RegionType::try_from(key.1.as_str())?,
+                    state: record.state,
+                    home_type: HomeType::try_from(key.2.as_str())?,
+                    percentile: Percentile::try_from(key.3.as_str())?,
+                    prices: ZhviPrices::default(),
+                };
+                zhvis.entry(key).or_insert(zhvi)
+            }
+        };
+        if let Some(value) = record.data_value {
+            zhvi.prices.push(ZhviPrice {
+                date: record.date,
+                value,
+                filled: false,
+            });
+        }
+    }
+
+    Ok(zhvis
+        .into_values()
+        .map(|mut zhvi| {
+            zhvi.prices.sort_by_key(|price| price.date);
+            zhvi
+        })
+        .collect())
+}
+
+#[async_trait]
+impl CpiPersist for HttpClient {
+    async fn create_cpi(&self, cpi: &Cpi) -> Result<CpiId, DomainError> {
+        self.database().create_cpi(cpi).await
+    }
+
+    async fn create_cpis(&self, cpis: &[Cpi]) -> Result<(), DomainError> {
+        self.database().create_cpis(cpis).await
+    }
+
+    async fn read_cpi_by_id(&self, id: &NaiveDate) -> Result<Cpi, DomainError> {
+        self.database().read_cpi_by_id(id).await
+    }
+
+    async fn update_cpi(&self, cpi: &Cpi) -> Result<(), DomainError> {
+        self.database().update_cpi(cpi).await
+    }
+
+    async fn delete_cpi_by_id(&self, id: &NaiveDate) -> Result<(), DomainError> {
+        self.database().delete_cpi_by_id(id).await
+    }
+
+    async fn read_cpis_by_query(&self, query: &CpiQuery) -> Result<Cpis, DomainError> {
+        self.database().read_cpis_by_query(query).await
+    }
+}
 
 #[async_trait]
 impl HpiPersist for HttpClient {
-    async fn create_hpi(&self, hpi: &Hpi) -> Result<(String, i32), DomainError> {
-        println!("Calling hpi create for: {:?} from HttpClient.", hpi);
-        Ok((String::default(), i32::default()))
+    async fn create_hpi(&self, hpi: &Hpi) -> Result<HpiId, DomainError> {
+        self.database().create_hpi(hpi).await
     }
 
-    async fn read_hpi_by_id(&self, id: (&str, i32)) -> Result<Hpi, DomainError> {
-        println!("Calling hpi read with id: {:?} from HttpClient.", id);
-        Ok(Hpi::default())
+    async fn create_hpis(&self, hpis: &[Hpi]) -> Result<(), DomainError> {
+        self.database().create_hpis(hpis).await
+    }
+
+    async fn read_hpi_by_id(&self, id: (&RegionType, &str, i32)) -> Result<Hpi, DomainError> {
+        self.database().read_hpi_by_id(id).await
     }
 
     async fn update_hpi(&self, hpi: &Hpi) -> Result<(), DomainError> {
-        println!("Calling hpi update for: {:?} from HttpClient.", hpi);
-        Ok(())
+        self.database().update_hpi(hpi).await
     }
 
-    async fn delete_hpi_by_id(&self, id: (&str, i32)) -> Result<(), DomainError> {
-        println!("Calling hpi delete with id: {:?} from HttpClient.", id);
-        Ok(())
+    async fn delete_hpi_by_id(&self, id: (&RegionType, &str, i32)) -> Result<(), DomainError> {
+        self.database().delete_hpi_by_id(id).await
     }
 
     async fn read_hpi_by_query(&self, query: &HpiQuery) -> Result<Hpis, DomainError> {
-        println!("Calling hpi read by: {:?} from HttpClient.", query);
-        Ok(vec![Hpi::default()])
+        self.database().read_hpi_by_query(query).await
+    }
+}
+
+#[async_trait]
+impl IncomePersist for HttpClient {
+    async fn create_income(&self, income: &Income) -> Result<IncomeId, DomainError> {
+        self.database().create_income(income).await
+    }
+
+    async fn create_incomes(&self, incomes: &[Income]) -> Result<(), DomainError> {
+        self.database().create_incomes(incomes).await
+    }
+
+    async fn read_income_by_id(&self, id: (&RegionType, &str, i32)) -> Result<Income, DomainError> {
+        self.database().read_income_by_id(id).await
+    }
+
+    async fn update_income(&self, income: &Income) -> Result<(), DomainError> {
+        self.database().update_income(income).await
+    }
+
+    async fn delete_income_by_id(&self, id: (&RegionType, &str, i32)) -> Result<(), DomainError> {
+        self.database().delete_income_by_id(id).await
+    }
+
+    async fn read_incomes_by_query(&self, query: &IncomeQuery) -> Result<Incomes, DomainError> {
+        self.database().read_incomes_by_query(query).await
+    }
+}
+
+#[async_trait]
+impl MortgageRatePersist for HttpClient {
+    async fn create_mortgage_rate(
+        &self,
+        mortgage_rate: &MortgageRate,
+    ) -> Result<MortgageRateId, DomainError> {
+        self.database().create_mortgage_rate(mortgage_rate).await
+    }
+
+    async fn create_mortgage_rates(
+        &self,
+        mortgage_rates: &[MortgageRate],
+    ) -> Result<(), DomainError> {
+        self.database().create_mortgage_rates(mortgage_rates).await
+    }
+
+    async fn read_mortgage_rate_by_id(
+        &self,
+        id: (&MortgageTerm, &NaiveDate),
+    ) -> Result<MortgageRate, DomainError> {
+        self.database().read_mortgage_rate_by_id(id).await
+    }
+
+    async fn update_mortgage_rate(&self, mortgage_rate: &MortgageRate) -> Result<(), DomainError> {
+        self.database().update_mortgage_rate(mortgage_rate).await
+    }
+
+    async fn delete_mortgage_rate_by_id(
+        &self,
+        id: (&MortgageTerm, &NaiveDate),
+    ) -> Result<(), DomainError> {
+        self.database().delete_mortgage_rate_by_id(id).await
+    }
+
+    async fn read_mortgage_rates_by_query(
+        &self,
+        query: &MortgageRateQuery,
+    ) -> Result<MortgageRates, DomainError> {
+        self.database().read_mortgage_rates_by_query(query).await
     }
 }
 
 #[async_trait]
 impl RegionPersist for HttpClient {
     async fn create_region(&self, region: &Region) -> Result<Zipcode, DomainError> {
-        println!("Calling region create for: {:?} from HttpClient.", region);
-        Ok(Zipcode::default())
+        self.database().create_region(region).await
     }
 
     async fn read_region_by_id(&self, id: &str) -> Result<Region, DomainError> {
-        println!("Calling region read for: {:?} from HttpClient.", id);
-        Ok(Region::default())
+        self.database().read_region_by_id(id).await
     }
 
     async fn read_regions_by_city(&self, id: &str) -> Result<Regions, DomainError> {
-        println!("Calling region read with id: {:?} from HttpClient.", id);
-        Ok(Regions::default())
+        self.database().read_regions_by_city(id).await
     }
 
     async fn read_regions_by_query(&self, query: &RegionQuery) -> Result<Regions, DomainError> {
-        println!(
-            "Calling region read with query: {:?} from HttpClient.",
-            query
-        );
-        Ok(Regions::default())
+        self.database().read_regions_by_query(query).await
     }
 
     async fn delete_region_by_id(&self, id: &str) -> Result<Zipcode, DomainError> {
-        println!("Calling region delete with id: {:?} from HttpClient.", id);
-        Ok(Zipcode::default())
+        self.database().delete_region_by_id(id).await
     }
 }
 
 #[async_trait]
 impl TYieldPersist for HttpClient {
-    async fn create_t_yield(&self, t_yield: &TYield) -> Result<(String, NaiveDate), DomainError> {
-        println!("Calling t_yield create for: {:?} from HttpClient.", t_yield);
-        Ok((String::default(), NaiveDate::default()))
+    async fn create_t_yield(&self, t_yield: &TYield) -> Result<TYieldId, DomainError> {
+        self.database().create_t_yield(t_yield).await
+    }
+
+    async fn create_t_yields(&self, t_yields: &[TYield]) -> Result<(), DomainError> {
+        self.database().create_t_yields(t_yields).await
     }
 
-    async fn read_t_yield_by_id(&self, id: (&str, &NaiveDate)) -> Result<TYield, DomainError> {
-        println!("Calling t_yield read with id: {:?} from HttpClient.", id);
-        Ok(TYield::default())
+    async fn read_t_yield_by_id(&self, id: (&Term, &NaiveDate)) -> Result<TYield, DomainError> {
+        self.database().read_t_yield_by_id(id).await
     }
 
     async fn update_t_yield(&self, t_yield: &TYield) -> Result<(), DomainError> {
-        println!("Calling t_yield update for: {:?} from HttpClient.", t_yield);
-        Ok(())
+        self.database().update_t_yield(t_yield).await
     }
 
-    async fn delete_t_yield_by_id(&self, id: (&str, &NaiveDate)) -> Result<(), DomainError> {
-        println!("Calling t_yield delete with id: {:?} from HttpClient.", id);
-        Ok(())
+    async fn delete_t_yield_by_id(&self, id: (&Term, &NaiveDate)) -> Result<(), DomainError> {
+        self.database().delete_t_yield_by_id(id).await
     }
 
     async fn read_t_yields_by_query(&self, query: &TYieldQuery) -> Result<TYields, DomainError> {
-        println!("Calling t_yield read by: {:?} from HttpClient.", query);
-        Ok(vec![TYield::default()])
+        self.database().read_t_yields_by_query(query).await
     }
 }
 
+impl Persist for HttpClient {}
+
 #[async_trait]
 impl ZhviPersist for HttpClient {
-    async fn create_zhvi(&self, zhvi: &Zhvi) -> Result<(), DomainError> {
-        println!("Calling zhvi create for: {:?} from HttpClient.", zhvi);
-        Ok(())
+    async fn create_zhvi(&self, _zhvi: &Zhvi) -> Result<(), DomainError> {
+        Err(read_only_error("Zhvi"))
     }
 
-    async fn read_zhvi_by_id(&self, id: (&str, &str, &str, &str)) -> Result<Zhvi, DomainError> {
-        println!("Calling zhvi read with id: {id:?} from HttpClient.");
-        Ok(Zhvi::default())
+    async fn create_zhvis(&self, _zhvis: &[Zhvi]) -> Result<(), DomainError> {
+        Err(read_only_error("Zhvi"))
     }
 
-    async fn update_zhvi(&self, zhvi: &Zhvi) -> Result<(), DomainError> {
-        println!("Calling zhvi update for: {:?} from HttpClient.", zhvi);
-        Ok(())
+    async fn read_zhvi_by_id(&self, id: ZhviId<'_>) -> Result<Zhvi, DomainError> {
+        let mut params = vec![
+            ("regionName", id.0.to_string()),
+            ("regionType", id.1.to_string()),
+            ("homeType", id.2.to_string()),
+            ("percentile", id.3.to_string()),
+        ];
+        if let Some(state) = id.4 {
+            params.push(("state", state.to_string()));
+        }
+        self.fetch_zhvis(params)
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| DomainError::Http("Zhvi not found".to_string()))
     }
 
-    async fn delete_zhvi_by_id(&self, id: (&str, &str, &str, &str)) -> Result<(), DomainError> {
-        println!("Calling zhvi delete with id: {id:?} from HttpClient.");
-        Ok(())
+    async fn update_zhvi(&self, _zhvi: &Zhvi) -> Result<(), DomainError> {
+        Err(read_only_error("Zhvi"))
+    }
+
+    async fn delete_zhvi_by_id(&self, _id: ZhviId<'_>) -> Result<(), DomainError> {
+        Err(read_only_error("Zhvi"))
     }
 
     async fn read_zhvi_by_query(&self, query: &ZhviQuery) -> Result<Zhvis, DomainError> {
-        println!("Calling zhvi read by: {:?} from HttpClient.", query);
-        Ok(Zhvis::default())
+        let mut params = vec![
+            ("regionName", query.region_name().to_string()),
+            ("regionType", query.region_type().to_string()),
+            ("homeType", query.home_type().to_string()),
+            ("percentile", query.percentile().to_string()),
+            ("startDate", query.start_date().to_string()),
+            ("endDate", query.end_date().to_string()),
+        ];
+        if let Some(state) = query.state() {
+            params.push(("state", state.to_string()));
+        }
+        let mut zhvis = self.fetch_zhvis(params).await?;
+
+        for zhvi in zhvis.iter_mut() {
+            zhvi.prices.retain(|price| {
+                &price.date >= query.start_date() && &price.date <= query.end_date()
+            });
+        }
+        Ok(zhvis)
     }
 }
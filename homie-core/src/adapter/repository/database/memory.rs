@@ -0,0 +1,527 @@
+use std::collections::BTreeMap;
+use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use async_trait::async_trait;
+use chrono::NaiveDate;
+
+use crate::adapter::repository::Persist;
+use crate::domain::common::RegionType;
+use crate::domain::cpi::{Cpi, CpiId, CpiPersist, CpiQuery, Cpis};
+use crate::domain::hpi::{Hpi, HpiId, HpiPersist, HpiQuery, Hpis};
+use crate::domain::income::{Income, IncomeId, IncomePersist, IncomeQuery, Incomes};
+use crate::domain::mortgage_rate::{
+    MortgageRate, MortgageRateId, MortgageRatePersist, MortgageRateQuery, MortgageRates,
+    MortgageTerm,
+};
+use crate::domain::region::{CountyFips, Region, RegionPersist, RegionQuery, Regions, Zipcode};
+use crate::domain::t_yield::{TYield, TYieldId, TYieldPersist, TYieldQuery, TYields, Term};
+use crate::domain::zhvi::{Zhvi, ZhviId, ZhviPersist, ZhviPrices, ZhviQuery, Zhvis};
+use crate::error::DomainError;
+
+type HpiKey = (String, String, i32);
+type IncomeKey = (String, String, i32);
+type MortgageRateKey = (String, NaiveDate);
+type TYieldKey = (String, NaiveDate);
+type ZhviKey = (String, String, String, String, Option<String>);
+
+/// Stores every dataset in process memory.
+/// Useful for tests and running offline without a database.
+#[derive(Default)]
+pub struct InMemoryClient {
+    cpis: RwLock<BTreeMap<NaiveDate, Cpi>>,
+    hpis: RwLock<BTreeMap<HpiKey, Hpi>>,
+    incomes: RwLock<BTreeMap<IncomeKey, Income>>,
+    mortgage_rates: RwLock<BTreeMap<MortgageRateKey, MortgageRate>>,
+    regions: RwLock<BTreeMap<(Zipcode, CountyFips), Region>>,
+    t_yields: RwLock<BTreeMap<TYieldKey, TYield>>,
+    zhvis: RwLock<BTreeMap<ZhviKey, Zhvi>>,
+}
+
+impl InMemoryClient {
+    pub fn new() -> Self {
+        InMemoryClient::default()
+    }
+}
+
+impl Persist for InMemoryClient {}
+
+fn read_lock<T>(lock: &RwLock<T>) -> Result<RwLockReadGuard<'_, T>, DomainError> {
+    lock.read()
+        .map_err(|e| DomainError::Database(format!("Failed to acquire read lock: {}", e)))
+}
+
+fn write_lock<T>(lock: &RwLock<T>) -> Result<RwLockWriteGuard<'_, T>, DomainError> {
+    lock.write()
+        .map_err(|e| DomainError::Database(format!("Failed to acquire write lock: {}", e)))
+}
+
+fn not_found(name: &str) -> DomainError {
+    DomainError::Database(format!("{} not found", name))
+}
+
+fn already_exists(name: &str) -> DomainError {
+    DomainError::Database(format!("{} already exists", name))
+}
+
+fn hpi_key(hpi: &Hpi) -> HpiKey {
+    (
+        hpi.region_type().to_string(),
+        hpi.region_id().to_string(),
+        hpi.year(),
+    )
+}
+
+fn hpi_id_key(id: (&RegionType, &str, i32)) -> HpiKey {
+    (id.0.to_string(), id.1.to_string(), id.2)
+}
+
+fn income_key(income: &Income) -> IncomeKey {
+    (
+        income.region_type().to_string(),
+        income.region_id().to_string(),
+        income.year(),
+    )
+}
+
+fn income_id_key(id: (&RegionType, &str, i32)) -> IncomeKey {
+    (id.0.to_string(), id.1.to_string(), id.2)
+}
+
+fn zhvi_key(zhvi: &Zhvi) -> ZhviKey {
+    (
+        zhvi.region_name().to_string(),
+        zhvi.region_type().to_string(),
+        zhvi.home_type().to_string(),
+        zhvi.percentile().to_string(),
+        zhvi.state().map(str::to_string),
+    )
+}
+
+fn zhvi_id_key(id: ZhviId) -> ZhviKey {
+    (
+        id.0.to_string(),
+        id.1.to_string(),
+        id.2.to_string(),
+        id.3.to_string(),
+        id.4.map(str::to_string),
+    )
+}
+
+#[async_trait]
+impl CpiPersist for InMemoryClient {
+    async fn create_cpi(&self, cpi: &Cpi) -> Result<CpiId, DomainError> {
+        let mut cpis = write_lock(&self.cpis)?;
+        if cpis.contains_key(cpi.date()) {
+            return Err(already_exists("Cpi"));
+        }
+        cpis.insert(*cpi.date(), cpi.clone());
+        Ok(*cpi.date())
+    }
+
+    async fn create_cpis(&self, cpis: &[Cpi]) -> Result<(), DomainError> {
+        let mut stored = write_lock(&self.cpis)?;
+        for cpi in cpis {
+            stored.entry(*cpi.date()).or_insert_with(|| cpi.clone());
+        }
+        Ok(())
+    }
+
+    async fn read_cpi_by_id(&self, id: &NaiveDate) -> Result<Cpi, DomainError> {
+        read_lock(&self.cpis)?
+            .get(id)
+            .cloned()
+            .ok_or_else(|| not_found("Cpi"))
+    }
+
+    async fn update_cpi(&self, cpi: &Cpi) -> Result<(), DomainError> {
+        let mut cpis = write_lock(&self.cpis)?;
+        let stored = cpis.get_mut(cpi.date()).ok_or_else(|| not_found("Cpi"))?;
+        stored.value = cpi.value();
+        Ok(())
+    }
+
+    async fn delete_cpi_by_id(&self, id: &NaiveDate) -> Result<(), DomainError> {
+        write_lock(&self.cpis)?
+            .remove(id)
+            .map(|_| ())
+            .ok_or_else(|| not_found("Cpi"))
+    }
+
+    async fn read_cpis_by_query(&self, query: &CpiQuery) -> Result<Cpis, DomainError> {
+        let cpis = read_lock(&self.cpis)?
+            .range(query.start_date()..=query.end_date())
+            .map(|(_, cpi)| cpi.clone())
+            .collect();
+        Ok(cpis)
+    }
+}
+
+#[async_trait]
+impl HpiPersist for InMemoryClient {
+    async fn create_hpi(&self, hpi: &Hpi) -> Result<HpiId, DomainError> {
+        let key = hpi_key(hpi);
+        let mut hpis = write_lock(&self.hpis)?;
+        if hpis.contains_key(&key) {
+            return Err(already_exists("Hpi"));
+        }
+        hpis.insert(key, hpi.clone());
+        Ok((
+            hpi.region_type().clone(),
+            hpi.region_id().to_string(),
+            hpi.year(),
+        ))
+    }
+
+    async fn create_hpis(&self, hpis: &[Hpi]) -> Result<(), DomainError> {
+        let mut stored = write_lock(&self.hpis)?;
+        for hpi in hpis {
+            stored.entry(hpi_key(hpi)).or_insert_with(|| hpi.clone());
+        }
+        Ok(())
+    }
+
+    async fn read_hpi_by_id(&self, id: (&RegionType, &str, i32)) -> Result<Hpi, DomainError> {
+        read_lock(&self.hpis)?
+            .get(&hpi_id_key(id))
+            .cloned()
+            .ok_or_else(|| not_found("Hpi"))
+    }
+
+    async fn update_hpi(&self, hpi: &Hpi) -> Result<(), DomainError> {
+        let mut hpis = write_lock(&self.hpis)?;
+        let stored = hpis
+            .get_mut(&hpi_key(hpi))
+            .ok_or_else(|| not_found("Hpi"))?;
+        stored.hpi = hpi.hpi();
+        Ok(())
+    }
+
+    async fn delete_hpi_by_id(&self, id: (&RegionType, &str, i32)) -> Result<(), DomainError> {
+        write_lock(&self.hpis)?
+            .remove(&hpi_id_key(id))
+            .map(|_| ())
+            .ok_or_else(|| not_found("Hpi"))
+    }
+
+    async fn read_hpi_by_query(&self, query: &HpiQuery) -> Result<Hpis, DomainError> {
+        let hpis = read_lock(&self.hpis)?
+            .values()
+            .filter(|hpi| {
+                hpi.region_type() == query.region_type() && hpi.region_id() == query.region_id()
+            })
+            .filter(|hpi| hpi.year() >= query.start_date() && hpi.year() <= query.end_date())
+            .cloned()
+            .collect();
+        Ok(hpis)
+    }
+}
+
+#[async_trait]
+impl IncomePersist for InMemoryClient {
+    async fn create_income(&self, income: &Income) -> Result<IncomeId, DomainError> {
+        let key = income_key(income);
+        let mut incomes = write_lock(&self.incomes)?;
+        if incomes.contains_key(&key) {
+            return Err(already_exists("Income"));
+        }
+        incomes.insert(key, income.clone());
+        Ok((
+            income.region_type().clone(),
+            income.region_id().to_string(),
+            income.year(),
+        ))
+    }
+
+    async fn create_incomes(&self, incomes: &[Income]) -> Result<(), DomainError> {
+        let mut stored = write_lock(&self.incomes)?;
+        for income in incomes {
+            stored
+                .entry(income_key(income))
+                .or_insert_with(|| income.clone());
+        }
+        Ok(())
+    }
+
+    async fn read_income_by_id(&self, id: (&RegionType, &str, i32)) -> Result<Income, DomainError> {
+        read_lock(&self.incomes)?
+            .get(&income_id_key(id))
+            .cloned()
+            .ok_or_else(|| not_found("Income"))
+    }
+
+    async fn update_income(&self, income: &Income) -> Result<(), DomainError> {
+        let mut incomes = write_lock(&self.incomes)?;
+        let stored = incomes
+            .get_mut(&income_key(income))
+            .ok_or_else(|| not_found("Income"))?;
+        stored.median_income = income.median_income();
+        stored.margin_of_error = income.margin_of_error();
+        Ok(())
+    }
+
+    async fn delete_income_by_id(&self, id: (&RegionType, &str, i32)) -> Result<(), DomainError> {
+        write_lock(&self.incomes)?
+            .remove(&income_id_key(id))
+            .map(|_| ())
+            .ok_or_else(|| not_found("Income"))
+    }
+
+    async fn read_incomes_by_query(&self, query: &IncomeQuery) -> Result<Incomes, DomainError> {
+        let incomes = read_lock(&self.incomes)?
+            .values()
+            .filter(|income| query.matches(income))
+            .cloned()
+            .collect();
+        Ok(incomes)
+    }
+}
+
+#[async_trait]
+impl MortgageRatePersist for InMemoryClient {
+    async fn create_mortgage_rate(
+        &self,
+        mortgage_rate: &MortgageRate,
+    ) -> Result<MortgageRateId, DomainError> {
+        let key = (mortgage_rate.term().to_string(), *mortgage_rate.date());
+        let mut mortgage_rates = write_lock(&self.mortgage_rates)?;
+        if mortgage_rates.contains_key(&key) {
+            return Err(already_exists("MortgageRate"));
+        }
+        mortgage_rates.insert(key, mortgage_rate.clone());
+        Ok((mortgage_rate.term().clone(), *mortgage_rate.date()))
+    }
+
+    async fn create_mortgage_rates(
+        &self,
+        mortgage_rates: &[MortgageRate],
+    ) -> Result<(), DomainError> {
+        let mut stored = write_lock(&self.mortgage_rates)?;
+        for mortgage_rate in mortgage_rates {
+            stored
+                .entry((mortgage_rate.term().to_string(), *mortgage_rate.date()))
+                .or_insert_with(|| mortgage_rate.clone());
+        }
+        Ok(())
+    }
+
+    async fn read_mortgage_rate_by_id(
+        &self,
+        id: (&MortgageTerm, &NaiveDate),
+    ) -> Result<MortgageRate, DomainError> {
+        read_lock(&self.mortgage_rates)?
+            .get(&(id.0.to_string(), *id.1))
+            .cloned()
+            .ok_or_else(|| not_found("MortgageRate"))
+    }
+
+    async fn update_mortgage_rate(&self, mortgage_rate: &MortgageRate) -> Result<(), DomainError> {
+        let mut mortgage_rates = write_lock(&self.mortgage_rates)?;
+        let stored = mortgage_rates
+            .get_mut(&(mortgage_rate.term().to_string(), *mortgage_rate.date()))
+            .ok_or_else(|| not_found("MortgageRate"))?;
+        stored.rate = *mortgage_rate.rate();
+        stored.points = *mortgage_rate.points();
+        Ok(())
+    }
+
+    async fn delete_mortgage_rate_by_id(
+        &self,
+        id: (&MortgageTerm, &NaiveDate),
+    ) -> Result<(), DomainError> {
+        write_lock(&self.mortgage_rates)?
+            .remove(&(id.0.to_string(), *id.1))
+            .map(|_| ())
+            .ok_or_else(|| not_found("MortgageRate"))
+    }
+
+    async fn read_mortgage_rates_by_query(
+        &self,
+        query: &MortgageRateQuery,
+    ) -> Result<MortgageRates, DomainError> {
+        let mortgage_rates = read_lock(&self.mortgage_rates)?
+            .values()
+            .filter(|mortgage_rate| query.contains(mortgage_rate.term(), mortgage_rate.date()))
+            .cloned()
+            .collect();
+        Ok(mortgage_rates)
+    }
+}
+
+#[async_trait]
+impl RegionPersist for InMemoryClient {
+    async fn create_region(&self, region: &Region) -> Result<Zipcode, DomainError> {
+        let key = (
+            region.zipcode().to_string(),
+            region.county_fips().to_string(),
+        );
+        write_lock(&self.regions)?.insert(key, region.clone());
+        Ok(region.zipcode().to_string())
+    }
+
+    async fn read_region_by_id(&self, id: &str) -> Result<Region, DomainError> {
+        read_lock(&self.regions)?
+            .values()
+            .filter(|region| region.zipcode() == id)
+            .max_by(|a, b| a.tot_ratio().total_cmp(&b.tot_ratio()))
+            .cloned()
+            .ok_or_else(|| not_found("Region"))
+    }
+
+    async fn read_regions_by_city(&self, id: &str) -> Result<Regions, DomainError> {
+        let regions = read_lock(&self.regions)?
+            .values()
+            .filter(|region| region.city() == id)
+            .cloned()
+            .collect();
+        Ok(regions)
+    }
+
+    async fn read_regions_by_query(&self, query: &RegionQuery) -> Result<Regions, DomainError> {
+        let regions = read_lock(&self.regions)?
+            .values()
+            .filter(|region| query.matches(region))
+            .cloned()
+            .collect();
+        Ok(regions)
+    }
+
+    async fn delete_region_by_id(&self, id: &str) -> Result<Zipcode, DomainError> {
+        let mut regions = write_lock(&self.regions)?;
+        let count = regions.len();
+        regions.retain(|(zipcode, _), _| zipcode != id);
+        if regions.len() == count {
+            return Err(not_found("Region"));
+        }
+        Ok(id.to_string())
+    }
+}
+
+#[async_trait]
+impl TYieldPersist for InMemoryClient {
+    async fn create_t_yield(&self, t_yield: &TYield) -> Result<TYieldId, DomainError> {
+        let key = (t_yield.term().to_string(), *t_yield.date());
+        let mut t_yields = write_lock(&self.t_yields)?;
+        if t_yields.contains_key(&key) {
+            return Err(already_exists("TYield"));
+        }
+        t_yields.insert(key, t_yield.clone());
+        Ok((t_yield.term().clone(), *t_yield.date()))
+    }
+
+    async fn create_t_yields(&self, t_yields: &[TYield]) -> Result<(), DomainError> {
+        let mut stored = write_lock(&self.t_yields)?;
+        for t_yield in t_yields {
+            stored
+                .entry((t_yield.term().to_string(), *t_yield.date()))
+                .or_insert_with(|| t_yield.clone());
+        }
+        Ok(())
+    }
+
+    async fn read_t_yield_by_id(&self, id: (&Term, &NaiveDate)) -> Result<TYield, DomainError> {
+        read_lock(&self.t_yields)?
+            .get(&(id.0.to_string(), *id.1))
+            .cloned()
+            .ok_or_else(|| not_found("TYield"))
+    }
+
+    async fn update_t_yield(&self, t_yield: &TYield) -> Result<(), DomainError> {
+        let mut t_yields = write_lock(&self.t_yields)?;
+        let stored = t_yields
+            .get_mut(&(t_yield.term().to_string(), *t_yield.date()))
+            .ok_or_else(|| not_found("TYield"))?;
+        stored.yield_return = *t_yield.yield_return();
+        Ok(())
+    }
+
+    async fn delete_t_yield_by_id(&self, id: (&Term, &NaiveDate)) -> Result<(), DomainError> {
+        write_lock(&self.t_yields)?
+            .remove(&(id.0.to_string(), *id.1))
+            .map(|_| ())
+            .ok_or_else(|| not_found("TYield"))
+    }
+
+    async fn read_t_yields_by_query(&self, query: &TYieldQuery) -> Result<TYields, DomainError> {
+        let t_yields = read_lock(&self.t_yields)?
+            .values()
+            .filter(|t_yield| query.contains(t_yield.term(), t_yield.date()))
+            .cloned()
+            .collect();
+        Ok(t_yields)
+    }
+}
+
+#[async_trait]
+impl ZhviPersist for InMemoryClient {
+    async fn create_zhvi(&self, zhvi: &Zhvi) -> Result<(), DomainError> {
+        let key = zhvi_key(zhvi);
+        let mut zhvis = write_lock(&self.zhvis)?;
+        if zhvis.contains_key(&key) {
+            return Err(already_exists("Zhvi"));
+        }
+        zhvis.insert(key, zhvi.clone());
+        Ok(())
+    }
+
+    async fn create_zhvis(&self, zhvis: &[Zhvi]) -> Result<(), DomainError> {
+        let mut stored = write_lock(&self.zhvis)?;
+        for zhvi in zhvis {
+            let stored = stored.entry(zhvi_key(zhvi)).or_insert_with(|| Zhvi {
+                prices: ZhviPrices::default(),
+                ..zhvi.clone()
+            });
+            for price in zhvi.prices() {
+                if !stored.prices.iter().any(|p| p.date == price.date) {
+                    stored.prices.push(price.clone());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn read_zhvi_by_id(&self, id: ZhviId<'_>) -> Result<Zhvi, DomainError> {
+        read_lock(&self.zhvis)?
+            .get(&zhvi_id_key(id))
+            .cloned()
+            .ok_or_else(|| not_found("Zhvi"))
+    }
+
+    async fn update_zhvi(&self, zhvi: &Zhvi) -> Result<(), DomainError> {
+        let mut zhvis = write_lock(&self.zhvis)?;
+        let stored = zhvis
+            .get_mut(&zhvi_key(zhvi))
+            .ok_or_else(|| not_found("Zhvi"))?;
+        stored.prices = zhvi.prices().clone();
+        Ok(())
+    }
+
+    async fn delete_zhvi_by_id(&self, id: ZhviId<'_>) -> Result<(), DomainError> {
+        write_lock(&self.zhvis)?
+            .remove(&zhvi_id_key(id))
+            .map(|_| ())
+            .ok_or_else(|| not_found("Zhvi"))
+    }
+
+    async fn read_zhvi_by_query(&self, query: &ZhviQuery) -> Result<Zhvis, DomainError> {
+        let zhvis = read_lock(&self.zhvis)?
+            .values()
+            .filter(|zhvi| {
+                zhvi.region_name() == query.region_name()
+                    && zhvi.region_type() == query.region_type()
+                    && zhvi.home_type() == query.home_type()
+                    && zhvi.percentile() == query.percentile()
+                    && query.includes_state(zhvi.state())
+            })
+            .map(|zhvi| Zhvi {
+                prices: zhvi
+                    .prices()
+                    .iter()
+                    .filter(|p| &p.date >= query.start_date() && &p.date <= query.end_date())
+                    .cloned()
+                    .collect(),
+                ..zhvi.clone()
+            })
+            .collect();
+        Ok(zhvis)
+    }
+}
@@ -1,49 +1,304 @@
 use async_trait::async_trait;
 use chrono::NaiveDate;
+use sqlx::migrate::{Migrate, Migrator};
 use sqlx::postgres::PgPoolOptions;
-use sqlx::{query, query_as, FromRow, Pool, Postgres};
+use sqlx::{query, query_as, query_scalar, FromRow, Pool, Postgres};
 
 use crate::adapter::repository::{Config, Persist};
-use crate::domain::common::{DateInterval, RegionType};
+use crate::domain::common::RegionType;
+use crate::domain::cpi::*;
 use crate::domain::hpi::*;
+use crate::domain::income::*;
+use crate::domain::mortgage_rate::*;
 use crate::domain::region::{Region, RegionPersist, RegionQuery, Regions, Zipcode};
 use crate::domain::t_yield::*;
 use crate::domain::zhvi::*;
 use crate::error::DomainError;
 
+static MIGRATOR: Migrator = sqlx::migrate!("./migrations/postgres");
+// Created by `psql < init_tables.sql` before migrations were embedded
+const INIT_TABLES_VERSION: i64 = 20240424014039;
+
+// Rows per UNNEST insert, keeps each statement's parameter arrays bounded
+const BATCH_SIZE: usize = 10_000;
+
 pub struct PostgresClient {
     pool: Pool<Postgres>,
 }
 
 impl PostgresClient {
-    pub async fn new(_config: &Config) -> Result<Self, DomainError> {
+    pub async fn new(config: &Config) -> Result<Self, DomainError> {
+        let client = PostgresClient::connect(&std::env::var("DATABASE_URL")?).await?;
+        if config.is_run_migrations_enabled() {
+            client.migrate().await?;
+        }
+        client.check_schema().await?;
+        Ok(client)
+    }
+
+    pub async fn connect(database_url: &str) -> Result<Self, DomainError> {
         let pool = PgPoolOptions::new()
             .max_connections(5)
-            .connect(&std::env::var("DATABASE_URL")?)
+            .connect(database_url)
             .await?;
         Ok(PostgresClient { pool })
     }
 
+    /// Applies pending embedded migrations, recording each version in
+    /// `_sqlx_migrations`
+    pub async fn migrate(&self) -> Result<(), DomainError> {
+        self.baseline().await?;
+        MIGRATOR
+            .run(self.pool())
+            .await
+            .map_err(|e| DomainError::Database(format!("Failed to migrate: {}", e)))
+    }
+
+    /// Marks the init migration applied on a database whose tables were
+    /// created by `init_tables.sql` without `_sqlx_migrations`, so migrating
+    /// picks up from the migrations after it
+    async fn baseline(&self) -> Result<(), DomainError> {
+        let untracked: bool = query_scalar(
+            "SELECT to_regclass('_sqlx_migrations') IS NULL AND to_regclass('zhvi_metadata') IS \
+             NOT NULL",
+        )
+        .fetch_one(self.pool())
+        .await?;
+        if !untracked {
+            return Ok(());
+        }
+        let Some(init) = MIGRATOR.iter().find(|m| m.version == INIT_TABLES_VERSION) else {
+            return Ok(());
+        };
+
+        let mut conn = self.pool().acquire().await?;
+        conn.ensure_migrations_table()
+            .await
+            .map_err(|e| DomainError::Database(format!("Failed to migrate: {}", e)))?;
+        query(
+            r#"
+                INSERT INTO _sqlx_migrations (version, description, success, checksum, execution_time)
+                VALUES ($1, $2, TRUE, $3, 0)
+            "#,
+        )
+        .bind(init.version)
+        .bind(init.description.as_ref())
+        .bind(init.checksum.as_ref())
+        .execute(&mut *conn)
+        .await?;
+        Ok(())
+    }
+
+    /// Refuses a database migrated past this binary, or whose enums drifted
+    /// from the Rust types
+    pub async fn check_schema(&self) -> Result<(), DomainError> {
+        let latest = MIGRATOR.iter().map(|m| m.version).max().unwrap_or(0);
+        let tracked: bool = query_scalar("SELECT to_regclass('_sqlx_migrations') IS NOT NULL")
+            .fetch_one(self.pool())
+            .await?;
+        let applied: Option<i64> = match tracked {
+            true => {
+                query_scalar("SELECT MAX(version) FROM _sqlx_migrations WHERE success")
+                    .fetch_one(self.pool())
+                    .await?
+            }
+            false => None,
+        };
+        if let Some(applied) = applied.filter(|applied| *applied > latest) {
+            return Err(DomainError::Database(format!(
+                "Database schema version {} is newer than this binary ({})",
+                applied, latest
+            )));
+        }
+
+        let labels: Vec<(String, String)> = query_as(
+            r#"
+                SELECT t.typname::TEXT, e.enumlabel::TEXT
+                FROM pg_type t
+                JOIN pg_enum e ON t.oid = e.enumtypid
+                JOIN pg_namespace n ON n.oid = t.typnamespace
+                WHERE n.nspname = current_schema()
+            "#,
+        )
+        .fetch_all(self.pool())
+        .await?;
+        let drift = enum_drift(&rust_enum_labels(), &labels);
+        if !drift.is_empty() {
+            return Err(DomainError::Database(format!(
+                "Postgres enums drifted from the Rust enums: {}",
+                drift.join(", ")
+            )));
+        }
+        Ok(())
+    }
+
     fn pool(&self) -> &Pool<Postgres> {
         &self.pool
     }
 }
 
+// sqlx encodes variants by lowercasing their names, see `#[sqlx(rename_all =
+// "lowercase")]`
+fn sqlx_labels<T: std::fmt::Debug>(variants: &[T]) -> Vec<String> {
+    variants
+        .iter()
+        .map(|variant| format!("{:?}", variant).to_lowercase())
+        .collect()
+}
+
+pub(crate) fn rust_enum_labels() -> Vec<(&'static str, Vec<String>)> {
+    vec![
+        ("home_type", sqlx_labels(&HomeType::ALL)),
+        ("mortgage_term", sqlx_labels(&MortgageTerm::ALL)),
+        ("percentile", sqlx_labels(&Percentile::ALL)),
+        ("region_type", sqlx_labels(&RegionType::ALL)),
+        ("term", sqlx_labels(&Term::ALL)),
+    ]
+}
+
+/// Lists every label missing from either side, given `(type name, label)` rows
+/// from Postgres
+pub(crate) fn enum_drift(
+    expected: &[(&str, Vec<String>)],
+    actual: &[(String, String)],
+) -> Vec<String> {
+    let mut drift = Vec::new();
+    for (type_name, labels) in expected {
+        let found: Vec<&str> = actual
+            .iter()
+            .filter(|(name, _)| name == type_name)
+            .map(|(_, label)| label.as_str())
+            .collect();
+        for label in labels {
+            if !found.contains(&label.as_str()) {
+                drift.push(format!("{} is missing '{}'", type_name, label));
+            }
+        }
+        for label in found {
+            if !labels.iter().any(|l| l == label) {
+                drift.push(format!("{} has unknown '{}'", type_name, label));
+            }
+        }
+    }
+    drift
+}
+
 impl Persist for PostgresClient {}
 
+#[async_trait]
+impl CpiPersist for PostgresClient {
+    async fn create_cpi(&self, cpi: &Cpi) -> Result<CpiId, DomainError> {
+        let record = query!(
+            r#"
+                INSERT INTO cpis
+                (date, value)
+                VALUES ($1, $2)
+                ON CONFLICT (date) DO NOTHING
+                RETURNING date;
+            "#,
+            cpi.date(),
+            cpi.value(),
+        )
+        .fetch_one(self.pool())
+        .await?;
+        Ok(record.date)
+    }
+
+    async fn create_cpis(&self, cpis: &[Cpi]) -> Result<(), DomainError> {
+        let mut tx = self.pool().begin().await?;
+        for chunk in cpis.chunks(BATCH_SIZE) {
+            query(
+                r#"
+                    INSERT INTO cpis
+                    (date, value)
+                    SELECT * FROM UNNEST($1::DATE[], $2::FLOAT8[])
+                    ON CONFLICT (date) DO NOTHING
+                "#,
+            )
+            .bind(chunk.iter().map(|cpi| *cpi.date()).collect::<Vec<_>>())
+            .bind(chunk.iter().map(|cpi| cpi.value()).collect::<Vec<_>>())
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn read_cpi_by_id(&self, id: &NaiveDate) -> Result<Cpi, DomainError> {
+        let record = query_as!(
+            Cpi,
+            r#"
+                SELECT date, value
+                FROM cpis
+                WHERE date = $1
+            "#,
+            id,
+        )
+        .fetch_one(self.pool())
+        .await?;
+        Ok(record)
+    }
+
+    async fn update_cpi(&self, cpi: &Cpi) -> Result<(), DomainError> {
+        query!(
+            r#"
+                UPDATE cpis
+                SET value = $1
+                WHERE date = $2
+                RETURNING date
+            "#,
+            cpi.value(),
+            cpi.date(),
+        )
+        .fetch_one(self.pool())
+        .await?;
+        Ok(())
+    }
+
+    async fn delete_cpi_by_id(&self, id: &NaiveDate) -> Result<(), DomainError> {
+        query!(
+            r#"
+                DELETE FROM cpis
+                WHERE date = $1
+                RETURNING date;
+            "#,
+            id,
+        )
+        .fetch_one(self.pool())
+        .await?;
+        Ok(())
+    }
+
+    async fn read_cpis_by_query(&self, cpi_query: &CpiQuery) -> Result<Cpis, DomainError> {
+        let query = r#"
+            SELECT date, value FROM cpis
+            WHERE date BETWEEN $1 AND $2
+            ORDER BY date
+        "#;
+        let cpis: Cpis = query_as(query)
+            .bind(cpi_query.start_date())
+            .bind(cpi_query.end_date())
+            .fetch_all(self.pool())
+            .await?;
+        Ok(cpis)
+    }
+}
+
 #[async_trait]
 impl HpiPersist for PostgresClient {
-    async fn create_hpi(&self, hpi: &Hpi) -> Result<(String, i32), DomainError> {
+    async fn create_hpi(&self, hpi: &Hpi) -> Result<HpiId, DomainError> {
         let record = query!(
             r#"
                 INSERT INTO hpis
-                (region_name, region_type, year, hpi, annual_change, hpi_1990_base, hpi_2000_base)
-                VALUES ($1, $2, $3, $4, $5, $6, $7)
-                ON CONFLICT (region_name, year) DO NOTHING
-                RETURNING region_name, year;
+                (region_type, region_id, region_name, state, year, hpi, annual_change, hpi_1990_base, hpi_2000_base)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                ON CONFLICT (region_type, region_id, year) DO NOTHING
+                RETURNING region_type AS "region_type: RegionType", region_id, year;
             "#,
-            &hpi.region_name(),
             &hpi.region_type() as _,
+            hpi.region_id(),
+            &hpi.region_name(),
+            hpi.state(),
             &hpi.year(),
             hpi.hpi() as Option<f32>,
             hpi.annual_change() as Option<f32>,
@@ -52,19 +307,47 @@ impl HpiPersist for PostgresClient {
         )
         .fetch_one(self.pool())
         .await?;
-        Ok((record.region_name, record.year))
+        Ok((record.region_type, record.region_id, record.year))
     }
 
-    async fn read_hpi_by_id(&self, id: (&str, i32)) -> Result<Hpi, DomainError> {
+    async fn create_hpis(&self, hpis: &[Hpi]) -> Result<(), DomainError> {
+        let mut tx = self.pool().begin().await?;
+        for chunk in hpis.chunks(BATCH_SIZE) {
+            query(
+                r#"
+                    INSERT INTO hpis
+                    (region_type, region_id, region_name, state, year, hpi, annual_change, hpi_1990_base, hpi_2000_base)
+                    SELECT * FROM UNNEST($1::region_type[], $2::VARCHAR[], $3::TEXT[], $4::VARCHAR[], $5::INTEGER[], $6::FLOAT4[], $7::FLOAT4[], $8::FLOAT4[], $9::FLOAT4[])
+                    ON CONFLICT (region_type, region_id, year) DO NOTHING
+                "#,
+            )
+            .bind(chunk.iter().map(|hpi| hpi.region_type().clone()).collect::<Vec<_>>())
+            .bind(chunk.iter().map(|hpi| hpi.region_id()).collect::<Vec<_>>())
+            .bind(chunk.iter().map(|hpi| hpi.region_name().as_str()).collect::<Vec<_>>())
+            .bind(chunk.iter().map(|hpi| hpi.state()).collect::<Vec<_>>())
+            .bind(chunk.iter().map(|hpi| hpi.year()).collect::<Vec<_>>())
+            .bind(chunk.iter().map(|hpi| hpi.hpi()).collect::<Vec<_>>())
+            .bind(chunk.iter().map(|hpi| hpi.annual_change()).collect::<Vec<_>>())
+            .bind(chunk.iter().map(|hpi| hpi.hpi_1990_base()).collect::<Vec<_>>())
+            .bind(chunk.iter().map(|hpi| hpi.hpi_2000_base()).collect::<Vec<_>>())
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn read_hpi_by_id(&self, id: (&RegionType, &str, i32)) -> Result<Hpi, DomainError> {
         let record = query_as!(
             Hpi,
             r#"
-                SELECT region_name, region_type AS "region_type: RegionType", year, hpi, annual_change, hpi_1990_base, hpi_2000_base
+                SELECT region_type AS "region_type: RegionType", region_id, region_name, state, year, hpi, annual_change, hpi_1990_base, hpi_2000_base
                 FROM hpis
-                WHERE region_name = $1 AND year = $2
+                WHERE region_type = $1 AND region_id = $2 AND year = $3
             "#,
-            id.0,
+            id.0 as _,
             id.1,
+            id.2,
         )
         .fetch_one(self.pool())
         .await?;
@@ -76,11 +359,12 @@ impl HpiPersist for PostgresClient {
             r#"
                 UPDATE hpis
                 SET hpi = $1
-                WHERE region_name = $2 AND year = $3
-                RETURNING region_name, year
+                WHERE region_type = $2 AND region_id = $3 AND year = $4
+                RETURNING region_id, year
             "#,
             hpi.hpi() as Option<f32>,
-            hpi.region_name(),
+            hpi.region_type() as _,
+            hpi.region_id(),
             hpi.year(),
         )
         .fetch_one(self.pool())
@@ -88,15 +372,16 @@ impl HpiPersist for PostgresClient {
         Ok(())
     }
 
-    async fn delete_hpi_by_id(&self, id: (&str, i32)) -> Result<(), DomainError> {
+    async fn delete_hpi_by_id(&self, id: (&RegionType, &str, i32)) -> Result<(), DomainError> {
         query!(
             r#"
                 DELETE FROM hpis
-                WHERE region_name = $1 AND year = $2
-                RETURNING region_name, year;
+                WHERE region_type = $1 AND region_id = $2 AND year = $3
+                RETURNING region_id, year;
             "#,
-            id.0,
+            id.0 as _,
             id.1,
+            id.2,
         )
         .fetch_one(self.pool())
         .await?;
@@ -106,12 +391,14 @@ impl HpiPersist for PostgresClient {
     async fn read_hpi_by_query(&self, hpi_query: &HpiQuery) -> Result<Hpis, DomainError> {
         let query = r#"
             SELECT * FROM hpis
-            WHERE region_name = $1
-            AND year >= $2
-            AND year <= $3
+            WHERE region_type = $1
+            AND region_id = $2
+            AND year >= $3
+            AND year <= $4
         "#;
         let hpis: Vec<Hpi> = query_as(query)
-            .bind(hpi_query.region_name())
+            .bind(hpi_query.region_type())
+            .bind(hpi_query.region_id())
             .bind(hpi_query.start_date())
             .bind(hpi_query.end_date())
             .fetch_all(self.pool())
@@ -120,20 +407,269 @@ impl HpiPersist for PostgresClient {
     }
 }
 
+#[async_trait]
+impl IncomePersist for PostgresClient {
+    async fn create_income(&self, income: &Income) -> Result<IncomeId, DomainError> {
+        let record = query!(
+            r#"
+                INSERT INTO incomes
+                (region_type, region_id, region_name, year, median_income, margin_of_error)
+                VALUES ($1, $2, $3, $4, $5, $6)
+                ON CONFLICT (region_type, region_id, year) DO NOTHING
+                RETURNING region_type AS "region_type: RegionType", region_id, year;
+            "#,
+            income.region_type() as _,
+            income.region_id(),
+            income.region_name(),
+            income.year(),
+            income.median_income(),
+            income.margin_of_error(),
+        )
+        .fetch_one(self.pool())
+        .await?;
+        Ok((record.region_type, record.region_id, record.year))
+    }
+
+    async fn create_incomes(&self, incomes: &[Income]) -> Result<(), DomainError> {
+        let mut tx = self.pool().begin().await?;
+        for chunk in incomes.chunks(BATCH_SIZE) {
+            query(
+                r#"
+                    INSERT INTO incomes
+                    (region_type, region_id, region_name, year, median_income, margin_of_error)
+                    SELECT * FROM UNNEST($1::region_type[], $2::VARCHAR[], $3::TEXT[], $4::INTEGER[], $5::FLOAT8[], $6::FLOAT8[])
+                    ON CONFLICT (region_type, region_id, year) DO NOTHING
+                "#,
+            )
+            .bind(chunk.iter().map(|income| income.region_type().clone()).collect::<Vec<_>>())
+            .bind(chunk.iter().map(|income| income.region_id()).collect::<Vec<_>>())
+            .bind(chunk.iter().map(|income| income.region_name()).collect::<Vec<_>>())
+            .bind(chunk.iter().map(|income| income.year()).collect::<Vec<_>>())
+            .bind(chunk.iter().map(|income| income.median_income()).collect::<Vec<_>>())
+            .bind(chunk.iter().map(|income| income.margin_of_error()).collect::<Vec<_>>())
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn read_income_by_id(&self, id: (&RegionType, &str, i32)) -> Result<Income, DomainError> {
+        let record = query_as!(
+            Income,
+            r#"
+                SELECT region_type AS "region_type: RegionType", region_id, region_name, year, median_income, margin_of_error
+                FROM incomes
+                WHERE region_type = $1 AND region_id = $2 AND year = $3
+            "#,
+            id.0 as _,
+            id.1,
+            id.2,
+        )
+        .fetch_one(self.pool())
+        .await?;
+        Ok(record)
+    }
+
+    async fn update_income(&self, income: &Income) -> Result<(), DomainError> {
+        query!(
+            r#"
+                UPDATE incomes
+                SET median_income = $1, margin_of_error = $2
+                WHERE region_type = $3 AND region_id = $4 AND year = $5
+                RETURNING region_id, year
+            "#,
+            income.median_income(),
+            income.margin_of_error(),
+            income.region_type() as _,
+            income.region_id(),
+            income.year(),
+        )
+        .fetch_one(self.pool())
+        .await?;
+        Ok(())
+    }
+
+    async fn delete_income_by_id(&self, id: (&RegionType, &str, i32)) -> Result<(), DomainError> {
+        query!(
+            r#"
+                DELETE FROM incomes
+                WHERE region_type = $1 AND region_id = $2 AND year = $3
+                RETURNING region_id, year;
+            "#,
+            id.0 as _,
+            id.1,
+            id.2,
+        )
+        .fetch_one(self.pool())
+        .await?;
+        Ok(())
+    }
+
+    async fn read_incomes_by_query(
+        &self,
+        income_query: &IncomeQuery,
+    ) -> Result<Incomes, DomainError> {
+        let query = r#"
+            SELECT * FROM incomes
+            WHERE region_type = $1
+            AND (region_id = $2 OR region_name = $2)
+            AND year >= $3
+            AND year <= $4
+            ORDER BY region_id, year
+        "#;
+        let incomes: Vec<Income> = query_as(query)
+            .bind(income_query.region_type())
+            .bind(income_query.region())
+            .bind(income_query.start_year())
+            .bind(income_query.end_year())
+            .fetch_all(self.pool())
+            .await?;
+        Ok(incomes)
+    }
+}
+
+#[async_trait]
+impl MortgageRatePersist for PostgresClient {
+    async fn create_mortgage_rate(
+        &self,
+        mortgage_rate: &MortgageRate,
+    ) -> Result<MortgageRateId, DomainError> {
+        let record = query!(
+            r#"
+                INSERT INTO mortgage_rates
+                (term, date, rate, points)
+                VALUES ($1, $2, $3, $4)
+                ON CONFLICT (term, date) DO NOTHING
+                RETURNING term AS "term: MortgageTerm", date;
+            "#,
+            mortgage_rate.term() as _,
+            mortgage_rate.date(),
+            *mortgage_rate.rate() as Option<f32>,
+            *mortgage_rate.points() as Option<f32>
+        )
+        .fetch_one(self.pool())
+        .await?;
+        Ok((record.term, record.date))
+    }
+
+    async fn create_mortgage_rates(
+        &self,
+        mortgage_rates: &[MortgageRate],
+    ) -> Result<(), DomainError> {
+        let mut tx = self.pool().begin().await?;
+        for chunk in mortgage_rates.chunks(BATCH_SIZE) {
+            query(
+                r#"
+                    INSERT INTO mortgage_rates
+                    (term, date, rate, points)
+                    SELECT * FROM UNNEST($1::mortgage_term[], $2::DATE[], $3::FLOAT4[], $4::FLOAT4[])
+                    ON CONFLICT (term, date) DO NOTHING
+                "#,
+            )
+            .bind(chunk.iter().map(|m| m.term().clone()).collect::<Vec<_>>())
+            .bind(chunk.iter().map(|m| *m.date()).collect::<Vec<_>>())
+            .bind(chunk.iter().map(|m| *m.rate()).collect::<Vec<_>>())
+            .bind(chunk.iter().map(|m| *m.points()).collect::<Vec<_>>())
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn read_mortgage_rate_by_id(
+        &self,
+        id: (&MortgageTerm, &NaiveDate),
+    ) -> Result<MortgageRate, DomainError> {
+        let record = query_as!(
+            MortgageRate,
+            r#"
+                SELECT term AS "term: MortgageTerm", date, rate, points
+                FROM mortgage_rates
+                WHERE term = $1 AND date = $2
+            "#,
+            id.0 as _,
+            id.1,
+        )
+        .fetch_one(self.pool())
+        .await?;
+        Ok(record)
+    }
+
+    async fn update_mortgage_rate(&self, mortgage_rate: &MortgageRate) -> Result<(), DomainError> {
+        query!(
+            r#"
+                UPDATE mortgage_rates
+                SET rate = $1, points = $2
+                WHERE term = $3 AND date = $4
+                RETURNING term AS "term: MortgageTerm", date
+            "#,
+            *mortgage_rate.rate() as Option<f32>,
+            *mortgage_rate.points() as Option<f32>,
+            mortgage_rate.term() as _,
+            mortgage_rate.date(),
+        )
+        .fetch_one(self.pool())
+        .await?;
+        Ok(())
+    }
+
+    async fn delete_mortgage_rate_by_id(
+        &self,
+        id: (&MortgageTerm, &NaiveDate),
+    ) -> Result<(), DomainError> {
+        query!(
+            r#"
+                DELETE FROM mortgage_rates
+                WHERE term = $1 AND date = $2
+                RETURNING term AS "term: MortgageTerm", date
+            "#,
+            id.0 as _,
+            id.1,
+        )
+        .fetch_one(self.pool())
+        .await?;
+        Ok(())
+    }
+
+    async fn read_mortgage_rates_by_query(
+        &self,
+        mortgage_rate_query: &MortgageRateQuery,
+    ) -> Result<MortgageRates, DomainError> {
+        // Resampled to the query's interval by the domain
+        let query = "SELECT term, date, rate, points FROM mortgage_rates WHERE date BETWEEN $1 \
+                     AND $2 AND ($3::mortgage_term IS NULL OR term = $3) ORDER BY date";
+
+        let mortgage_rates: MortgageRates = query_as(query)
+            .bind(mortgage_rate_query.start_date())
+            .bind(mortgage_rate_query.end_date())
+            .bind(mortgage_rate_query.term())
+            .fetch_all(self.pool())
+            .await?;
+        Ok(mortgage_rates)
+    }
+}
+
 #[async_trait]
 impl RegionPersist for PostgresClient {
     async fn create_region(&self, region: &Region) -> Result<Zipcode, DomainError> {
         let record = query!(
             r#"
                 INSERT INTO regions
-                (city, zipcode)
-                VALUES ($1, $2)
-                ON CONFLICT (zipcode) DO UPDATE
-                SET city = $1
+                (city, zipcode, state, county_fips, res_ratio, bus_ratio, tot_ratio)
+                VALUES ($1, $2, $3, $4, $5, $6, $7)
+                ON CONFLICT (zipcode, county_fips) DO UPDATE
+                SET city = $1, state = $3, res_ratio = $5, bus_ratio = $6, tot_ratio = $7
                 RETURNING zipcode;
             "#,
             region.city(),
             region.zipcode(),
+            region.state(),
+            region.county_fips(),
+            region.res_ratio(),
+            region.bus_ratio(),
+            region.tot_ratio(),
         )
         .fetch_one(self.pool())
         .await?;
@@ -144,9 +680,11 @@ impl RegionPersist for PostgresClient {
         let record = query_as!(
             Region,
             r#"
-                SELECT city, zipcode
+                SELECT city, zipcode, state, county_fips, res_ratio, bus_ratio, tot_ratio
                 FROM regions
                 WHERE zipcode = $1
+                ORDER BY tot_ratio DESC
+                LIMIT 1
             "#,
             id,
         )
@@ -168,47 +706,37 @@ impl RegionPersist for PostgresClient {
         &self,
         region_query: &RegionQuery,
     ) -> Result<Regions, DomainError> {
+        let selectors = [
+            ("city", region_query.cities().as_slice()),
+            ("zipcode", region_query.zipcodes().as_slice()),
+            ("county_fips", region_query.county_fips()),
+        ];
+
         let mut query = "SELECT * FROM regions".to_string();
-        let mut params = Vec::<&str>::new();
-        let mut where_clause = String::new();
-
-        if !region_query.cities().is_empty() {
-            let mut city_placeholders = String::new();
-            for (index, city) in region_query.cities().iter().enumerate() {
-                if index != 0 {
-                    city_placeholders.push_str(", ");
-                }
-                city_placeholders.push_str(&format!("${}", params.len() + 1));
-                params.push(city);
+        let mut params: Vec<&[String]> = Vec::new();
+        let mut selected = Vec::new();
+        for (column, values) in selectors {
+            if !values.is_empty() {
+                params.push(values);
+                selected.push(format!("{column} = ANY(${})", params.len()));
             }
-            where_clause.push_str(&format!(" city = {}", city_placeholders));
         }
 
-        if !region_query.zipcodes().is_empty() {
-            if !where_clause.is_empty() {
-                where_clause.push_str(" OR");
-            }
-            let mut zipcodes_placeholders = String::new();
-            for (index, zipcode) in region_query.zipcodes().iter().enumerate() {
-                if index != 0 {
-                    zipcodes_placeholders.push_str(", ");
-                }
-                zipcodes_placeholders.push_str(&format!("${}", params.len() + 1));
-                params.push(zipcode);
-            }
-            where_clause.push_str(&format!(" zipcode = {}", zipcodes_placeholders));
+        let mut conditions = Vec::new();
+        if !selected.is_empty() {
+            conditions.push(format!("({})", selected.join(" OR ")));
         }
-
-        if !where_clause.is_empty() {
-            query.push_str(&format!(" WHERE{}", where_clause));
+        if !region_query.states().is_empty() {
+            params.push(region_query.states());
+            conditions.push(format!("state = ANY(${})", params.len()));
         }
-        println!("{query}");
-        let mut query = query_as(&query);
-        for i in 0..region_query.cities().len() {
-            query = query.bind(region_query.cities()[i].clone());
+        if !conditions.is_empty() {
+            query.push_str(&format!(" WHERE {}", conditions.join(" AND ")));
         }
-        for i in 0..region_query.zipcodes().len() {
-            query = query.bind(region_query.zipcodes()[i].clone());
+
+        let mut query = query_as(&query);
+        for param in params {
+            query = query.bind(param);
         }
         let regions = query.fetch_all(self.pool()).await?;
 
@@ -216,17 +744,25 @@ impl RegionPersist for PostgresClient {
     }
 
     async fn delete_region_by_id(&self, id: &str) -> Result<Zipcode, DomainError> {
-        println!(
-            "Calling region delete with id: {:?} from PostgresClient.",
-            id
-        );
-        Ok(Zipcode::default())
+        let result = query!(
+            r#"
+                DELETE FROM regions
+                WHERE zipcode = $1
+            "#,
+            id,
+        )
+        .execute(self.pool())
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(sqlx::Error::RowNotFound.into());
+        }
+        Ok(id.to_string())
     }
 }
 
 #[async_trait]
 impl TYieldPersist for PostgresClient {
-    async fn create_t_yield(&self, t_yield: &TYield) -> Result<(String, NaiveDate), DomainError> {
+    async fn create_t_yield(&self, t_yield: &TYield) -> Result<TYieldId, DomainError> {
         let record = query!(
             r#"
                 INSERT INTO tyields
@@ -241,10 +777,31 @@ impl TYieldPersist for PostgresClient {
         )
         .fetch_one(self.pool())
         .await?;
-        Ok((record.term.to_string(), record.date))
+        Ok((record.term, record.date))
     }
 
-    async fn read_t_yield_by_id(&self, id: (&str, &NaiveDate)) -> Result<TYield, DomainError> {
+    async fn create_t_yields(&self, t_yields: &[TYield]) -> Result<(), DomainError> {
+        let mut tx = self.pool().begin().await?;
+        for chunk in t_yields.chunks(BATCH_SIZE) {
+            query(
+                r#"
+                    INSERT INTO tyields
+                    (term, date, yield_return)
+                    SELECT * FROM UNNEST($1::term[], $2::DATE[], $3::FLOAT4[])
+                    ON CONFLICT (term, date) DO NOTHING
+                "#,
+            )
+            .bind(chunk.iter().map(|t| t.term().clone()).collect::<Vec<_>>())
+            .bind(chunk.iter().map(|t| *t.date()).collect::<Vec<_>>())
+            .bind(chunk.iter().map(|t| *t.yield_return()).collect::<Vec<_>>())
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn read_t_yield_by_id(&self, id: (&Term, &NaiveDate)) -> Result<TYield, DomainError> {
         let record = query_as!(
             TYield,
             r#"
@@ -276,7 +833,7 @@ impl TYieldPersist for PostgresClient {
         Ok(())
     }
 
-    async fn delete_t_yield_by_id(&self, id: (&str, &NaiveDate)) -> Result<(), DomainError> {
+    async fn delete_t_yield_by_id(&self, id: (&Term, &NaiveDate)) -> Result<(), DomainError> {
         query!(
             r#"
                 DELETE FROM tyields
@@ -294,27 +851,14 @@ impl TYieldPersist for PostgresClient {
         &self,
         t_yield_query: &TYieldQuery,
     ) -> Result<TYields, DomainError> {
-        let query = match t_yield_query.date_interval() {
-            DateInterval::Day => {
-                "SELECT term, CAST(date AS DATE) AS date, CAST(AVG(yield_return) AS FLOAT4) AS \
-                 yield_return FROM tyields WHERE date BETWEEN $1 AND $2 GROUP BY term, date ORDER \
-                 BY date"
-            }
-            DateInterval::Month => {
-                "SELECT term, CAST(DATE_TRUNC('month', date) AS DATE) AS date, \
-                 CAST(AVG(yield_return) AS FLOAT4) AS yield_return FROM tyields WHERE date BETWEEN \
-                 $1 AND $2 GROUP BY term, DATE_TRUNC('month', date) ORDER BY date"
-            }
-            DateInterval::Year => {
-                "SELECT term, CAST(DATE_TRUNC('year', date) AS DATE) AS date, \
-                 CAST(AVG(yield_return) AS FLOAT4) AS yield_return FROM tyields WHERE date BETWEEN \
-                 $1 AND $2 GROUP BY term, DATE_TRUNC('year', date) ORDER BY date"
-            }
-        };
+        // Resampled to the query's interval by the domain
+        let query = "SELECT term, date, yield_return FROM tyields WHERE date BETWEEN $1 AND $2 \
+                     AND ($3::term IS NULL OR term = $3) ORDER BY date";
 
         let yields: TYields = query_as(query)
             .bind(t_yield_query.start_date())
             .bind(t_yield_query.end_date())
+            .bind(t_yield_query.term())
             .fetch_all(self.pool())
             .await?;
         Ok(yields)
@@ -327,6 +871,7 @@ struct ZhviMetadataPgRow {
     region_type: RegionType,
     region_name: String,
     percentile: Percentile,
+    state: Option<String>,
 }
 #[allow(dead_code)]
 #[derive(FromRow)]
@@ -348,7 +893,11 @@ impl TryFrom<ZhviPricePgRow> for ZhviPrice {
             .date
             .ok_or(DomainError::Database("Date Not Found".to_string()))?;
         let value = row.value;
-        Ok(Self { date, value })
+        Ok(Self {
+            date,
+            value,
+            filled: false,
+        })
     }
 }
 
@@ -361,16 +910,18 @@ impl ZhviPersist for PostgresClient {
         let region_type = zhvi.region_type();
         let region_name = zhvi.region_name();
         let percentile = zhvi.percentile();
+        let state = zhvi.state().unwrap_or_default();
         query!(
             r#"
                 INSERT INTO zhvi_metadata
-                (region_name, region_type, home_type, percentile)
-                VALUES ($1, $2, $3, $4)
+                (region_name, region_type, home_type, percentile, state)
+                VALUES ($1, $2, $3, $4, $5)
             "#,
             region_name,
             region_type as _,
             home_type as _,
-            percentile as _
+            percentile as _,
+            state
         )
         .execute(&mut *tx)
         .await?;
@@ -379,13 +930,14 @@ impl ZhviPersist for PostgresClient {
             query!(
                 r#"
                     INSERT INTO zhvi_prices
-                    (region_name, region_type, home_type, percentile, date, value)
-                    VALUES ($1, $2, $3, $4, $5, $6)
+                    (region_name, region_type, home_type, percentile, state, date, value)
+                    VALUES ($1, $2, $3, $4, $5, $6, $7)
                 "#,
                 region_name,
                 region_type as _,
                 home_type as _,
                 percentile as _,
+                state,
                 price.date as _,
                 price.value as _
             )
@@ -397,19 +949,72 @@ impl ZhviPersist for PostgresClient {
         Ok(())
     }
 
-    async fn read_zhvi_by_id(&self, id: (&str, &str, &str, &str)) -> Result<Zhvi, DomainError> {
+    async fn create_zhvis(&self, zhvis: &[Zhvi]) -> Result<(), DomainError> {
+        let mut tx = self.pool().begin().await?;
+
+        for chunk in zhvis.chunks(BATCH_SIZE) {
+            query(
+                r#"
+                    INSERT INTO zhvi_metadata
+                    (region_name, region_type, home_type, percentile, state)
+                    SELECT * FROM UNNEST($1::TEXT[], $2::region_type[], $3::home_type[], $4::percentile[], $5::TEXT[])
+                    ON CONFLICT DO NOTHING
+                "#,
+            )
+            .bind(chunk.iter().map(|z| z.region_name().to_string()).collect::<Vec<_>>())
+            .bind(chunk.iter().map(|z| z.region_type().clone()).collect::<Vec<_>>())
+            .bind(chunk.iter().map(|z| z.home_type().clone()).collect::<Vec<_>>())
+            .bind(chunk.iter().map(|z| z.percentile().clone()).collect::<Vec<_>>())
+            .bind(chunk.iter().map(|z| z.state().unwrap_or_default()).collect::<Vec<_>>())
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        // Flatten every price with its Zhvi key so rows can be chunked evenly
+        let rows: Vec<(&Zhvi, &ZhviPrice)> = zhvis
+            .iter()
+            .flat_map(|zhvi| zhvi.prices().iter().map(move |price| (zhvi, price)))
+            .collect();
+        for chunk in rows.chunks(BATCH_SIZE) {
+            query(
+                r#"
+                    INSERT INTO zhvi_prices
+                    (region_name, region_type, home_type, percentile, state, date, value)
+                    SELECT * FROM UNNEST($1::TEXT[], $2::region_type[], $3::home_type[], $4::percentile[], $5::TEXT[], $6::DATE[], $7::FLOAT8[])
+                    ON CONFLICT DO NOTHING
+                "#,
+            )
+            .bind(chunk.iter().map(|(z, _)| z.region_name().to_string()).collect::<Vec<_>>())
+            .bind(chunk.iter().map(|(z, _)| z.region_type().clone()).collect::<Vec<_>>())
+            .bind(chunk.iter().map(|(z, _)| z.home_type().clone()).collect::<Vec<_>>())
+            .bind(chunk.iter().map(|(z, _)| z.percentile().clone()).collect::<Vec<_>>())
+            .bind(chunk.iter().map(|(z, _)| z.state().unwrap_or_default()).collect::<Vec<_>>())
+            .bind(chunk.iter().map(|(_, p)| p.date).collect::<Vec<_>>())
+            .bind(chunk.iter().map(|(_, p)| p.value).collect::<Vec<_>>())
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn read_zhvi_by_id(&self, id: ZhviId<'_>) -> Result<Zhvi, DomainError> {
         let mut tx = self.pool().begin().await?;
+        let state = id.4.unwrap_or_default();
         let metadata = query_as!(
             ZhviMetadataPgRow,
             r#"
-                SELECT region_name, region_type AS "region_type: RegionType", home_type AS "home_type: HomeType", percentile AS "percentile: Percentile"
+                SELECT region_name, region_type AS "region_type: RegionType", home_type AS "home_type: HomeType", percentile AS "percentile: Percentile", NULLIF(state, '') AS state
                 FROM zhvi_metadata
-                WHERE region_name = $1 AND region_type = $2 AND home_type = $3 AND percentile = $4
+                WHERE region_name = $1 AND region_type = $2 AND home_type = $3 AND percentile = $4 AND state = $5
             "#,
             id.0,
             id.1 as _,
             id.2 as _,
             id.3 as _,
+            state,
         )
         .fetch_one(&mut *tx)
         .await?;
@@ -419,12 +1024,13 @@ impl ZhviPersist for PostgresClient {
             r#"
                 SELECT region_name, region_type AS "region_type: RegionType", home_type AS "home_type: HomeType", percentile AS "percentile: Percentile", date, value
                 FROM zhvi_prices
-                WHERE region_name = $1 AND region_type = $2 AND home_type = $3 AND percentile = $4
+                WHERE region_name = $1 AND region_type = $2 AND home_type = $3 AND percentile = $4 AND state = $5
             "#,
             id.0,
             id.1 as _,
             id.2 as _,
             id.3 as _,
+            state,
         )
         .fetch_all(&mut *tx)
         .await?
@@ -440,6 +1046,7 @@ impl ZhviPersist for PostgresClient {
             home_type: metadata.home_type,
             region_type: metadata.region_type,
             region_name: metadata.region_name,
+            state: metadata.state,
             percentile: metadata.percentile,
             prices,
         };
@@ -453,17 +1060,19 @@ impl ZhviPersist for PostgresClient {
         let region_type = zhvi.region_type();
         let region_name = zhvi.region_name();
         let percentile = zhvi.percentile();
+        let state = zhvi.state().unwrap_or_default();
 
         // TODO: Should be updating price(s), not a key
         query!(
             r#"
                 UPDATE zhvi_metadata
-                SET percentile = $1 WHERE home_type = $2 AND region_type = $3 AND region_name = $4
+                SET percentile = $1 WHERE home_type = $2 AND region_type = $3 AND region_name = $4 AND state = $5
             "#,
             percentile as _,
             home_type as _,
             region_type as _,
-            region_name
+            region_name,
+            state
         )
         .execute(&mut *tx)
         .await?;
@@ -472,12 +1081,13 @@ impl ZhviPersist for PostgresClient {
         query!(
             r#"
                 DELETE FROM zhvi_prices
-                WHERE region_name = $1 AND region_type = $2 AND home_type = $3 AND percentile = $4
+                WHERE region_name = $1 AND region_type = $2 AND home_type = $3 AND percentile = $4 AND state = $5
             "#,
             region_name,
             region_type as _,
             home_type as _,
-            percentile as _
+            percentile as _,
+            state
         )
         .execute(&mut *tx)
         .await?;
@@ -487,13 +1097,14 @@ impl ZhviPersist for PostgresClient {
             query!(
                 r#"
                     INSERT INTO zhvi_prices
-                    (region_name, region_type, home_type, percentile, date, value)
-                    VALUES ($1, $2, $3, $4, $5, $6)
+                    (region_name, region_type, home_type, percentile, state, date, value)
+                    VALUES ($1, $2, $3, $4, $5, $6, $7)
                 "#,
                 region_name,
                 region_type as _,
                 home_type as _,
                 percentile as _,
+                state,
                 &price.date as _,
                 &price.value as _
             )
@@ -506,18 +1117,20 @@ impl ZhviPersist for PostgresClient {
         Ok(())
     }
 
-    async fn delete_zhvi_by_id(&self, id: (&str, &str, &str, &str)) -> Result<(), DomainError> {
+    async fn delete_zhvi_by_id(&self, id: ZhviId<'_>) -> Result<(), DomainError> {
         let mut tx = self.pool().begin().await?;
+        let state = id.4.unwrap_or_default();
 
         query!(
             r#"
                 DELETE FROM zhvi_prices
-                WHERE region_name = $1 AND region_type = $2 AND home_type = $3 AND percentile = $4
+                WHERE region_name = $1 AND region_type = $2 AND home_type = $3 AND percentile = $4 AND state = $5
             "#,
             id.0,
             id.1 as _,
             id.2 as _,
             id.3 as _,
+            state,
         )
         .execute(&mut *tx)
         .await?;
@@ -525,12 +1138,13 @@ impl ZhviPersist for PostgresClient {
         query!(
             r#"
                 DELETE FROM zhvi_metadata
-                WHERE region_name = $1 AND region_type = $2 AND home_type = $3 AND percentile = $4
+                WHERE region_name = $1 AND region_type = $2 AND home_type = $3 AND percentile = $4 AND state = $5
             "#,
             id.0,
             id.1 as _,
             id.2 as _,
             id.3 as _,
+            state,
         )
         .execute(&mut *tx)
         .await?;
@@ -546,71 +1160,49 @@ impl ZhviPersist for PostgresClient {
         let metadata = query_as!(
             ZhviMetadataPgRow,
             r#"
-                SELECT region_name, region_type AS "region_type: RegionType", home_type AS "home_type: HomeType", percentile AS "percentile: Percentile"
+                SELECT region_name, region_type AS "region_type: RegionType", home_type AS "home_type: HomeType", percentile AS "percentile: Percentile", NULLIF(state, '') AS state
                 FROM zhvi_metadata
                 WHERE region_name = $1 AND region_type = $2 AND home_type = $3 AND percentile = $4
+                AND ($5::TEXT IS NULL OR state = $5)
             "#,
             query.region_name(),
             query.region_type() as _,
             query.home_type() as _,
             query.percentile() as _,
+            query.state(),
         )
         .fetch_all(&mut *tx)
         .await?;
 
         let mut zhvis = vec![];
         for metadata in metadata {
-            let prices = if query.date_interval() == &DateInterval::Month {
-                sqlx::query_as!(
-                    ZhviPricePgRow,
-                    r#"
-                        SELECT region_name, region_type AS "region_type: RegionType", home_type AS "home_type: HomeType", percentile AS "percentile: Percentile", date, value
-                        FROM zhvi_prices
-                        WHERE region_name = $1 AND region_type = $2 AND home_type = $3 AND percentile = $4
-                        AND date >= $5 AND date <= $6
-                    "#,
-                    query.region_name(),
-                    query.region_type() as _,
-                    query.home_type() as _,
-                    query.percentile() as _,
-                    query.start_date(),
-                    query.end_date(),
-                )
-                .fetch_all(&mut *tx)
-                .await?
-                .into_iter()
-                .map(ZhviPrice::try_from)
-                .collect::<Result<ZhviPrices, DomainError>>()?
-            } else if query.date_interval() == &DateInterval::Year {
-                query_as!(
-                    ZhviPricePgRow,
-                    r#"
-                        SELECT region_name, region_type AS "region_type: RegionType", home_type AS "home_type: HomeType", percentile AS "percentile: Percentile", date, value
-                        FROM zhvi_prices
-                        WHERE region_name = $1 AND region_type = $2 AND home_type = $3 AND percentile = $4
-                        AND date >= $5 AND date <= $6
-                        AND EXTRACT(MONTH FROM date) = 1
-                    "#,
-                    query.region_name(),
-                    query.region_type()as _,
-                    query.home_type() as _,
-                    query.percentile() as _,
-                    query.start_date(),
-                    query.end_date(),
-                )
-                .fetch_all(&mut *tx)
-                .await?
-                .into_iter()
-                .map(ZhviPrice::try_from)
-                .collect::<Result<ZhviPrices, DomainError>>()?
-            } else {
-                return Err(DomainError::Parse("Prices not found".to_string()));
-            };
+            let prices = sqlx::query_as!(
+                ZhviPricePgRow,
+                r#"
+                    SELECT region_name, region_type AS "region_type: RegionType", home_type AS "home_type: HomeType", percentile AS "percentile: Percentile", date, value
+                    FROM zhvi_prices
+                    WHERE region_name = $1 AND region_type = $2 AND home_type = $3 AND percentile = $4
+                    AND state = $5 AND date >= $6 AND date <= $7
+                "#,
+                query.region_name(),
+                query.region_type() as _,
+                query.home_type() as _,
+                query.percentile() as _,
+                metadata.state.as_deref().unwrap_or_default(),
+                query.start_date(),
+                query.end_date(),
+            )
+            .fetch_all(&mut *tx)
+            .await?
+            .into_iter()
+            .map(ZhviPrice::try_from)
+            .collect::<Result<ZhviPrices, DomainError>>()?;
 
             let zhvi = Zhvi {
                 home_type: metadata.home_type,
                 region_type: metadata.region_type,
                 region_name: metadata.region_name,
+                state: metadata.state,
                 percentile: metadata.percentile,
                 prices,
             };
@@ -0,0 +1,984 @@
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+use sqlx::{query, query_as, FromRow, Pool, Sqlite};
+
+use crate::adapter::repository::{Config, Persist};
+use crate::domain::common::RegionType;
+use crate::domain::cpi::*;
+use crate::domain::hpi::*;
+use crate::domain::income::*;
+use crate::domain::mortgage_rate::*;
+use crate::domain::region::{Region, RegionPersist, RegionQuery, Regions, Zipcode};
+use crate::domain::t_yield::*;
+use crate::domain::zhvi::*;
+use crate::error::DomainError;
+
+pub struct SqliteClient {
+    pool: Pool<Sqlite>,
+}
+
+impl SqliteClient {
+    pub async fn new(_config: &Config) -> Result<Self, DomainError> {
+        SqliteClient::connect(&std::env::var("DATABASE_URL")?).await
+    }
+
+    pub async fn connect(database_url: &str) -> Result<Self, DomainError> {
+        let options = SqliteConnectOptions::from_str(database_url)?.create_if_missing(true);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(5)
+            .connect_with(options)
+            .await?;
+        sqlx::migrate!("./migrations/sqlite")
+            .run(&pool)
+            .await
+            .map_err(|e| DomainError::Database(format!("Failed to migrate: {}", e)))?;
+        Ok(SqliteClient { pool })
+    }
+
+    fn pool(&self) -> &Pool<Sqlite> {
+        &self.pool
+    }
+}
+
+impl Persist for SqliteClient {}
+
+#[async_trait]
+impl CpiPersist for SqliteClient {
+    async fn create_cpi(&self, cpi: &Cpi) -> Result<CpiId, DomainError> {
+        let (date,) = query_as(
+            r#"
+                INSERT INTO cpis
+                (date, value)
+                VALUES ($1, $2)
+                ON CONFLICT (date) DO NOTHING
+                RETURNING date;
+            "#,
+        )
+        .bind(cpi.date())
+        .bind(cpi.value())
+        .fetch_one(self.pool())
+        .await?;
+        Ok(date)
+    }
+
+    async fn create_cpis(&self, cpis: &[Cpi]) -> Result<(), DomainError> {
+        let mut tx = self.pool().begin().await?;
+        for cpi in cpis {
+            query(
+                r#"
+                    INSERT INTO cpis
+                    (date, value)
+                    VALUES ($1, $2)
+                    ON CONFLICT (date) DO NOTHING
+                "#,
+            )
+            .bind(cpi.date())
+            .bind(cpi.value())
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn read_cpi_by_id(&self, id: &NaiveDate) -> Result<Cpi, DomainError> {
+        let record = query_as(
+            r#"
+                SELECT date, value
+                FROM cpis
+                WHERE date = $1
+            "#,
+        )
+        .bind(id)
+        .fetch_one(self.pool())
+        .await?;
+        Ok(record)
+    }
+
+    async fn update_cpi(&self, cpi: &Cpi) -> Result<(), DomainError> {
+        query(
+            r#"
+                UPDATE cpis
+                SET value = $1
+                WHERE date = $2
+                RETURNING date
+            "#,
+        )
+        .bind(cpi.value())
+        .bind(cpi.date())
+        .fetch_one(self.pool())
+        .await?;
+        Ok(())
+    }
+
+    async fn delete_cpi_by_id(&self, id: &NaiveDate) -> Result<(), DomainError> {
+        query(
+            r#"
+                DELETE FROM cpis
+                WHERE date = $1
+                RETURNING date;
+            "#,
+        )
+        .bind(id)
+        .fetch_one(self.pool())
+        .await?;
+        Ok(())
+    }
+
+    async fn read_cpis_by_query(&self, cpi_query: &CpiQuery) -> Result<Cpis, DomainError> {
+        let query = r#"
+            SELECT date, value FROM cpis
+            WHERE date BETWEEN $1 AND $2
+            ORDER BY date
+        "#;
+        let cpis: Cpis = query_as(query)
+            .bind(cpi_query.start_date())
+            .bind(cpi_query.end_date())
+            .fetch_all(self.pool())
+            .await?;
+        Ok(cpis)
+    }
+}
+
+#[async_trait]
+impl HpiPersist for SqliteClient {
+    async fn create_hpi(&self, hpi: &Hpi) -> Result<HpiId, DomainError> {
+        let id = query_as(
+            r#"
+                INSERT INTO hpis
+                (region_type, region_id, region_name, state, year, hpi, annual_change, hpi_1990_base, hpi_2000_base)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                ON CONFLICT (region_type, region_id, year) DO NOTHING
+                RETURNING region_type, region_id, year;
+            "#,
+        )
+        .bind(hpi.region_type())
+        .bind(hpi.region_id())
+        .bind(hpi.region_name())
+        .bind(hpi.state())
+        .bind(hpi.year())
+        .bind(hpi.hpi())
+        .bind(hpi.annual_change())
+        .bind(hpi.hpi_1990_base())
+        .bind(hpi.hpi_2000_base())
+        .fetch_one(self.pool())
+        .await?;
+        Ok(id)
+    }
+
+    async fn create_hpis(&self, hpis: &[Hpi]) -> Result<(), DomainError> {
+        let mut tx = self.pool().begin().await?;
+        for hpi in hpis {
+            query(
+                r#"
+                    INSERT INTO hpis
+                    (region_type, region_id, region_name, state, year, hpi, annual_change, hpi_1990_base, hpi_2000_base)
+                    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                    ON CONFLICT (region_type, region_id, year) DO NOTHING
+                "#,
+            )
+            .bind(hpi.region_type())
+            .bind(hpi.region_id())
+            .bind(hpi.region_name())
+            .bind(hpi.state())
+            .bind(hpi.year())
+            .bind(hpi.hpi())
+            .bind(hpi.annual_change())
+            .bind(hpi.hpi_1990_base())
+            .bind(hpi.hpi_2000_base())
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn read_hpi_by_id(&self, id: (&RegionType, &str, i32)) -> Result<Hpi, DomainError> {
+        let record = query_as(
+            r#"
+                SELECT region_type, region_id, region_name, state, year, hpi, annual_change, hpi_1990_base, hpi_2000_base
+                FROM hpis
+                WHERE region_type = $1 AND region_id = $2 AND year = $3
+            "#,
+        )
+        .bind(id.0)
+        .bind(id.1)
+        .bind(id.2)
+        .fetch_one(self.pool())
+        .await?;
+        Ok(record)
+    }
+
+    async fn update_hpi(&self, hpi: &Hpi) -> Result<(), DomainError> {
+        query(
+            r#"
+                UPDATE hpis
+                SET hpi = $1
+                WHERE region_type = $2 AND region_id = $3 AND year = $4
+                RETURNING region_id, year
+            "#,
+        )
+        .bind(hpi.hpi())
+        .bind(hpi.region_type())
+        .bind(hpi.region_id())
+        .bind(hpi.year())
+        .fetch_one(self.pool())
+        .await?;
+        Ok(())
+    }
+
+    async fn delete_hpi_by_id(&self, id: (&RegionType, &str, i32)) -> Result<(), DomainError> {
+        query(
+            r#"
+                DELETE FROM hpis
+                WHERE region_type = $1 AND region_id = $2 AND year = $3
+                RETURNING region_id, year;
+            "#,
+        )
+        .bind(id.0)
+        .bind(id.1)
+        .bind(id.2)
+        .fetch_one(self.pool())
+        .await?;
+        Ok(())
+    }
+
+    async fn read_hpi_by_query(&self, hpi_query: &HpiQuery) -> Result<Hpis, DomainError> {
+        let query = r#"
+            SELECT * FROM hpis
+            WHERE region_type = $1
+            AND region_id = $2
+            AND year >= $3
+            AND year <= $4
+        "#;
+        let hpis: Vec<Hpi> = query_as(query)
+            .bind(hpi_query.region_type())
+            .bind(hpi_query.region_id())
+            .bind(hpi_query.start_date())
+            .bind(hpi_query.end_date())
+            .fetch_all(self.pool())
+            .await?;
+        Ok(hpis)
+    }
+}
+
+#[async_trait]
+impl IncomePersist for SqliteClient {
+    async fn create_income(&self, income: &Income) -> Result<IncomeId, DomainError> {
+        let id = query_as(
+            r#"
+                INSERT INTO incomes
+                (region_type, region_id, region_name, year, median_income, margin_of_error)
+                VALUES ($1, $2, $3, $4, $5, $6)
+                ON CONFLICT (region_type, region_id, year) DO NOTHING
+                RETURNING region_type, region_id, year;
+            "#,
+        )
+        .bind(income.region_type())
+        .bind(income.region_id())
+        .bind(income.region_name())
+        .bind(income.year())
+        .bind(income.median_income())
+        .bind(income.margin_of_error())
+        .fetch_one(self.pool())
+        .await?;
+        Ok(id)
+    }
+
+    async fn create_incomes(&self, incomes: &[Income]) -> Result<(), DomainError> {
+        let mut tx = self.pool().begin().await?;
+        for income in incomes {
+            query(
+                r#"
+                    INSERT INTO incomes
+                    (region_type, region_id, region_name, year, median_income, margin_of_error)
+                    VALUES ($1, $2, $3, $4, $5, $6)
+                    ON CONFLICT (region_type, region_id, year) DO NOTHING
+                "#,
+            )
+            .bind(income.region_type())
+            .bind(income.region_id())
+            .bind(income.region_name())
+            .bind(income.year())
+            .bind(income.median_income())
+            .bind(income.margin_of_error())
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn read_income_by_id(&self, id: (&RegionType, &str, i32)) -> Result<Income, DomainError> {
+        let record = query_as(
+            r#"
+                SELECT region_type, region_id, region_name, year, median_income, margin_of_error
+                FROM incomes
+                WHERE region_type = $1 AND region_id = $2 AND year = $3
+            "#,
+        )
+        .bind(id.0)
+        .bind(id.1)
+        .bind(id.2)
+        .fetch_one(self.pool())
+        .await?;
+        Ok(record)
+    }
+
+    async fn update_income(&self, income: &Income) -> Result<(), DomainError> {
+        query(
+            r#"
+                UPDATE incomes
+                SET median_income = $1, margin_of_error = $2
+                WHERE region_type = $3 AND region_id = $4 AND year = $5
+                RETURNING region_id, year
+            "#,
+        )
+        .bind(income.median_income())
+        .bind(income.margin_of_error())
+        .bind(income.region_type())
+        .bind(income.region_id())
+        .bind(income.year())
+        .fetch_one(self.pool())
+        .await?;
+        Ok(())
+    }
+
+    async fn delete_income_by_id(&self, id: (&RegionType, &str, i32)) -> Result<(), DomainError> {
+        query(
+            r#"
+                DELETE FROM incomes
+                WHERE region_type = $1 AND region_id = $2 AND year = $3
+                RETURNING region_id, year;
+            "#,
+        )
+        .bind(id.0)
+        .bind(id.1)
+        .bind(id.2)
+        .fetch_one(self.pool())
+        .await?;
+        Ok(())
+    }
+
+    async fn read_incomes_by_query(
+        &self,
+        income_query: &IncomeQuery,
+    ) -> Result<Incomes, DomainError> {
+        let query = r#"
+            SELECT * FROM incomes
+            WHERE region_type = $1
+            AND (region_id = $2 OR region_name = $2)
+            AND year >= $3
+            AND year <= $4
+            ORDER BY region_id, year
+        "#;
+        let incomes: Vec<Income> = query_as(query)
+            .bind(income_query.region_type())
+            .bind(income_query.region())
+            .bind(income_query.start_year())
+            .bind(income_query.end_year())
+            .fetch_all(self.pool())
+            .await?;
+        Ok(incomes)
+    }
+}
+
+#[async_trait]
+impl MortgageRatePersist for SqliteClient {
+    async fn create_mortgage_rate(
+        &self,
+        mortgage_rate: &MortgageRate,
+    ) -> Result<MortgageRateId, DomainError> {
+        let id: MortgageRateId = query_as(
+            r#"
+                INSERT INTO mortgage_rates
+                (term, date, rate, points)
+                VALUES ($1, $2, $3, $4)
+                ON CONFLICT (term, date) DO NOTHING
+                RETURNING term, date;
+            "#,
+        )
+        .bind(mortgage_rate.term())
+        .bind(mortgage_rate.date())
+        .bind(mortgage_rate.rate())
+        .bind(mortgage_rate.points())
+        .fetch_one(self.pool())
+        .await?;
+        Ok(id)
+    }
+
+    async fn create_mortgage_rates(
+        &self,
+        mortgage_rates: &[MortgageRate],
+    ) -> Result<(), DomainError> {
+        let mut tx = self.pool().begin().await?;
+        for mortgage_rate in mortgage_rates {
+            query(
+                r#"
+                    INSERT INTO mortgage_rates
+                    (term, date, rate, points)
+                    VALUES ($1, $2, $3, $4)
+                    ON CONFLICT (term, date) DO NOTHING
+                "#,
+            )
+            .bind(mortgage_rate.term())
+            .bind(mortgage_rate.date())
+            .bind(mortgage_rate.rate())
+            .bind(mortgage_rate.points())
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn read_mortgage_rate_by_id(
+        &self,
+        id: (&MortgageTerm, &NaiveDate),
+    ) -> Result<MortgageRate, DomainError> {
+        let record = query_as(
+            r#"
+                SELECT term, date, rate, points
+                FROM mortgage_rates
+                WHERE term = $1 AND date = $2
+            "#,
+        )
+        .bind(id.0)
+        .bind(id.1)
+        .fetch_one(self.pool())
+        .await?;
+        Ok(record)
+    }
+
+    async fn update_mortgage_rate(&self, mortgage_rate: &MortgageRate) -> Result<(), DomainError> {
+        query(
+            r#"
+                UPDATE mortgage_rates
+                SET rate = $1, points = $2
+                WHERE term = $3 AND date = $4
+                RETURNING term, date
+            "#,
+        )
+        .bind(mortgage_rate.rate())
+        .bind(mortgage_rate.points())
+        .bind(mortgage_rate.term())
+        .bind(mortgage_rate.date())
+        .fetch_one(self.pool())
+        .await?;
+        Ok(())
+    }
+
+    async fn delete_mortgage_rate_by_id(
+        &self,
+        id: (&MortgageTerm, &NaiveDate),
+    ) -> Result<(), DomainError> {
+        query(
+            r#"
+                DELETE FROM mortgage_rates
+                WHERE term = $1 AND date = $2
+                RETURNING term, date
+            "#,
+        )
+        .bind(id.0)
+        .bind(id.1)
+        .fetch_one(self.pool())
+        .await?;
+        Ok(())
+    }
+
+    async fn read_mortgage_rates_by_query(
+        &self,
+        mortgage_rate_query: &MortgageRateQuery,
+    ) -> Result<MortgageRates, DomainError> {
+        // Resampled to the query's interval by the domain
+        let query = "SELECT term, date, rate, points FROM mortgage_rates WHERE date BETWEEN $1 \
+                     AND $2 AND ($3 IS NULL OR term = $3) ORDER BY date";
+
+        let mortgage_rates: MortgageRates = query_as(query)
+            .bind(mortgage_rate_query.start_date())
+            .bind(mortgage_rate_query.end_date())
+            .bind(mortgage_rate_query.term())
+            .fetch_all(self.pool())
+            .await?;
+        Ok(mortgage_rates)
+    }
+}
+
+#[async_trait]
+impl RegionPersist for SqliteClient {
+    async fn create_region(&self, region: &Region) -> Result<Zipcode, DomainError> {
+        let (zipcode,) = query_as(
+            r#"
+                INSERT INTO regions
+                (city, zipcode, state, county_fips, res_ratio, bus_ratio, tot_ratio)
+                VALUES ($1, $2, $3, $4, $5, $6, $7)
+                ON CONFLICT (zipcode, county_fips) DO UPDATE
+                SET city = $1, state = $3, res_ratio = $5, bus_ratio = $6, tot_ratio = $7
+                RETURNING zipcode;
+            "#,
+        )
+        .bind(region.city())
+        .bind(region.zipcode())
+        .bind(region.state())
+        .bind(region.county_fips())
+        .bind(region.res_ratio())
+        .bind(region.bus_ratio())
+        .bind(region.tot_ratio())
+        .fetch_one(self.pool())
+        .await?;
+        Ok(zipcode)
+    }
+
+    async fn read_region_by_id(&self, id: &str) -> Result<Region, DomainError> {
+        let record = query_as(
+            r#"
+                SELECT city, zipcode, state, county_fips, res_ratio, bus_ratio, tot_ratio
+                FROM regions
+                WHERE zipcode = $1
+                ORDER BY tot_ratio DESC
+                LIMIT 1
+            "#,
+        )
+        .bind(id)
+        .fetch_one(self.pool())
+        .await?;
+        Ok(record)
+    }
+
+    async fn read_regions_by_city(&self, id: &str) -> Result<Regions, DomainError> {
+        let query = r#"
+            SELECT * FROM regions
+            WHERE city = $1
+        "#;
+        let regions = query_as(query).bind(id).fetch_all(self.pool()).await?;
+        Ok(regions)
+    }
+
+    async fn read_regions_by_query(
+        &self,
+        region_query: &RegionQuery,
+    ) -> Result<Regions, DomainError> {
+        let selectors = [
+            ("city", region_query.cities().as_slice()),
+            ("zipcode", region_query.zipcodes().as_slice()),
+            ("county_fips", region_query.county_fips()),
+        ];
+
+        let mut query = "SELECT * FROM regions".to_string();
+        let mut params: Vec<&String> = vec![];
+        let mut selected = vec![];
+        for (column, values) in selectors {
+            if !values.is_empty() {
+                let placeholders = vec!["?"; values.len()].join(", ");
+                selected.push(format!("{} IN ({})", column, placeholders));
+                params.extend(values);
+            }
+        }
+
+        let mut conditions = vec![];
+        if !selected.is_empty() {
+            conditions.push(format!("({})", selected.join(" OR ")));
+        }
+        if !region_query.states().is_empty() {
+            let placeholders = vec!["?"; region_query.states().len()].join(", ");
+            conditions.push(format!("state IN ({})", placeholders));
+            params.extend(region_query.states());
+        }
+        if !conditions.is_empty() {
+            query.push_str(&format!(" WHERE {}", conditions.join(" AND ")));
+        }
+
+        let mut query = query_as(&query);
+        for param in params {
+            query = query.bind(param);
+        }
+        let regions = query.fetch_all(self.pool()).await?;
+
+        Ok(regions)
+    }
+
+    async fn delete_region_by_id(&self, id: &str) -> Result<Zipcode, DomainError> {
+        let result = query(
+            r#"
+                DELETE FROM regions
+                WHERE zipcode = $1
+            "#,
+        )
+        .bind(id)
+        .execute(self.pool())
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(sqlx::Error::RowNotFound.into());
+        }
+        Ok(id.to_string())
+    }
+}
+
+#[async_trait]
+impl TYieldPersist for SqliteClient {
+    async fn create_t_yield(&self, t_yield: &TYield) -> Result<TYieldId, DomainError> {
+        let id: TYieldId = query_as(
+            r#"
+                INSERT INTO tyields
+                (term, date, yield_return)
+                VALUES ($1, $2, $3)
+                ON CONFLICT (term, date) DO NOTHING
+                RETURNING term, date;
+            "#,
+        )
+        .bind(t_yield.term())
+        .bind(t_yield.date())
+        .bind(t_yield.yield_return())
+        .fetch_one(self.pool())
+        .await?;
+        Ok(id)
+    }
+
+    async fn create_t_yields(&self, t_yields: &[TYield]) -> Result<(), DomainError> {
+        let mut tx = self.pool().begin().await?;
+        for t_yield in t_yields {
+            query(
+                r#"
+                    INSERT INTO tyields
+                    (term, date, yield_return)
+                    VALUES ($1, $2, $3)
+                    ON CONFLICT (term, date) DO NOTHING
+                "#,
+            )
+            .bind(t_yield.term())
+            .bind(t_yield.date())
+            .bind(t_yield.yield_return())
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn read_t_yield_by_id(&self, id: (&Term, &NaiveDate)) -> Result<TYield, DomainError> {
+        let record = query_as(
+            r#"
+                SELECT term, date, yield_return
+                FROM tyields
+                WHERE term = $1 AND date = $2
+            "#,
+        )
+        .bind(id.0)
+        .bind(id.1)
+        .fetch_one(self.pool())
+        .await?;
+        Ok(record)
+    }
+
+    async fn update_t_yield(&self, t_yield: &TYield) -> Result<(), DomainError> {
+        query(
+            r#"
+                UPDATE tyields
+                SET yield_return = $1
+                WHERE term = $2 AND date = $3
+                RETURNING term, date
+            "#,
+        )
+        .bind(t_yield.yield_return())
+        .bind(t_yield.term())
+        .bind(t_yield.date())
+        .fetch_one(self.pool())
+        .await?;
+        Ok(())
+    }
+
+    async fn delete_t_yield_by_id(&self, id: (&Term, &NaiveDate)) -> Result<(), DomainError> {
+        query(
+            r#"
+                DELETE FROM tyields
+                WHERE term = $1 AND date = $2
+                RETURNING term, date
+            "#,
+        )
+        .bind(id.0)
+        .bind(id.1)
+        .fetch_one(self.pool())
+        .await?;
+        Ok(())
+    }
+
+    async fn read_t_yields_by_query(
+        &self,
+        t_yield_query: &TYieldQuery,
+    ) -> Result<TYields, DomainError> {
+        // Resampled to the query's interval by the domain
+        let query = "SELECT term, date, yield_return FROM tyields WHERE date BETWEEN $1 AND $2 \
+                     AND ($3 IS NULL OR term = $3) ORDER BY date";
+
+        let yields: TYields = query_as(query)
+            .bind(t_yield_query.start_date())
+            .bind(t_yield_query.end_date())
+            .bind(t_yield_query.term())
+            .fetch_all(self.pool())
+            .await?;
+        Ok(yields)
+    }
+}
+
+#[derive(Debug, FromRow)]
+struct ZhviMetadataSqliteRow {
+    home_type: HomeType,
+    region_type: RegionType,
+    region_name: String,
+    percentile: Percentile,
+    state: Option<String>,
+}
+
+#[derive(FromRow)]
+struct ZhviPriceSqliteRow {
+    date: NaiveDate,
+    value: f64,
+}
+
+impl From<ZhviPriceSqliteRow> for ZhviPrice {
+    fn from(row: ZhviPriceSqliteRow) -> Self {
+        Self {
+            date: row.date,
+            value: row.value,
+            filled: false,
+        }
+    }
+}
+
+impl SqliteClient {
+    async fn insert_zhvi_prices(
+        tx: &mut sqlx::Transaction<'_, Sqlite>,
+        zhvi: &Zhvi,
+    ) -> Result<(), DomainError> {
+        for price in zhvi.prices() {
+            query(
+                r#"
+                    INSERT INTO zhvi_prices
+                    (region_name, region_type, home_type, percentile, state, date, value)
+                    VALUES ($1, $2, $3, $4, $5, $6, $7)
+                    ON CONFLICT DO NOTHING
+                "#,
+            )
+            .bind(zhvi.region_name())
+            .bind(zhvi.region_type())
+            .bind(zhvi.home_type())
+            .bind(zhvi.percentile())
+            .bind(zhvi.state().unwrap_or_default())
+            .bind(price.date)
+            .bind(price.value)
+            .execute(&mut **tx)
+            .await?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ZhviPersist for SqliteClient {
+    async fn create_zhvi(&self, zhvi: &Zhvi) -> Result<(), DomainError> {
+        let mut tx = self.pool().begin().await?;
+
+        query(
+            r#"
+                INSERT INTO zhvi_metadata
+                (region_name, region_type, home_type, percentile, state)
+                VALUES ($1, $2, $3, $4, $5)
+            "#,
+        )
+        .bind(zhvi.region_name())
+        .bind(zhvi.region_type())
+        .bind(zhvi.home_type())
+        .bind(zhvi.percentile())
+        .bind(zhvi.state().unwrap_or_default())
+        .execute(&mut *tx)
+        .await?;
+
+        SqliteClient::insert_zhvi_prices(&mut tx, zhvi).await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn create_zhvis(&self, zhvis: &[Zhvi]) -> Result<(), DomainError> {
+        let mut tx = self.pool().begin().await?;
+
+        for zhvi in zhvis {
+            query(
+                r#"
+                    INSERT INTO zhvi_metadata
+                    (region_name, region_type, home_type, percentile, state)
+                    VALUES ($1, $2, $3, $4, $5)
+                    ON CONFLICT DO NOTHING
+                "#,
+            )
+            .bind(zhvi.region_name())
+            .bind(zhvi.region_type())
+            .bind(zhvi.home_type())
+            .bind(zhvi.percentile())
+            .bind(zhvi.state().unwrap_or_default())
+            .execute(&mut *tx)
+            .await?;
+
+            SqliteClient::insert_zhvi_prices(&mut tx, zhvi).await?;
+        }
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn read_zhvi_by_id(&self, id: ZhviId<'_>) -> Result<Zhvi, DomainError> {
+        let mut tx = self.pool().begin().await?;
+        let metadata: ZhviMetadataSqliteRow = query_as(
+            r#"
+                SELECT region_name, region_type, home_type, percentile, NULLIF(state, '') AS state
+                FROM zhvi_metadata
+                WHERE region_name = $1 AND region_type = $2 AND home_type = $3 AND percentile = $4
+                AND state = $5
+            "#,
+        )
+        .bind(id.0)
+        .bind(id.1)
+        .bind(id.2)
+        .bind(id.3)
+        .bind(id.4.unwrap_or_default())
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let prices = query_as(
+            r#"
+                SELECT date, value
+                FROM zhvi_prices
+                WHERE region_name = $1 AND region_type = $2 AND home_type = $3 AND percentile = $4
+                AND state = $5
+                ORDER BY date
+            "#,
+        )
+        .bind(id.0)
+        .bind(id.1)
+        .bind(id.2)
+        .bind(id.3)
+        .bind(id.4.unwrap_or_default())
+        .fetch_all(&mut *tx)
+        .await?
+        .into_iter()
+        .map(|row: ZhviPriceSqliteRow| row.into())
+        .collect();
+
+        tx.commit().await?;
+
+        Ok(Zhvi {
+            home_type: metadata.home_type,
+            region_type: metadata.region_type,
+            region_name: metadata.region_name,
+            state: metadata.state,
+            percentile: metadata.percentile,
+            prices,
+        })
+    }
+
+    async fn update_zhvi(&self, zhvi: &Zhvi) -> Result<(), DomainError> {
+        let mut tx = self.pool().begin().await?;
+
+        // Replace existing prices
+        query(
+            r#"
+                DELETE FROM zhvi_prices
+                WHERE region_name = $1 AND region_type = $2 AND home_type = $3 AND percentile = $4
+                AND state = $5
+            "#,
+        )
+        .bind(zhvi.region_name())
+        .bind(zhvi.region_type())
+        .bind(zhvi.home_type())
+        .bind(zhvi.percentile())
+        .bind(zhvi.state().unwrap_or_default())
+        .execute(&mut *tx)
+        .await?;
+
+        SqliteClient::insert_zhvi_prices(&mut tx, zhvi).await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn delete_zhvi_by_id(&self, id: ZhviId<'_>) -> Result<(), DomainError> {
+        let mut tx = self.pool().begin().await?;
+
+        for table in ["zhvi_prices", "zhvi_metadata"] {
+            query(&format!(
+                "DELETE FROM {} WHERE region_name = $1 AND region_type = $2 AND home_type = $3 \
+                 AND percentile = $4 AND state = $5",
+                table
+            ))
+            .bind(id.0)
+            .bind(id.1)
+            .bind(id.2)
+            .bind(id.3)
+            .bind(id.4.unwrap_or_default())
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn read_zhvi_by_query(&self, query: &ZhviQuery) -> Result<Zhvis, DomainError> {
+        // Resampled to the query's interval by the domain
+        let prices_query = "SELECT date, value FROM zhvi_prices WHERE region_name = $1 AND \
+                            region_type = $2 AND home_type = $3 AND percentile = $4 AND state = \
+                            $5 AND date >= $6 AND date <= $7 ORDER BY date";
+
+        let mut tx = self.pool().begin().await?;
+
+        let metadata: Vec<ZhviMetadataSqliteRow> = query_as(
+            r#"
+                SELECT region_name, region_type, home_type, percentile, NULLIF(state, '') AS state
+                FROM zhvi_metadata
+                WHERE region_name = $1 AND region_type = $2 AND home_type = $3 AND percentile = $4
+                AND ($5 IS NULL OR state = $5)
+            "#,
+        )
+        .bind(query.region_name())
+        .bind(query.region_type())
+        .bind(query.home_type())
+        .bind(query.percentile())
+        .bind(query.state())
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let mut zhvis = vec![];
+        for metadata in metadata {
+            let prices = query_as(prices_query)
+                .bind(query.region_name())
+                .bind(query.region_type())
+                .bind(query.home_type())
+                .bind(query.percentile())
+                .bind(metadata.state.as_deref().unwrap_or_default())
+                .bind(query.start_date())
+                .bind(query.end_date())
+                .fetch_all(&mut *tx)
+                .await?
+                .into_iter()
+                .map(|row: ZhviPriceSqliteRow| row.into())
+                .collect();
+
+            zhvis.push(Zhvi {
+                home_type: metadata.home_type,
+                region_type: metadata.region_type,
+                region_name: metadata.region_name,
+                state: metadata.state,
+                percentile: metadata.percentile,
+                prices,
+            });
+        }
+
+        tx.commit().await?;
+
+        Ok(zhvis)
+    }
+}
@@ -1,15 +1,33 @@
+use self::cache::CachedPersist;
+use self::database::memory::InMemoryClient;
+#[cfg(feature = "postgres")]
 use self::database::postgres::PostgresClient;
-use crate::adapter::config::Config;
+#[cfg(feature = "sqlite")]
+use self::database::sqlite::SqliteClient;
+use crate::adapter::config::{Config, DatabaseType};
 use crate::adapter::repository::database::http::HttpClient;
+use crate::domain::cpi::CpiPersist;
 use crate::domain::hpi::HpiPersist;
+use crate::domain::income::IncomePersist;
+use crate::domain::mortgage_rate::MortgageRatePersist;
 use crate::domain::region::RegionPersist;
 use crate::domain::t_yield::TYieldPersist;
 use crate::domain::zhvi::ZhviPersist;
 use crate::error::DomainError;
 
+pub mod cache;
 pub mod database;
 
-pub trait Persist: HpiPersist + RegionPersist + TYieldPersist + ZhviPersist {}
+pub trait Persist:
+    CpiPersist
+    + HpiPersist
+    + IncomePersist
+    + MortgageRatePersist
+    + RegionPersist
+    + TYieldPersist
+    + ZhviPersist
+{
+}
 
 pub struct Repository {
     client: Box<dyn Persist>,
@@ -21,12 +39,51 @@ impl Repository {
         Ok(Repository { client })
     }
 
+    /// Brings the configured database up to this binary's schema
+    pub async fn migrate(config: &Config) -> Result<(), DomainError> {
+        match config.database_type() {
+            DatabaseType::InMemory => Ok(()),
+            #[cfg(feature = "postgres")]
+            DatabaseType::Postgres => {
+                let client = PostgresClient::connect(&std::env::var("DATABASE_URL")?).await?;
+                client.migrate().await?;
+                client.check_schema().await
+            }
+            // Migrations already run on connect
+            #[cfg(feature = "sqlite")]
+            DatabaseType::Sqlite => SqliteClient::new(config).await.map(|_| ()),
+            #[allow(unreachable_patterns)]
+            database_type => Err(DomainError::Database(format!(
+                "{:?} support was not enabled at build time",
+                database_type
+            ))),
+        }
+    }
+
     async fn establish_session(config: &Config) -> Result<Box<dyn Persist>, DomainError> {
+        let mut session = Repository::establish_database(config).await?;
         if config.is_zillow_api_enabled() {
             println!("Using HTTP client.");
-            Ok(Box::new(HttpClient))
-        } else {
-            Ok(Box::new(PostgresClient::new(config).await?))
+            session = Box::new(HttpClient::new(config, session));
+        }
+        if let Some(ttl) = config.cache_ttl() {
+            session = Box::new(CachedPersist::new(session, ttl, config.cache_max_entries()));
+        }
+        Ok(session)
+    }
+
+    async fn establish_database(config: &Config) -> Result<Box<dyn Persist>, DomainError> {
+        match config.database_type() {
+            DatabaseType::InMemory => Ok(Box::new(InMemoryClient::new())),
+            #[cfg(feature = "postgres")]
+            DatabaseType::Postgres => Ok(Box::new(PostgresClient::new(config).await?)),
+            #[cfg(feature = "sqlite")]
+            DatabaseType::Sqlite => Ok(Box::new(SqliteClient::new(config).await?)),
+            #[allow(unreachable_patterns)]
+            database_type => Err(DomainError::Database(format!(
+                "{:?} support was not enabled at build time",
+                database_type
+            ))),
         }
     }
 
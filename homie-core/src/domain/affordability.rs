@@ -0,0 +1,232 @@
+use std::collections::BTreeMap;
+
+use chrono::{Datelike, Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::adapter::repository::Persist;
+use crate::domain::common::{DateInterval, RegionType};
+use crate::domain::income::{Income, IncomeQuery};
+use crate::domain::mortgage::{read_rates, Mortgage, RateSource};
+use crate::domain::zhvi::{HomeType, Percentile, Zhvi, ZhviQuery};
+use crate::error::DomainError;
+
+// How far back the rate and income series are read, so the first months of
+// the query still find an earlier observation
+const RATE_LOOKBACK_MONTHS: u32 = 12;
+// ACS estimates are published a year or more after the survey year
+const INCOME_LOOKBACK_YEARS: i32 = 5;
+
+/// The monthly payment on a home bought at the ZHVI price, against the local
+/// median household income
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct AffordabilityPoint {
+    pub date: NaiveDate,
+    pub price: f64,
+    /// Annual rate in percent
+    pub rate: f64,
+    pub monthly_payment: f64,
+    pub median_income: f64,
+    /// Survey year of the income, the latest one on or before `date`
+    pub income_year: i32,
+    /// Yearly payments as a percent of the median income
+    pub payment_to_income: f64,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct Affordability {
+    pub region_name: String,
+    pub region_type: RegionType,
+    pub home_type: HomeType,
+    pub percentile: Percentile,
+    pub points: Vec<AffordabilityPoint>,
+}
+
+pub type Affordabilities = Vec<Affordability>;
+
+/// How the home is paid for
+#[derive(Clone, Debug, PartialEq)]
+pub struct Financing {
+    rate_source: RateSource,
+    term_years: u32,
+    down_payment_percent: f64,
+}
+
+impl Financing {
+    pub fn new(
+        rate_source: RateSource,
+        term_years: u32,
+        down_payment_percent: f64,
+    ) -> Result<Self, DomainError> {
+        if !(0.0..100.0).contains(&down_payment_percent) {
+            return Err(DomainError::ConvertDomain(format!(
+                "Down payment {}% must be between 0 and 100",
+                down_payment_percent
+            )));
+        }
+        Ok(Self {
+            rate_source,
+            term_years,
+            down_payment_percent,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AffordabilityQuery {
+    region_name: String,
+    region_type: RegionType,
+    home_type: HomeType,
+    /// Every tier when unset
+    percentile: Option<Percentile>,
+    /// Region id or name the income is stored under, when it differs from the
+    /// ZHVI region name
+    income_region: Option<String>,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+    financing: Financing,
+}
+
+impl AffordabilityQuery {
+    pub fn new(
+        region_name: String,
+        region_type: RegionType,
+        home_type: HomeType,
+        percentile: Option<Percentile>,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+        financing: Financing,
+    ) -> Self {
+        Self {
+            region_name,
+            region_type,
+            home_type,
+            percentile,
+            income_region: None,
+            start_date,
+            end_date,
+            financing,
+        }
+    }
+
+    /// Reads the income stored under another region id or name
+    pub fn with_income_region(mut self, income_region: String) -> Self {
+        self.income_region = Some(income_region);
+        self
+    }
+
+    fn income_region(&self) -> &str {
+        self.income_region.as_deref().unwrap_or(&self.region_name)
+    }
+}
+
+impl Affordability {
+    /// Reads monthly prices for each tier, the monthly average rate and the
+    /// yearly median income, and lines them up by month. Months without a
+    /// rate or income on or before them are left out.
+    pub async fn read_by_query(
+        client: &dyn Persist,
+        query: &AffordabilityQuery,
+    ) -> Result<Affordabilities, DomainError> {
+        let financing = &query.financing;
+        let rates = read_rates(
+            client,
+            &financing.rate_source,
+            financing.term_years,
+            query.start_date - Months::new(RATE_LOOKBACK_MONTHS),
+            query.end_date,
+            DateInterval::Month,
+        )
+        .await?;
+
+        let income_query = IncomeQuery::new(
+            query.region_type.clone(),
+            query.income_region().to_string(),
+            query.start_date.year() - INCOME_LOOKBACK_YEARS,
+            query.end_date.year(),
+        );
+        let incomes = Income::read_by_query(client, &income_query).await?;
+        let incomes = median_incomes(query.income_region(), &incomes)?;
+
+        let percentiles = match &query.percentile {
+            Some(percentile) => vec![percentile.clone()],
+            None => Percentile::ALL.to_vec(),
+        };
+        let mut affordabilities = vec![];
+        for percentile in percentiles {
+            // Daily keeps each stored price on its own date
+            let zhvi_query = ZhviQuery::new(
+                query.start_date,
+                query.end_date,
+                DateInterval::Day,
+                query.region_name.clone(),
+                query.region_type.clone(),
+                query.home_type.clone(),
+                percentile,
+            );
+            for zhvi in Zhvi::read_by_query(client, &zhvi_query).await? {
+                affordabilities.push(affordability(&zhvi, &rates, &incomes, financing)?);
+            }
+        }
+        Ok(affordabilities)
+    }
+}
+
+/// Median income by year for a single region. Place names repeat across
+/// states, so a name matching several regions must be narrowed by id.
+fn median_incomes(region: &str, incomes: &[Income]) -> Result<BTreeMap<i32, f64>, DomainError> {
+    let mut region_ids: Vec<&str> = incomes.iter().map(|income| income.region_id()).collect();
+    region_ids.sort();
+    region_ids.dedup();
+    if region_ids.len() > 1 {
+        return Err(DomainError::ConvertDomain(format!(
+            "Income region '{}' matches several regions, pick one of {}",
+            region,
+            region_ids.join(", ")
+        )));
+    }
+    Ok(incomes
+        .iter()
+        .filter_map(|income| income.median_income().map(|value| (income.year(), value)))
+        .collect())
+}
+
+/// Payment-to-income at each of the ZHVI's prices. `rates` and `incomes` are
+/// keyed by month start and year, and the latest entry on or before each
+/// price is used.
+pub fn affordability(
+    zhvi: &Zhvi,
+    rates: &BTreeMap<NaiveDate, f64>,
+    incomes: &BTreeMap<i32, f64>,
+    financing: &Financing,
+) -> Result<Affordability, DomainError> {
+    let mut points = vec![];
+    for price in zhvi.prices() {
+        let rate = rates.range(..=price.date).next_back();
+        let income = incomes.range(..=price.date.year()).next_back();
+        let (Some((_, rate)), Some((income_year, median_income))) = (rate, income) else {
+            continue;
+        };
+        if price.value <= 0.0 || *median_income <= 0.0 {
+            continue;
+        }
+        let down_payment = price.value * financing.down_payment_percent / 100.0;
+        let mortgage = Mortgage::new(price.value, down_payment, *rate, financing.term_years)?;
+        let monthly_payment = mortgage.monthly_payment();
+        points.push(AffordabilityPoint {
+            date: price.date,
+            price: price.value,
+            rate: *rate,
+            monthly_payment,
+            median_income: *median_income,
+            income_year: *income_year,
+            payment_to_income: monthly_payment * 12.0 / median_income * 100.0,
+        });
+    }
+    Ok(Affordability {
+        region_name: zhvi.region_name().to_string(),
+        region_type: zhvi.region_type().clone(),
+        home_type: zhvi.home_type().clone(),
+        percentile: zhvi.percentile().clone(),
+        points,
+    })
+}
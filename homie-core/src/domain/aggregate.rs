@@ -0,0 +1,310 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::adapter::repository::Persist;
+use crate::domain::common::RegionType;
+use crate::domain::hpi::{Hpi, HpiQuery, Hpis};
+use crate::domain::region::{Region, RegionQuery, State, Zipcode};
+use crate::domain::zhvi::{HomeType, Percentile, Zhvi, ZhviPrice, ZhviQuery};
+use crate::error::DomainError;
+
+#[derive(Clone, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub enum Aggregation {
+    #[default]
+    Mean,
+    Median,
+}
+
+impl TryFrom<&str> for Aggregation {
+    type Error = crate::error::DomainError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        match s {
+            "mean" => Ok(Aggregation::Mean),
+            "median" => Ok(Aggregation::Median),
+            _ => Err(DomainError::Parse(
+                "Failed to parse Aggregation".to_string(),
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for Aggregation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Aggregation::Mean => write!(f, "mean"),
+            Aggregation::Median => write!(f, "median"),
+        }
+    }
+}
+
+/// A city or county series built from five-zip series rather than read from a
+/// source
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Derived<T> {
+    pub series: T,
+    pub aggregation: Aggregation,
+    pub state: State,
+    /// Five-zip series the values were built from
+    pub zipcodes: Vec<Zipcode>,
+}
+
+/// Zip weights per city or county, from the share of each zip's residential
+/// addresses (HUD `RES_RATIO`) that fall in it. Cities are keyed by state as
+/// well, so same-named cities stay apart; counties are named by their FIPS
+/// code.
+pub struct Aggregator {
+    region_type: RegionType,
+    groups: BTreeMap<(State, String), BTreeMap<Zipcode, f64>>,
+}
+
+impl Aggregator {
+    pub fn new(regions: &[Region], region_type: RegionType) -> Result<Self, DomainError> {
+        let mut groups: BTreeMap<(State, String), BTreeMap<Zipcode, f64>> = BTreeMap::new();
+        for region in regions {
+            let name = match region_type {
+                RegionType::City => region.city(),
+                RegionType::County => region.county_fips(),
+                _ => {
+                    return Err(DomainError::ConvertDomain(format!(
+                        "Cannot aggregate zips into {}",
+                        region_type
+                    )))
+                }
+            };
+            *groups
+                .entry((region.state().to_string(), name.to_string()))
+                .or_default()
+                .entry(region.zipcode().to_string())
+                .or_default() += region.res_ratio();
+        }
+        Ok(Self {
+            region_type,
+            groups,
+        })
+    }
+
+    /// Weighs the zips of the regions `query` selects into cities or counties.
+    /// `query` must select cities, zips or counties, not every region.
+    pub async fn read(
+        client: &dyn Persist,
+        query: &RegionQuery,
+        region_type: RegionType,
+    ) -> Result<Self, DomainError> {
+        if query.cities().is_empty()
+            && query.zipcodes().is_empty()
+            && query.county_fips().is_empty()
+        {
+            return Err(DomainError::ConvertDomain(
+                "Select the cities, zips or counties to aggregate".to_string(),
+            ));
+        }
+        let regions = Region::read_by_query(client, query).await?;
+        Aggregator::new(&regions, region_type)
+    }
+
+    /// Reads the five-zip ZHVIs of every weighed zip, over `query`'s dates,
+    /// interval, home type and percentile, and combines them
+    pub async fn read_zhvis(
+        &self,
+        client: &dyn Persist,
+        query: &ZhviQuery,
+        aggregation: Aggregation,
+    ) -> Result<Vec<Derived<Zhvi>>, DomainError> {
+        let mut zhvis = vec![];
+        for name in self.stored_zip_names() {
+            let query = query.for_region(name, RegionType::FiveZip);
+            zhvis.extend(Zhvi::read_by_query(client, &query).await?);
+        }
+        Ok(self.zhvis(&zhvis, aggregation))
+    }
+
+    /// Reads the five-zip HPIs of every weighed zip over `query`'s years, and
+    /// combines them
+    pub async fn read_hpis(
+        &self,
+        client: &dyn Persist,
+        query: &HpiQuery,
+        aggregation: Aggregation,
+    ) -> Result<Vec<Derived<Hpis>>, DomainError> {
+        let mut hpis = vec![];
+        for name in self.stored_zip_names() {
+            let query = query.for_region(RegionType::FiveZip, name);
+            hpis.extend(Hpi::read_by_query(client, &query).await?);
+        }
+        Ok(self.hpis(&hpis, aggregation))
+    }
+
+    /// The names five-zip series of the weighed zips may be stored under,
+    /// with and without their leading zeros
+    fn stored_zip_names(&self) -> BTreeSet<String> {
+        self.groups
+            .values()
+            .flat_map(|weights| weights.keys())
+            .flat_map(|zipcode| [zipcode.clone(), zipcode.trim_start_matches('0').to_string()])
+            .filter(|name| !name.is_empty())
+            .collect()
+    }
+
+    /// One series per region, home type and percentile with any five-zip ZHVI
+    /// inside it
+    pub fn zhvis(&self, zhvis: &[Zhvi], aggregation: Aggregation) -> Vec<Derived<Zhvi>> {
+        let mut by_zip: Vec<(HomeType, Percentile, BTreeMap<Zipcode, &Zhvi>)> = vec![];
+        for zhvi in zhvis
+            .iter()
+            .filter(|z| z.region_type == RegionType::FiveZip)
+        {
+            let index = by_zip
+                .iter()
+                .position(|(home_type, percentile, _)| {
+                    *home_type == zhvi.home_type && *percentile == zhvi.percentile
+                })
+                .unwrap_or_else(|| {
+                    by_zip.push((
+                        zhvi.home_type.clone(),
+                        zhvi.percentile.clone(),
+                        BTreeMap::new(),
+                    ));
+                    by_zip.len() - 1
+                });
+            by_zip[index]
+                .2
+                .insert(normalize_zipcode(&zhvi.region_name), zhvi);
+        }
+
+        let mut derived = vec![];
+        for ((state, name), weights) in &self.groups {
+            for (home_type, percentile, zips) in &by_zip {
+                let mut values: BTreeMap<NaiveDate, Vec<(f64, f64)>> = BTreeMap::new();
+                let mut zipcodes = vec![];
+                for (zipcode, weight) in weights {
+                    if let Some(zhvi) = zips.get(zipcode) {
+                        zipcodes.push(zipcode.clone());
+                        for price in &zhvi.prices {
+                            values
+                                .entry(price.date)
+                                .or_default()
+                                .push((price.value, *weight));
+                        }
+                    }
+                }
+                if zipcodes.is_empty() {
+                    continue;
+                }
+                let prices = values
+                    .into_iter()
+                    .filter_map(|(date, mut values)| {
+                        aggregate(&mut values, &aggregation).map(|value| ZhviPrice {
+                            date,
+                            value,
+                            filled: false,
+                        })
+                    })
+                    .collect();
+                derived.push(Derived {
+                    series: Zhvi {
+                        region_name: name.clone(),
+                        region_type: self.region_type.clone(),
+                        state: Some(state.clone()),
+                        home_type: home_type.clone(),
+                        percentile: percentile.clone(),
+                        prices,
+                    },
+                    aggregation: aggregation.clone(),
+                    state: state.clone(),
+                    zipcodes,
+                });
+            }
+        }
+        derived
+    }
+
+    /// One yearly series per region with any five-zip HPI inside it
+    pub fn hpis(&self, hpis: &[Hpi], aggregation: Aggregation) -> Vec<Derived<Hpis>> {
+        let mut by_zip: BTreeMap<Zipcode, Vec<&Hpi>> = BTreeMap::new();
+        for hpi in hpis.iter().filter(|h| h.region_type == RegionType::FiveZip) {
+            by_zip
+                .entry(normalize_zipcode(&hpi.region_id))
+                .or_default()
+                .push(hpi);
+        }
+
+        let mut derived = vec![];
+        for ((state, name), weights) in &self.groups {
+            let mut years: BTreeMap<i32, Vec<(&Hpi, f64)>> = BTreeMap::new();
+            let mut zipcodes = vec![];
+            for (zipcode, weight) in weights {
+                if let Some(zip_hpis) = by_zip.get(zipcode) {
+                    zipcodes.push(zipcode.clone());
+                    for hpi in zip_hpis {
+                        years.entry(hpi.year).or_default().push((hpi, *weight));
+                    }
+                }
+            }
+            if zipcodes.is_empty() {
+                continue;
+            }
+            let field = |hpis: &[(&Hpi, f64)], value: fn(&Hpi) -> Option<f32>| {
+                let mut values: Vec<(f64, f64)> = hpis
+                    .iter()
+                    .filter_map(|(hpi, weight)| value(hpi).map(|v| (v as f64, *weight)))
+                    .collect();
+                aggregate(&mut values, &aggregation).map(|v| v as f32)
+            };
+            let series = years
+                .into_iter()
+                .map(|(year, hpis)| Hpi {
+                    region_type: self.region_type.clone(),
+                    region_id: name.clone(),
+                    region_name: name.clone(),
+                    state: Some(state.clone()),
+                    year,
+                    hpi: field(&hpis, |h| h.hpi),
+                    annual_change: field(&hpis, |h| h.annual_change),
+                    hpi_1990_base: field(&hpis, |h| h.hpi_1990_base),
+                    hpi_2000_base: field(&hpis, |h| h.hpi_2000_base),
+                })
+                .collect();
+            derived.push(Derived {
+                series,
+                aggregation: aggregation.clone(),
+                state: state.clone(),
+                zipcodes,
+            });
+        }
+        derived
+    }
+}
+
+// Zillow and FHFA drop leading zeros, e.g. "501" for 00501
+fn normalize_zipcode(region_name: &str) -> Zipcode {
+    format!("{:0>5}", region_name)
+}
+
+/// Weighted mean or median of `(value, weight)` pairs; `None` without any
+/// weight
+fn aggregate(values: &mut [(f64, f64)], aggregation: &Aggregation) -> Option<f64> {
+    let total: f64 = values.iter().map(|(_, weight)| weight).sum();
+    if total <= 0.0 {
+        return None;
+    }
+    match aggregation {
+        Aggregation::Mean => Some(
+            values
+                .iter()
+                .map(|(value, weight)| value * weight)
+                .sum::<f64>()
+                / total,
+        ),
+        Aggregation::Median => {
+            values.sort_by(|a, b| a.0.total_cmp(&b.0));
+            let mut cumulative = 0.0;
+            values.iter().find_map(|(value, weight)| {
+                cumulative += weight;
+                (cumulative >= total / 2.0).then_some(*value)
+            })
+        }
+    }
+}
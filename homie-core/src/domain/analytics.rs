@@ -0,0 +1,396 @@
+use std::collections::BTreeMap;
+
+use chrono::{Datelike, Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::common::RegionType;
+use crate::domain::hpi::Hpi;
+use crate::domain::t_yield::{TYield, Term};
+use crate::domain::zhvi::{HomeType, Percentile, Zhvi, ZhviPrice};
+use crate::error::DomainError;
+
+/// Observations per window of the rolling transforms, a year of monthly data
+pub const DEFAULT_WINDOW: usize = 12;
+
+#[derive(Clone, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub enum Transform {
+    /// Percent change from a year earlier
+    #[default]
+    Yoy,
+    /// Percent change from a month earlier
+    Mom,
+    /// Compound annual growth between the first and last observation
+    Cagr,
+    RollingMean,
+    /// Standard deviation of the percent changes between observations
+    RollingVolatility,
+    /// Percent below the running peak, with the deepest drawdown and its
+    /// recovery
+    Drawdown,
+}
+
+impl TryFrom<&str> for Transform {
+    type Error = crate::error::DomainError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        match s {
+            "yoy" => Ok(Transform::Yoy),
+            "mom" => Ok(Transform::Mom),
+            "cagr" => Ok(Transform::Cagr),
+            "rollingmean" => Ok(Transform::RollingMean),
+            "rollingvolatility" => Ok(Transform::RollingVolatility),
+            "drawdown" => Ok(Transform::Drawdown),
+            _ => Err(DomainError::Parse("Failed to parse Transform".to_string())),
+        }
+    }
+}
+
+impl std::fmt::Display for Transform {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Transform::Yoy => write!(f, "yoy"),
+            Transform::Mom => write!(f, "mom"),
+            Transform::Cagr => write!(f, "cagr"),
+            Transform::RollingMean => write!(f, "rollingmean"),
+            Transform::RollingVolatility => write!(f, "rollingvolatility"),
+            Transform::Drawdown => write!(f, "drawdown"),
+        }
+    }
+}
+
+/// One dated value of a series
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Point {
+    pub date: NaiveDate,
+    pub value: f64,
+}
+
+/// The deepest fall from a running peak
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Drawdown {
+    /// Percent below the peak at the trough, zero or negative
+    pub max_drawdown: f64,
+    pub peak: NaiveDate,
+    pub trough: NaiveDate,
+    pub peak_to_trough_months: u32,
+    /// First date back at the peak, `None` while still below it
+    pub recovery: Option<NaiveDate>,
+    pub recovery_months: Option<u32>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Analysis {
+    pub transform: Transform,
+    /// Observations per window, for the rolling transforms
+    pub window: Option<usize>,
+    /// The transformed series, empty for `Cagr`
+    pub points: Vec<Point>,
+    /// Percent per year, for `Cagr`
+    pub cagr: Option<f64>,
+    /// For `Drawdown`, `None` without any observations
+    pub drawdown: Option<Drawdown>,
+}
+
+/// Dated values of a stored series, oldest first, without missing values
+pub trait Observations {
+    fn observations(&self) -> Vec<Point>;
+}
+
+impl Observations for [ZhviPrice] {
+    fn observations(&self) -> Vec<Point> {
+        sorted(
+            self.iter()
+                .map(|price| Point {
+                    date: price.date,
+                    value: price.value,
+                })
+                .collect(),
+        )
+    }
+}
+
+/// Annual HPIs, dated January 1st of their year
+impl Observations for [Hpi] {
+    fn observations(&self) -> Vec<Point> {
+        sorted(
+            self.iter()
+                .filter_map(|hpi| {
+                    let date = NaiveDate::from_ymd_opt(hpi.year(), 1, 1)?;
+                    hpi.hpi().map(|value| Point {
+                        date,
+                        value: value as f64,
+                    })
+                })
+                .collect(),
+        )
+    }
+}
+
+impl Observations for [TYield] {
+    fn observations(&self) -> Vec<Point> {
+        sorted(
+            self.iter()
+                .filter_map(|t_yield| {
+                    (*t_yield.yield_return()).map(|value| Point {
+                        date: *t_yield.date(),
+                        value: value as f64,
+                    })
+                })
+                .collect(),
+        )
+    }
+}
+
+fn sorted(mut points: Vec<Point>) -> Vec<Point> {
+    points.sort_by_key(|point| point.date);
+    points
+}
+
+/// Applies `transform` to date-ordered `points`. `window` only matters for
+/// the rolling transforms.
+pub fn analyze(points: &[Point], transform: &Transform, window: usize) -> Analysis {
+    let mut analysis = Analysis {
+        transform: transform.clone(),
+        ..Default::default()
+    };
+    match transform {
+        Transform::Yoy => analysis.points = change(points, 12),
+        Transform::Mom => analysis.points = change(points, 1),
+        Transform::Cagr => analysis.cagr = cagr(points),
+        Transform::RollingMean => {
+            analysis.window = Some(window);
+            analysis.points = rolling_mean(points, window);
+        }
+        Transform::RollingVolatility => {
+            analysis.window = Some(window);
+            analysis.points = rolling_volatility(points, window);
+        }
+        Transform::Drawdown => {
+            let (points, drawdown) = drawdown(points);
+            analysis.points = points;
+            analysis.drawdown = drawdown;
+        }
+    }
+    analysis
+}
+
+/// Percent change from the observation `months` earlier. Daily series may
+/// miss that exact date, so the latest one up to a month before it is used.
+pub fn change(points: &[Point], months: u32) -> Vec<Point> {
+    let values: BTreeMap<NaiveDate, f64> = points.iter().map(|p| (p.date, p.value)).collect();
+    points
+        .iter()
+        .filter_map(|point| {
+            let target = months_back(point.date, months)?;
+            let (_, previous) = values.range(target - Months::new(1)..=target).next_back()?;
+            (*previous != 0.0).then(|| Point {
+                date: point.date,
+                value: (point.value / previous - 1.0) * 100.0,
+            })
+        })
+        .collect()
+}
+
+/// Compound annual growth in percent between the first and last observation
+pub fn cagr(points: &[Point]) -> Option<f64> {
+    let (first, last) = (points.first()?, points.last()?);
+    let years = (last.date - first.date).num_days() as f64 / 365.25;
+    if years <= 0.0 || first.value <= 0.0 || last.value < 0.0 {
+        return None;
+    }
+    Some(((last.value / first.value).powf(1.0 / years) - 1.0) * 100.0)
+}
+
+/// Mean of each trailing `window` observations
+pub fn rolling_mean(points: &[Point], window: usize) -> Vec<Point> {
+    if window == 0 {
+        return vec![];
+    }
+    points
+        .windows(window)
+        .map(|window_points| Point {
+            date: window_points[window_points.len() - 1].date,
+            value: mean(window_points.iter().map(|p| p.value)),
+        })
+        .collect()
+}
+
+/// Sample standard deviation of each trailing `window` percent changes
+/// between consecutive observations
+pub fn rolling_volatility(points: &[Point], window: usize) -> Vec<Point> {
+    if window < 2 {
+        return vec![];
+    }
+    let changes: Vec<Point> = points
+        .windows(2)
+        .filter(|pair| pair[0].value != 0.0)
+        .map(|pair| Point {
+            date: pair[1].date,
+            value: (pair[1].value / pair[0].value - 1.0) * 100.0,
+        })
+        .collect();
+    changes
+        .windows(window)
+        .map(|window_changes| {
+            let values = window_changes.iter().map(|p| p.value);
+            let mean = mean(values.clone());
+            let variance =
+                values.map(|v| (v - mean).powi(2)).sum::<f64>() / (window_changes.len() - 1) as f64;
+            Point {
+                date: window_changes[window_changes.len() - 1].date,
+                value: variance.sqrt(),
+            }
+        })
+        .collect()
+}
+
+/// Percent below the running peak at each date, and the deepest drawdown
+pub fn drawdown(points: &[Point]) -> (Vec<Point>, Option<Drawdown>) {
+    let mut series = vec![];
+    let mut deepest: Option<Drawdown> = None;
+    let mut peak: Option<&Point> = None;
+    for point in points {
+        let current = match peak {
+            Some(peak) if peak.value > point.value => peak,
+            _ => {
+                peak = Some(point);
+                point
+            }
+        };
+        let value = match current.value > 0.0 {
+            true => (point.value / current.value - 1.0) * 100.0,
+            false => 0.0,
+        };
+        series.push(Point {
+            date: point.date,
+            value,
+        });
+        if deepest.as_ref().map_or(true, |d| value < d.max_drawdown) {
+            deepest = Some(Drawdown {
+                max_drawdown: value,
+                peak: current.date,
+                trough: point.date,
+                peak_to_trough_months: months_between(current.date, point.date),
+                recovery: None,
+                recovery_months: None,
+            });
+        }
+    }
+
+    if let Some(deepest) = deepest.as_mut() {
+        let peak_value = points
+            .iter()
+            .find(|p| p.date == deepest.peak)
+            .map_or(0.0, |p| p.value);
+        deepest.recovery = points
+            .iter()
+            .find(|p| p.date > deepest.trough && p.value >= peak_value)
+            .map(|p| p.date);
+        deepest.recovery_months = deepest
+            .recovery
+            .map(|recovery| months_between(deepest.trough, recovery));
+    }
+    (series, deepest)
+}
+
+fn mean(values: impl Iterator<Item = f64>) -> f64 {
+    let (sum, count) = values.fold((0.0, 0), |(sum, count), v| (sum + v, count + 1));
+    sum / count as f64
+}
+
+/// `date` less `months`, keeping month ends on month ends so April 30th
+/// goes back to March 31st
+fn months_back(date: NaiveDate, months: u32) -> Option<NaiveDate> {
+    let target = date.checked_sub_months(Months::new(months))?;
+    if date.succ_opt()?.month() == date.month() {
+        return Some(target);
+    }
+    let month_start = target.with_day(1)?;
+    month_start.checked_add_months(Months::new(1))?.pred_opt()
+}
+
+fn months_between(start: NaiveDate, end: NaiveDate) -> u32 {
+    let months = (end.year() - start.year()) * 12 + end.month() as i32 - start.month() as i32;
+    months.max(0) as u32
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct ZhviAnalysis {
+    pub region_name: String,
+    pub region_type: RegionType,
+    pub home_type: HomeType,
+    pub percentile: Percentile,
+    #[serde(flatten)]
+    pub analysis: Analysis,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct HpiAnalysis {
+    pub region_type: RegionType,
+    pub region_id: String,
+    pub region_name: String,
+    #[serde(flatten)]
+    pub analysis: Analysis,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct TYieldAnalysis {
+    pub term: Term,
+    #[serde(flatten)]
+    pub analysis: Analysis,
+}
+
+pub fn analyze_zhvis(zhvis: &[Zhvi], transform: &Transform, window: usize) -> Vec<ZhviAnalysis> {
+    zhvis
+        .iter()
+        .map(|zhvi| ZhviAnalysis {
+            region_name: zhvi.region_name().to_string(),
+            region_type: zhvi.region_type().clone(),
+            home_type: zhvi.home_type().clone(),
+            percentile: zhvi.percentile().clone(),
+            analysis: analyze(&zhvi.prices().observations(), transform, window),
+        })
+        .collect()
+}
+
+/// One analysis per region among `hpis`
+pub fn analyze_hpis(hpis: &[Hpi], transform: &Transform, window: usize) -> Vec<HpiAnalysis> {
+    let mut regions: BTreeMap<(String, &str), Vec<Hpi>> = BTreeMap::new();
+    for hpi in hpis {
+        regions
+            .entry((hpi.region_type().to_string(), hpi.region_id()))
+            .or_default()
+            .push(hpi.clone());
+    }
+    regions
+        .into_values()
+        .map(|hpis| HpiAnalysis {
+            region_type: hpis[0].region_type().clone(),
+            region_id: hpis[0].region_id().to_string(),
+            region_name: hpis[0].region_name().to_string(),
+            analysis: analyze(&hpis.observations(), transform, window),
+        })
+        .collect()
+}
+
+/// One analysis per term among `t_yields`, shortest first
+pub fn analyze_t_yields(
+    t_yields: &[TYield],
+    transform: &Transform,
+    window: usize,
+) -> Vec<TYieldAnalysis> {
+    Term::ALL
+        .iter()
+        .filter_map(|term| {
+            let t_yields: Vec<TYield> = t_yields
+                .iter()
+                .filter(|t_yield| t_yield.term() == term)
+                .cloned()
+                .collect();
+            (!t_yields.is_empty()).then(|| TYieldAnalysis {
+                term: term.clone(),
+                analysis: analyze(&t_yields.observations(), transform, window),
+            })
+        })
+        .collect()
+}
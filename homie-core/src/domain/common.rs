@@ -3,10 +3,13 @@ use utoipa::ToSchema;
 
 use crate::error::DomainError;
 
-#[derive(Clone, Debug, Default, PartialEq)]
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
 pub enum DateInterval {
     Day,
+    /// Starting on Monday
+    Week,
     Month,
+    Quarter,
     #[default]
     Year,
 }
@@ -17,7 +20,9 @@ impl TryFrom<&str> for DateInterval {
     fn try_from(s: &str) -> Result<Self, Self::Error> {
         match s {
             "day" => Ok(DateInterval::Day),
+            "week" => Ok(DateInterval::Week),
             "month" => Ok(DateInterval::Month),
+            "quarter" => Ok(DateInterval::Quarter),
             "year" => Ok(DateInterval::Year),
             _ => Err(DomainError::Parse(
                 "Failed to parse DateInterval".to_string(),
@@ -26,7 +31,9 @@ impl TryFrom<&str> for DateInterval {
     }
 }
 
-#[derive(Clone, Debug, Default, Deserialize, Serialize, sqlx::Type, ToSchema)]
+#[derive(
+    Clone, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize, sqlx::Type, ToSchema,
+)]
 #[sqlx(type_name = "region_type", rename_all = "lowercase")]
 pub enum RegionType {
     ThreeZip,
@@ -34,6 +41,23 @@ pub enum RegionType {
     #[default]
     City,
     County,
+    State,
+    Msa,
+    National,
+}
+
+impl RegionType {
+    // Only the Postgres enum-drift check needs every label
+    #[cfg(feature = "postgres")]
+    pub(crate) const ALL: [RegionType; 7] = [
+        RegionType::ThreeZip,
+        RegionType::FiveZip,
+        RegionType::City,
+        RegionType::County,
+        RegionType::State,
+        RegionType::Msa,
+        RegionType::National,
+    ];
 }
 
 impl TryFrom<&str> for RegionType {
@@ -45,6 +69,9 @@ impl TryFrom<&str> for RegionType {
             "fivezip" => Ok(RegionType::FiveZip),
             "city" => Ok(RegionType::City),
             "county" => Ok(RegionType::County),
+            "state" => Ok(RegionType::State),
+            "msa" => Ok(RegionType::Msa),
+            "national" => Ok(RegionType::National),
             _ => Err(DomainError::Parse("Failed to parse RegionType".to_string())),
         }
     }
@@ -57,6 +84,9 @@ impl std::fmt::Display for RegionType {
             RegionType::FiveZip => write!(f, "fivezip"),
             RegionType::City => write!(f, "city"),
             RegionType::County => write!(f, "county"),
+            RegionType::State => write!(f, "state"),
+            RegionType::Msa => write!(f, "msa"),
+            RegionType::National => write!(f, "national"),
         }
     }
 }
@@ -0,0 +1,294 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+
+use async_trait::async_trait;
+use chrono::{Datelike, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::adapter::importer::ImportReport;
+use crate::adapter::repository::Persist;
+use crate::domain::util::CsvHeader;
+use crate::error::DomainError;
+
+/// U.S. city average, all items, not seasonally adjusted
+pub(crate) const DEFAULT_SERIES_ID: &str = "CUUR0000SA0";
+
+/// BLS Consumer Price Index for All Urban Consumers (CPI-U) for one month
+#[derive(Clone, Debug, Default, Deserialize, Serialize, sqlx::FromRow)]
+pub struct Cpi {
+    /// First of the month
+    pub(crate) date: NaiveDate,
+    pub(crate) value: f64,
+}
+
+impl Cpi {
+    pub(crate) fn date(&self) -> &NaiveDate {
+        &self.date
+    }
+
+    pub(crate) fn value(&self) -> f64 {
+        self.value
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct CpiData {
+    cpis: Cpis,
+}
+
+impl CpiData {
+    pub fn cpis(&self) -> &Cpis {
+        &self.cpis
+    }
+}
+
+pub type Cpis = Vec<Cpi>;
+
+pub type CpiId = NaiveDate;
+
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct CpiQuery {
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+}
+
+impl CpiQuery {
+    pub fn new(start_date: NaiveDate, end_date: NaiveDate) -> Self {
+        Self {
+            start_date,
+            end_date,
+        }
+    }
+
+    pub(crate) fn start_date(&self) -> &NaiveDate {
+        &self.start_date
+    }
+
+    pub(crate) fn end_date(&self) -> &NaiveDate {
+        &self.end_date
+    }
+
+    pub(crate) fn contains(&self, date: &NaiveDate) -> bool {
+        &self.start_date <= date && date <= &self.end_date
+    }
+}
+
+#[async_trait]
+pub trait CpiPersist: Send + Sync {
+    async fn create_cpi(&self, cpi: &Cpi) -> Result<CpiId, DomainError>;
+    /// Inserts in bulk, skipping months that already exist
+    async fn create_cpis(&self, cpis: &[Cpi]) -> Result<(), DomainError>;
+    async fn read_cpi_by_id(&self, id: &NaiveDate) -> Result<Cpi, DomainError>;
+    async fn update_cpi(&self, cpi: &Cpi) -> Result<(), DomainError>;
+    async fn delete_cpi_by_id(&self, id: &NaiveDate) -> Result<(), DomainError>;
+    async fn read_cpis_by_query(&self, query: &CpiQuery) -> Result<Cpis, DomainError>;
+}
+
+impl Cpi {
+    pub async fn create(&self, client: &dyn Persist) -> Result<CpiId, DomainError> {
+        client.create_cpi(self).await
+    }
+
+    pub async fn create_many(client: &dyn Persist, cpis: &[Cpi]) -> Result<(), DomainError> {
+        client.create_cpis(cpis).await
+    }
+
+    pub async fn read(client: &dyn Persist, id: &NaiveDate) -> Result<Cpi, DomainError> {
+        client.read_cpi_by_id(id).await
+    }
+
+    pub async fn update(&self, client: &dyn Persist) -> Result<(), DomainError> {
+        client.update_cpi(self).await
+    }
+
+    pub async fn delete(client: &dyn Persist, id: &NaiveDate) -> Result<(), DomainError> {
+        client.delete_cpi_by_id(id).await
+    }
+
+    pub async fn read_by_query(
+        client: &dyn Persist,
+        query: &CpiQuery,
+    ) -> Result<Cpis, DomainError> {
+        client.read_cpis_by_query(query).await
+    }
+}
+
+/// The month whose dollars real values are expressed in
+#[derive(Clone, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub enum CpiBase {
+    /// The latest month with a CPI
+    #[default]
+    Latest,
+    /// Any day of the month
+    Month(NaiveDate),
+}
+
+/// Converts nominal values into dollars of a base month
+pub(crate) struct Deflator {
+    base: f64,
+    monthly: BTreeMap<NaiveDate, f64>,
+}
+
+impl Deflator {
+    /// Reads the CPIs from `start_date` through `end_date` and the base month.
+    /// A base month without a CPI is a conversion error.
+    pub(crate) async fn read(
+        client: &dyn Persist,
+        base: &CpiBase,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<Self, DomainError> {
+        let (start_date, end_date) = match base {
+            CpiBase::Latest => (start_date, Utc::now().date_naive().max(end_date)),
+            CpiBase::Month(month) => (start_date.min(*month), end_date.max(*month)),
+        };
+        let query = CpiQuery::new(month_start(start_date), end_date);
+        let monthly: BTreeMap<NaiveDate, f64> = Cpi::read_by_query(client, &query)
+            .await?
+            .into_iter()
+            .map(|cpi| (month_start(cpi.date), cpi.value))
+            .collect();
+
+        let base = match base {
+            CpiBase::Latest => monthly.values().next_back().copied().ok_or_else(|| {
+                DomainError::ConvertDomain(format!("No CPI on or after {}", start_date))
+            })?,
+            CpiBase::Month(month) => {
+                monthly.get(&month_start(*month)).copied().ok_or_else(|| {
+                    DomainError::ConvertDomain(format!(
+                        "No CPI for base month {}",
+                        month.format("%Y-%m")
+                    ))
+                })?
+            }
+        };
+        Ok(Deflator { base, monthly })
+    }
+
+    /// `value` in base-month dollars, if its month has a CPI
+    pub(crate) fn deflate(&self, date: NaiveDate, value: f64) -> Option<f64> {
+        let cpi = self.monthly.get(&month_start(date))?;
+        Some(value * self.base / cpi)
+    }
+
+    /// Multiplier into base-month dollars for a value of all of `year`, by
+    /// the mean CPI of its months on hand
+    pub(crate) fn year_factor(&self, year: i32) -> Option<f64> {
+        Some(self.base / self.annual_average(year)?)
+    }
+
+    /// A nominal percent change over `year` net of that year's inflation
+    pub(crate) fn real_change(&self, year: i32, nominal_change: f64) -> Option<f64> {
+        let inflation = self.annual_average(year)? / self.annual_average(year - 1)?;
+        Some(((1.0 + nominal_change / 100.0) / inflation - 1.0) * 100.0)
+    }
+
+    fn annual_average(&self, year: i32) -> Option<f64> {
+        let start = NaiveDate::from_ymd_opt(year, 1, 1)?;
+        let end = NaiveDate::from_ymd_opt(year, 12, 31)?;
+        let values: Vec<f64> = self.monthly.range(start..=end).map(|(_, v)| *v).collect();
+        (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+    }
+}
+
+fn month_start(date: NaiveDate) -> NaiveDate {
+    date.with_day(1).unwrap_or(date)
+}
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct CpiConfig {
+    cpi_path: Option<String>,
+    series_id: Option<String>,
+}
+
+impl CpiConfig {
+    pub(crate) fn new(cpi_path: Option<String>, series_id: Option<String>) -> Self {
+        CpiConfig {
+            cpi_path,
+            series_id,
+        }
+    }
+
+    fn cpi_path(&self) -> Option<&str> {
+        self.cpi_path.as_deref()
+    }
+
+    fn series_id(&self) -> &str {
+        self.series_id.as_deref().unwrap_or(DEFAULT_SERIES_ID)
+    }
+}
+
+pub(crate) fn read_bls_cpis(
+    cpi_config: &CpiConfig,
+    report: &mut ImportReport,
+) -> Result<CpiData, DomainError> {
+    let mut cpi_data = CpiData::default();
+    if let Some(cpi_path) = cpi_config.cpi_path() {
+        cpi_data.cpis = read_bls_cpi_file(cpi_path, cpi_config.series_id(), report)?;
+    }
+    Ok(cpi_data)
+}
+
+/// BLS time series files are tab separated, the API's CSV exports are not
+fn delimiter(path: &str) -> Result<u8, DomainError> {
+    let file = File::open(path)
+        .map_err(|e| DomainError::Parse(format!("Failed to read {}: {}", path, e)))?;
+    let mut header = String::new();
+    BufReader::new(file)
+        .read_line(&mut header)
+        .map_err(|e| DomainError::Parse(format!("Failed to read {}: {}", path, e)))?;
+    Ok(if header.contains('\t') { b'\t' } else { b',' })
+}
+
+/// Month of a BLS period such as `M01`. `M13` is the annual average and `S01`
+/// to `S03` are half years, none of which are months.
+fn to_month(year: i32, period: &str) -> Option<NaiveDate> {
+    let month: u32 = period.strip_prefix('M')?.parse().ok()?;
+    NaiveDate::from_ymd_opt(year, month, 1)
+}
+
+/// Reads the monthly values of `series_id` from a BLS `series_id`, `year`,
+/// `period`, `value` file such as `cu.data.0.Current`. Other series are
+/// skipped.
+fn read_bls_cpi_file(
+    path: &str,
+    series_id: &str,
+    report: &mut ImportReport,
+) -> Result<Cpis, DomainError> {
+    let mut rdr = csv::ReaderBuilder::new()
+        .has_headers(true)
+        .delimiter(delimiter(path)?)
+        .flexible(true)
+        .from_path(path)?;
+
+    let header = CsvHeader::from_reader(path, &mut rdr)?;
+    let series_col = header.column("series_id")?;
+    let year_col = header.column("year")?;
+    let period_col = header.column("period")?;
+    let value_col = header.column("value")?;
+
+    let mut cpis = vec![];
+    for entry in header.records(&mut rdr, report) {
+        if !entry.get(series_col).eq_ignore_ascii_case(series_id) {
+            continue;
+        }
+        let period = entry.get(period_col);
+        // Months BLS could not collect, such as October 2025, are a dash
+        if !period.starts_with('M') || period == "M13" || entry.get(value_col) == "-" {
+            continue;
+        }
+        let mut row = header.row(&entry, report);
+        let year: Option<i32> = row.required(year_col);
+        let value: Option<f64> = row.required(value_col);
+        let date = year.and_then(|year| to_month(year, period));
+        if year.is_some() && date.is_none() {
+            row.error(period_col, format!("invalid value '{}'", period));
+        }
+        if let (true, Some(date), Some(value)) = (row.is_valid(), date, value) {
+            cpis.push(Cpi { date, value });
+        }
+    }
+
+    Ok(cpis)
+}
@@ -0,0 +1,208 @@
+use serde::{Deserialize, Serialize};
+
+use crate::adapter::repository::Persist;
+use crate::domain::affordability::{Affordability, AffordabilityPoint, AffordabilityQuery};
+use crate::domain::common::RegionType;
+use crate::domain::zhvi::{HomeType, Percentile};
+use crate::error::DomainError;
+
+// Two-sided 95% band under normal residuals
+const BAND_Z: f64 = 1.96;
+
+/// How the fair price-to-income ratio is modelled
+#[derive(Clone, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub enum FairValueModel {
+    /// The long-run mean ratio of price to median income
+    #[default]
+    PriceToIncome,
+    /// The ratio regressed on the mortgage rate, so cheaper credit justifies
+    /// higher prices
+    RateAdjusted,
+}
+
+impl TryFrom<&str> for FairValueModel {
+    type Error = crate::error::DomainError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        match s {
+            "pricetoincome" => Ok(FairValueModel::PriceToIncome),
+            "rateadjusted" => Ok(FairValueModel::RateAdjusted),
+            _ => Err(DomainError::Parse(
+                "Failed to parse FairValueModel".to_string(),
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for FairValueModel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FairValueModel::PriceToIncome => write!(f, "pricetoincome"),
+            FairValueModel::RateAdjusted => write!(f, "rateadjusted"),
+        }
+    }
+}
+
+/// The fitted ratio `intercept + slope * rate`, with `slope` fixed at zero
+/// for `PriceToIncome`
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct FairValueFit {
+    pub intercept: f64,
+    pub slope: f64,
+    /// Standard deviation of the ratio around the fit
+    pub std_error: f64,
+    /// `None` for `PriceToIncome`, which explains no variance
+    pub r_squared: Option<f64>,
+    pub observations: usize,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct FairValuePoint {
+    pub date: chrono::NaiveDate,
+    pub price: f64,
+    pub fair_value: f64,
+    /// Percent the price sits above (positive) or below fair value. `None`
+    /// where a rate-adjusted fit extrapolates to a fair value of zero or less.
+    pub residual: Option<f64>,
+    pub lower: f64,
+    pub upper: f64,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct FairValue {
+    pub region_name: String,
+    pub region_type: RegionType,
+    pub home_type: HomeType,
+    pub percentile: Percentile,
+    pub model: FairValueModel,
+    /// `None` without enough dates to fit the model
+    pub fit: Option<FairValueFit>,
+    pub points: Vec<FairValuePoint>,
+}
+
+pub type FairValues = Vec<FairValue>;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FairValueQuery {
+    affordability: AffordabilityQuery,
+    model: FairValueModel,
+}
+
+impl FairValueQuery {
+    pub fn new(affordability: AffordabilityQuery, model: FairValueModel) -> Self {
+        Self {
+            affordability,
+            model,
+        }
+    }
+}
+
+impl FairValue {
+    /// Fits the model per tier over the prices, rates and incomes that
+    /// `/affordability` lines up for the same query
+    pub async fn read_by_query(
+        client: &dyn Persist,
+        query: &FairValueQuery,
+    ) -> Result<FairValues, DomainError> {
+        let affordabilities = Affordability::read_by_query(client, &query.affordability).await?;
+        Ok(affordabilities
+            .into_iter()
+            .map(|affordability| {
+                let (fit, points) = match fair_values(&affordability.points, &query.model) {
+                    Some((fit, points)) => (Some(fit), points),
+                    None => (None, vec![]),
+                };
+                FairValue {
+                    region_name: affordability.region_name,
+                    region_type: affordability.region_type,
+                    home_type: affordability.home_type,
+                    percentile: affordability.percentile,
+                    model: query.model.clone(),
+                    fit,
+                    points,
+                }
+            })
+            .collect())
+    }
+}
+
+/// Fits `model` to the price-to-income ratios and values each date with it.
+/// `None` with fewer dates than the model has parameters plus one.
+pub fn fair_values(
+    points: &[AffordabilityPoint],
+    model: &FairValueModel,
+) -> Option<(FairValueFit, Vec<FairValuePoint>)> {
+    let ratios: Vec<f64> = points.iter().map(|p| p.price / p.median_income).collect();
+    let rates: Vec<f64> = points.iter().map(|p| p.rate).collect();
+    let count = ratios.len() as f64;
+    let parameters = match model {
+        FairValueModel::PriceToIncome => 1,
+        FairValueModel::RateAdjusted => 2,
+    };
+    if ratios.len() <= parameters {
+        return None;
+    }
+
+    let mean_ratio = ratios.iter().sum::<f64>() / count;
+    let (intercept, slope) = match model {
+        FairValueModel::PriceToIncome => (mean_ratio, 0.0),
+        FairValueModel::RateAdjusted => {
+            let mean_rate = rates.iter().sum::<f64>() / count;
+            let covariance: f64 = rates
+                .iter()
+                .zip(&ratios)
+                .map(|(rate, ratio)| (rate - mean_rate) * (ratio - mean_ratio))
+                .sum();
+            let variance: f64 = rates.iter().map(|rate| (rate - mean_rate).powi(2)).sum();
+            // A rate that never moved can't explain anything
+            let slope = match variance > 0.0 {
+                true => covariance / variance,
+                false => 0.0,
+            };
+            (mean_ratio - slope * mean_rate, slope)
+        }
+    };
+
+    let fitted: Vec<f64> = rates.iter().map(|rate| intercept + slope * rate).collect();
+    let residual_sum: f64 = ratios
+        .iter()
+        .zip(&fitted)
+        .map(|(ratio, fit)| (ratio - fit).powi(2))
+        .sum();
+    let std_error = (residual_sum / (count - parameters as f64)).sqrt();
+    let r_squared = match model {
+        FairValueModel::PriceToIncome => None,
+        FairValueModel::RateAdjusted => {
+            let total: f64 = ratios.iter().map(|r| (r - mean_ratio).powi(2)).sum();
+            Some(match total > 0.0 {
+                true => 1.0 - residual_sum / total,
+                false => 1.0,
+            })
+        }
+    };
+
+    let values = points
+        .iter()
+        .zip(&fitted)
+        .map(|(point, fit)| {
+            let fair_value = fit * point.median_income;
+            let band = BAND_Z * std_error * point.median_income;
+            FairValuePoint {
+                date: point.date,
+                price: point.price,
+                fair_value,
+                residual: (fair_value > 0.0).then(|| (point.price / fair_value - 1.0) * 100.0),
+                lower: (fair_value - band).max(0.0),
+                upper: fair_value + band,
+            }
+        })
+        .collect();
+    let fit = FairValueFit {
+        intercept,
+        slope,
+        std_error,
+        r_squared,
+        observations: points.len(),
+    };
+    Some((fit, values))
+}
@@ -0,0 +1,480 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::adapter::repository::Persist;
+use crate::domain::common::{DateInterval, RegionType};
+use crate::domain::zhvi::{
+    is_false, month_after, month_index, HomeType, Percentile, Zhvi, ZhviPrice, ZhviQuery,
+};
+use crate::error::DomainError;
+
+// Two-sided 95% prediction interval under normal errors
+const BAND_Z: f64 = 1.96;
+/// Latest points of the history each model is refit at to score it
+pub const BACKTEST_ORIGINS: usize = 12;
+// Smoothing parameters searched when fitting Holt-Winters
+const SMOOTHING_GRID: [f64; 5] = [0.1, 0.3, 0.5, 0.7, 0.9];
+const DAMPING_GRID: [f64; 4] = [0.8, 0.9, 0.98, 1.0];
+
+/// How a series is projected
+#[derive(Clone, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize, ToSchema)]
+pub enum ForecastModel {
+    /// Additive Holt-Winters exponential smoothing with a damped trend
+    #[default]
+    HoltWinters,
+    /// The value one season earlier, the baseline to beat
+    SeasonalNaive,
+}
+
+impl ForecastModel {
+    pub(crate) const ALL: [ForecastModel; 2] =
+        [ForecastModel::HoltWinters, ForecastModel::SeasonalNaive];
+
+    /// Fewest observations the model can be fitted to
+    fn min_observations(&self, season: usize) -> usize {
+        match (self, season) {
+            (ForecastModel::HoltWinters, 1) => 3,
+            // A season each for the initial level and trend, and one to fit
+            (ForecastModel::HoltWinters, _) => 2 * season + 1,
+            (ForecastModel::SeasonalNaive, _) => season + 1,
+        }
+    }
+}
+
+impl TryFrom<&str> for ForecastModel {
+    type Error = crate::error::DomainError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        match s {
+            "holtwinters" => Ok(ForecastModel::HoltWinters),
+            "seasonalnaive" => Ok(ForecastModel::SeasonalNaive),
+            _ => Err(DomainError::Parse(
+                "Failed to parse ForecastModel".to_string(),
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for ForecastModel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ForecastModel::HoltWinters => write!(f, "holtwinters"),
+            ForecastModel::SeasonalNaive => write!(f, "seasonalnaive"),
+        }
+    }
+}
+
+/// Holt-Winters smoothing weights for the level, trend and season, and the
+/// trend's damping
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize, ToSchema)]
+pub struct Smoothing {
+    pub alpha: f64,
+    pub beta: f64,
+    pub gamma: f64,
+    pub phi: f64,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize, ToSchema)]
+pub struct ForecastFit {
+    /// Observations per season, 1 for annual series
+    pub season: usize,
+    /// `None` for `SeasonalNaive`, which has no parameters
+    pub smoothing: Option<Smoothing>,
+    /// Standard deviation of the one-step-ahead errors
+    pub std_error: f64,
+    pub observations: usize,
+}
+
+/// Errors of forecasts made from each of the last `BACKTEST_ORIGINS` points
+/// of the history, up to the horizon ahead
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize, ToSchema)]
+pub struct Backtest {
+    pub model: ForecastModel,
+    pub mae: f64,
+    pub rmse: f64,
+    /// Mean absolute percent error
+    pub mape: f64,
+    pub errors: usize,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize, ToSchema)]
+pub struct ForecastPoint {
+    pub date: NaiveDate,
+    pub value: f64,
+    /// Projected by the model rather than observed
+    pub forecast: bool,
+    /// Observed points built from a filled month, see `GapFill`
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub filled: bool,
+    /// Prediction interval of forecast points
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lower: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub upper: Option<f64>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, ToSchema)]
+pub struct ZhviForecast {
+    pub region_name: String,
+    pub region_type: RegionType,
+    pub home_type: HomeType,
+    pub percentile: Percentile,
+    pub model: ForecastModel,
+    /// `None` without enough history to fit the model, or with months missing
+    /// from it, leaving only the observed points
+    pub fit: Option<ForecastFit>,
+    /// Every model with enough history, to compare `model` to the baseline.
+    /// Empty for a series with months missing.
+    pub backtests: Vec<Backtest>,
+    /// The observed prices followed by the forecast
+    pub points: Vec<ForecastPoint>,
+}
+
+pub type ZhviForecasts = Vec<ZhviForecast>;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ForecastQuery {
+    zhvi: ZhviQuery,
+    horizon: usize,
+    model: ForecastModel,
+}
+
+impl ForecastQuery {
+    pub fn new(zhvi: ZhviQuery, horizon: usize, model: ForecastModel) -> Self {
+        Self {
+            zhvi,
+            horizon,
+            model,
+        }
+    }
+}
+
+/// One projected value with its prediction interval
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Projection {
+    pub value: f64,
+    pub lower: f64,
+    pub upper: f64,
+}
+
+/// Observations per season of a ZHVI series resampled to `date_interval`.
+/// Daily ZHVIs are the stored monthly prices.
+fn season_length(date_interval: &DateInterval) -> Option<usize> {
+    match date_interval {
+        DateInterval::Day | DateInterval::Month => Some(12),
+        DateInterval::Quarter => Some(4),
+        DateInterval::Year => Some(1),
+        DateInterval::Week => None,
+    }
+}
+
+/// Months between observations of a ZHVI series resampled to `date_interval`
+fn step_months(date_interval: &DateInterval) -> u32 {
+    match date_interval {
+        DateInterval::Quarter => 3,
+        DateInterval::Year => 12,
+        _ => 1,
+    }
+}
+
+/// Whether each price falls `step` months after the one before, as the
+/// models assume
+fn evenly_spaced(prices: &[ZhviPrice], step: u32) -> bool {
+    prices
+        .windows(2)
+        .all(|pair| month_index(pair[1].date) - month_index(pair[0].date) == step as i32)
+}
+
+impl ZhviForecast {
+    /// Projects each ZHVI series that `/zhvis` returns for the same query
+    /// `horizon` intervals past its last price
+    pub async fn read_by_query(
+        client: &dyn Persist,
+        query: &ForecastQuery,
+    ) -> Result<ZhviForecasts, DomainError> {
+        let date_interval = query.zhvi.date_interval();
+        let season = season_length(date_interval).ok_or_else(|| {
+            DomainError::ConvertDomain(
+                "Forecasts need a day, month, quarter or year interval".to_string(),
+            )
+        })?;
+        let step = step_months(date_interval);
+
+        let zhvis = Zhvi::read_by_query(client, &query.zhvi).await?;
+        Ok(zhvis
+            .into_iter()
+            .map(|zhvi| {
+                let values: Vec<f64> = zhvi.prices.iter().map(|price| price.value).collect();
+                // A series with missing months is neither fit nor scored
+                let spaced = evenly_spaced(&zhvi.prices, step);
+                let mut points: Vec<ForecastPoint> = zhvi
+                    .prices
+                    .iter()
+                    .map(|price| ForecastPoint {
+                        date: price.date,
+                        value: price.value,
+                        filled: price.filled,
+                        ..Default::default()
+                    })
+                    .collect();
+
+                let fit = spaced
+                    .then(|| project(&values, season, query.horizon, &query.model))
+                    .flatten()
+                    .map(|(fit, projections)| {
+                        let last = zhvi.prices.last().map(|price| price.date);
+                        let dates = (1..=query.horizon as u32)
+                            .map_while(|k| last.and_then(|last| month_after(last, k * step)));
+                        points.extend(dates.zip(projections).map(|(date, projection)| {
+                            ForecastPoint {
+                                date,
+                                value: projection.value,
+                                forecast: true,
+                                filled: false,
+                                lower: Some(projection.lower),
+                                upper: Some(projection.upper),
+                            }
+                        }));
+                        fit
+                    });
+                let backtests = ForecastModel::ALL
+                    .iter()
+                    .filter(|_| spaced)
+                    .filter_map(|model| backtest(&values, season, query.horizon, model))
+                    .collect();
+
+                ZhviForecast {
+                    region_name: zhvi.region_name,
+                    region_type: zhvi.region_type,
+                    home_type: zhvi.home_type,
+                    percentile: zhvi.percentile,
+                    model: query.model.clone(),
+                    fit,
+                    backtests,
+                    points,
+                }
+            })
+            .collect())
+    }
+}
+
+/// Fits `model` to evenly spaced `values` and projects `horizon` steps past
+/// the last. `None` with fewer values than the model needs.
+pub fn project(
+    values: &[f64],
+    season: usize,
+    horizon: usize,
+    model: &ForecastModel,
+) -> Option<(ForecastFit, Vec<Projection>)> {
+    let season = season.max(1);
+    if values.len() < model.min_observations(season) {
+        return None;
+    }
+    match model {
+        ForecastModel::HoltWinters => {
+            let fit = fit_holt_winters(values, season)?;
+            Some((fit.forecast_fit(), fit.project(horizon)))
+        }
+        ForecastModel::SeasonalNaive => Some(seasonal_naive(values, season, horizon)),
+    }
+}
+
+/// Refits `model` at each of the last `BACKTEST_ORIGINS` points it has enough
+/// history before, and scores its forecasts up to `horizon` steps ahead
+/// against what was observed. `None` without any such point.
+pub fn backtest(
+    values: &[f64],
+    season: usize,
+    horizon: usize,
+    model: &ForecastModel,
+) -> Option<Backtest> {
+    let first = model
+        .min_observations(season.max(1))
+        .max(values.len().saturating_sub(BACKTEST_ORIGINS));
+    let mut errors = vec![];
+    let mut percent_errors = vec![];
+    for origin in first..values.len() {
+        let Some((_, projections)) = project(&values[..origin], season, horizon, model) else {
+            continue;
+        };
+        for (projection, actual) in projections.iter().zip(&values[origin..]) {
+            let error = actual - projection.value;
+            errors.push(error);
+            if *actual != 0.0 {
+                percent_errors.push((error / actual).abs() * 100.0);
+            }
+        }
+    }
+    if errors.is_empty() {
+        return None;
+    }
+
+    let count = errors.len() as f64;
+    Some(Backtest {
+        model: model.clone(),
+        mae: errors.iter().map(|e| e.abs()).sum::<f64>() / count,
+        rmse: (errors.iter().map(|e| e * e).sum::<f64>() / count).sqrt(),
+        mape: percent_errors.iter().sum::<f64>() / percent_errors.len().max(1) as f64,
+        errors: errors.len(),
+    })
+}
+
+/// Additive Holt-Winters state after the last observation
+struct HoltWinters {
+    smoothing: Smoothing,
+    level: f64,
+    trend: f64,
+    /// Indexed by position in the series modulo the season
+    seasonals: Vec<f64>,
+    sse: f64,
+    observations: usize,
+}
+
+impl HoltWinters {
+    /// Runs the smoothing over `values` from the first season's averages
+    fn run(values: &[f64], season: usize, smoothing: Smoothing) -> Self {
+        let Smoothing {
+            alpha,
+            beta,
+            gamma,
+            phi,
+        } = smoothing;
+        let (mut level, mut trend, mut seasonals) = if season == 1 {
+            (values[0], values[1] - values[0], vec![0.0])
+        } else {
+            let first = values[..season].iter().sum::<f64>() / season as f64;
+            let second = values[season..2 * season].iter().sum::<f64>() / season as f64;
+            let seasonals = values[..season].iter().map(|v| v - first).collect();
+            (first, (second - first) / season as f64, seasonals)
+        };
+
+        let mut sse = 0.0;
+        for (t, value) in values.iter().enumerate() {
+            let i = t % season;
+            let error = value - (level + phi * trend + seasonals[i]);
+            sse += error * error;
+            let previous = level;
+            level = alpha * (value - seasonals[i]) + (1.0 - alpha) * (level + phi * trend);
+            trend = beta * (level - previous) + (1.0 - beta) * phi * trend;
+            if season > 1 {
+                seasonals[i] = gamma * (value - level) + (1.0 - gamma) * seasonals[i];
+            }
+        }
+        HoltWinters {
+            smoothing,
+            level,
+            trend,
+            seasonals,
+            sse,
+            observations: values.len(),
+        }
+    }
+
+    fn variance(&self) -> f64 {
+        self.sse / self.observations as f64
+    }
+
+    fn forecast_fit(&self) -> ForecastFit {
+        ForecastFit {
+            season: self.seasonals.len(),
+            smoothing: Some(self.smoothing.clone()),
+            std_error: self.variance().sqrt(),
+            observations: self.observations,
+        }
+    }
+
+    /// Intervals widen by the ETS(A,Ad,A) variance of the h-step error
+    fn project(&self, horizon: usize) -> Vec<Projection> {
+        let Smoothing {
+            alpha,
+            beta,
+            gamma,
+            phi,
+        } = self.smoothing;
+        let season = self.seasonals.len();
+        let variance = self.variance();
+        let n = self.observations;
+
+        let mut projections = vec![];
+        let mut damping = 0.0;
+        let mut spread = 1.0;
+        for h in 1..=horizon {
+            damping += phi.powi(h as i32);
+            let value = self.level + damping * self.trend + self.seasonals[(n + h - 1) % season];
+            let band = BAND_Z * (variance * spread).sqrt();
+            projections.push(Projection {
+                value,
+                lower: value - band,
+                upper: value + band,
+            });
+            // The next step's error carries this step's through the states
+            let seasonal = if season > 1 && h % season == 0 {
+                gamma * (1.0 - alpha)
+            } else {
+                0.0
+            };
+            let c = alpha * (1.0 + beta * damping) + seasonal;
+            spread += c * c;
+        }
+        projections
+    }
+}
+
+/// The smoothing on the grid with the least one-step-ahead squared error
+fn fit_holt_winters(values: &[f64], season: usize) -> Option<HoltWinters> {
+    // Without a season there is nothing for gamma to smooth
+    let gammas: &[f64] = if season == 1 { &[0.0] } else { &SMOOTHING_GRID };
+    let mut best: Option<HoltWinters> = None;
+    for &alpha in &SMOOTHING_GRID {
+        for &beta in &SMOOTHING_GRID {
+            for &gamma in gammas {
+                for &phi in &DAMPING_GRID {
+                    let smoothing = Smoothing {
+                        alpha,
+                        beta,
+                        gamma,
+                        phi,
+                    };
+                    let fit = HoltWinters::run(values, season, smoothing);
+                    if fit.sse.is_finite() && best.as_ref().map_or(true, |best| fit.sse < best.sse)
+                    {
+                        best = Some(fit);
+                    }
+                }
+            }
+        }
+    }
+    best
+}
+
+/// Repeats the last season. The error of a step k seasons out adds up k + 1
+/// seasonal random walk errors.
+fn seasonal_naive(values: &[f64], season: usize, horizon: usize) -> (ForecastFit, Vec<Projection>) {
+    let errors: Vec<f64> = values
+        .windows(season + 1)
+        .map(|window| window[season] - window[0])
+        .collect();
+    let variance = errors.iter().map(|e| e * e).sum::<f64>() / errors.len() as f64;
+    let n = values.len();
+
+    let projections = (1..=horizon)
+        .map(|h| {
+            let seasons = (h - 1) / season;
+            let value = values[n - 1 + h - season * (seasons + 1)];
+            let band = BAND_Z * (variance * (seasons + 1) as f64).sqrt();
+            Projection {
+                value,
+                lower: value - band,
+                upper: value + band,
+            }
+        })
+        .collect();
+    let fit = ForecastFit {
+        season,
+        smoothing: None,
+        std_error: variance.sqrt(),
+        observations: n,
+    };
+    (fit, projections)
+}
@@ -1,15 +1,23 @@
 use async_trait::async_trait;
+use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 
 use super::common::RegionType;
+use super::cpi::{CpiBase, Deflator};
+use crate::adapter::importer::ImportReport;
 use crate::adapter::repository::Persist;
-use crate::domain::util::CsvRecord;
+use crate::domain::util::CsvHeader;
 use crate::error::DomainError;
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize, sqlx::FromRow)]
 pub struct Hpi {
     pub(crate) region_type: RegionType,
+    /// Zip code, county FIPS, state abbreviation or CBSA code, unique within
+    /// `region_type`
+    pub(crate) region_id: String,
     pub(crate) region_name: String,
+    /// USPS state abbreviation of county and state HPIs
+    pub(crate) state: Option<String>,
     pub(crate) year: i32,
     pub(crate) hpi: Option<f32>,
     pub(crate) annual_change: Option<f32>,
@@ -22,10 +30,20 @@ impl Hpi {
         &self.region_type
     }
 
+    pub(crate) fn region_id(&self) -> &str {
+        &self.region_id
+    }
+
     pub(crate) fn region_name(&self) -> &String {
         &self.region_name
     }
 
+    // Only the SQL backends and tests read the state
+    #[cfg(any(feature = "postgres", feature = "sqlite", test))]
+    pub(crate) fn state(&self) -> Option<&str> {
+        self.state.as_deref()
+    }
+
     pub(crate) fn year(&self) -> i32 {
         self.year
     }
@@ -34,14 +52,18 @@ impl Hpi {
         self.hpi
     }
 
+    // Only the SQL backends and tests read these
+    #[cfg(any(feature = "postgres", feature = "sqlite", test))]
     pub(crate) fn annual_change(&self) -> Option<f32> {
         self.annual_change
     }
 
+    #[cfg(any(feature = "postgres", feature = "sqlite", test))]
     pub(crate) fn hpi_1990_base(&self) -> Option<f32> {
         self.hpi_1990_base
     }
 
+    #[cfg(any(feature = "postgres", feature = "sqlite", test))]
     pub(crate) fn hpi_2000_base(&self) -> Option<f32> {
         self.hpi_2000_base
     }
@@ -52,6 +74,9 @@ pub struct HpiData {
     three_zip_hpis: Hpis,
     five_zip_hpis: Hpis,
     county_hpis: Hpis,
+    state_hpis: Hpis,
+    msa_hpis: Hpis,
+    national_hpis: Hpis,
 }
 
 impl HpiData {
@@ -66,31 +91,68 @@ impl HpiData {
     pub fn county_hpis(&self) -> &Hpis {
         &self.county_hpis
     }
+
+    pub fn state_hpis(&self) -> &Hpis {
+        &self.state_hpis
+    }
+
+    pub fn msa_hpis(&self) -> &Hpis {
+        &self.msa_hpis
+    }
+
+    pub fn national_hpis(&self) -> &Hpis {
+        &self.national_hpis
+    }
 }
 
 pub type Hpis = Vec<Hpi>;
+/// `(region_type, region_id, year)`
+pub type HpiId = (RegionType, String, i32);
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
 pub struct HpiQuery {
-    // region_type: RegionType
-    region_name: String,
+    region_type: RegionType,
+    region_id: String,
     start_date: i32,
     end_date: i32,
+    real: Option<CpiBase>,
     // annual_change: Option<bool>,
     // hpi_2000_base: Option<bool>,
 }
 
 impl HpiQuery {
-    pub fn new(region_name: String, start_date: i32, end_date: i32) -> Self {
+    pub fn new(region_type: RegionType, region_id: String, start_date: i32, end_date: i32) -> Self {
         Self {
-            region_name,
+            region_type,
+            region_id,
             start_date,
             end_date,
+            real: None,
         }
     }
 
-    pub(crate) fn region_name(&self) -> &str {
-        &self.region_name
+    /// Deflates each year by its mean CPI to dollars of `base`, and nets
+    /// inflation out of the annual change. Years without a CPI are left out.
+    pub fn with_real(mut self, base: CpiBase) -> Self {
+        self.real = Some(base);
+        self
+    }
+
+    /// The same query for another region
+    pub(crate) fn for_region(&self, region_type: RegionType, region_id: String) -> Self {
+        HpiQuery {
+            region_type,
+            region_id,
+            ..self.clone()
+        }
+    }
+
+    pub(crate) fn region_type(&self) -> &RegionType {
+        &self.region_type
+    }
+
+    pub(crate) fn region_id(&self) -> &str {
+        &self.region_id
     }
 
     pub(crate) fn start_date(&self) -> i32 {
@@ -104,19 +166,28 @@ impl HpiQuery {
 
 #[async_trait]
 pub trait HpiPersist: Send + Sync {
-    async fn create_hpi(&self, hpi: &Hpi) -> Result<(String, i32), DomainError>;
-    async fn read_hpi_by_id(&self, id: (&str, i32)) -> Result<Hpi, DomainError>;
+    async fn create_hpi(&self, hpi: &Hpi) -> Result<HpiId, DomainError>;
+    /// Inserts in bulk, skipping HPIs that already exist
+    async fn create_hpis(&self, hpis: &[Hpi]) -> Result<(), DomainError>;
+    async fn read_hpi_by_id(&self, id: (&RegionType, &str, i32)) -> Result<Hpi, DomainError>;
     async fn update_hpi(&self, hpi: &Hpi) -> Result<(), DomainError>;
-    async fn delete_hpi_by_id(&self, id: (&str, i32)) -> Result<(), DomainError>;
+    async fn delete_hpi_by_id(&self, id: (&RegionType, &str, i32)) -> Result<(), DomainError>;
     async fn read_hpi_by_query(&self, query: &HpiQuery) -> Result<Hpis, DomainError>;
 }
 
 impl Hpi {
-    pub async fn create(&self, client: &dyn Persist) -> Result<(String, i32), DomainError> {
+    pub async fn create(&self, client: &dyn Persist) -> Result<HpiId, DomainError> {
         client.create_hpi(self).await
     }
 
-    pub async fn read(client: &dyn Persist, id: (&str, i32)) -> Result<Hpi, DomainError> {
+    pub async fn create_many(client: &dyn Persist, hpis: &[Hpi]) -> Result<(), DomainError> {
+        client.create_hpis(hpis).await
+    }
+
+    pub async fn read(
+        client: &dyn Persist,
+        id: (&RegionType, &str, i32),
+    ) -> Result<Hpi, DomainError> {
         client.read_hpi_by_id(id).await
     }
 
@@ -124,7 +195,10 @@ impl Hpi {
         client.update_hpi(self).await
     }
 
-    pub async fn delete(client: &dyn Persist, id: (&str, i32)) -> Result<(), DomainError> {
+    pub async fn delete(
+        client: &dyn Persist,
+        id: (&RegionType, &str, i32),
+    ) -> Result<(), DomainError> {
         client.delete_hpi_by_id(id).await
     }
 
@@ -132,7 +206,35 @@ impl Hpi {
         client: &dyn Persist,
         query: &HpiQuery,
     ) -> Result<Hpis, DomainError> {
-        client.read_hpi_by_query(query).await
+        let hpis = client.read_hpi_by_query(query).await?;
+        let Some(base) = &query.real else {
+            return Ok(hpis);
+        };
+        // The year before the first carries the first year's inflation
+        let start_date = NaiveDate::from_ymd_opt(query.start_date - 1, 1, 1);
+        let end_date = NaiveDate::from_ymd_opt(query.end_date, 12, 31);
+        let (Some(start_date), Some(end_date)) = (start_date, end_date) else {
+            return Err(DomainError::ConvertDomain(format!(
+                "Invalid years {} to {}",
+                query.start_date, query.end_date
+            )));
+        };
+        let deflator = Deflator::read(client, base, start_date, end_date).await?;
+        let hpis = hpis
+            .into_iter()
+            .filter_map(|mut hpi| {
+                let factor = deflator.year_factor(hpi.year)? as f32;
+                hpi.hpi = hpi.hpi.map(|value| value * factor);
+                hpi.hpi_1990_base = hpi.hpi_1990_base.map(|value| value * factor);
+                hpi.hpi_2000_base = hpi.hpi_2000_base.map(|value| value * factor);
+                hpi.annual_change = hpi
+                    .annual_change
+                    .and_then(|change| deflator.real_change(hpi.year, change as f64))
+                    .map(|change| change as f32);
+                Some(hpi)
+            })
+            .collect();
+        Ok(hpis)
     }
 }
 
@@ -141,6 +243,9 @@ pub(crate) struct HpiConfig {
     three_zip_hpis_path: Option<String>,
     five_zip_hpis_path: Option<String>,
     county_hpis_path: Option<String>,
+    state_hpis_path: Option<String>,
+    msa_hpis_path: Option<String>,
+    national_hpis_path: Option<String>,
 }
 
 impl HpiConfig {
@@ -148,11 +253,17 @@ impl HpiConfig {
         three_zip_hpis_path: Option<String>,
         five_zip_hpis_path: Option<String>,
         county_hpis_path: Option<String>,
+        state_hpis_path: Option<String>,
+        msa_hpis_path: Option<String>,
+        national_hpis_path: Option<String>,
     ) -> Self {
         HpiConfig {
             three_zip_hpis_path,
             five_zip_hpis_path,
             county_hpis_path,
+            state_hpis_path,
+            msa_hpis_path,
+            national_hpis_path,
         }
     }
 
@@ -167,105 +278,126 @@ impl HpiConfig {
     fn county_hpi_path(&self) -> Option<&str> {
         self.county_hpis_path.as_deref()
     }
-}
-
-pub(crate) fn read_fhfa_hpis(hpi_config: &HpiConfig) -> Result<HpiData, DomainError> {
-    let mut hpi_data = HpiData::default();
 
-    if let Some(three_zpi_hpi_path) = hpi_config.three_zip_hpi_path() {
-        hpi_data.three_zip_hpis = read_three_zip_fhfa_hpis(three_zpi_hpi_path)?;
+    fn state_hpi_path(&self) -> Option<&str> {
+        self.state_hpis_path.as_deref()
     }
-    if let Some(five_zpi_hpi_path) = hpi_config.five_zip_hpi_path() {
-        hpi_data.five_zip_hpis = read_five_zip_fhfa_hpis(five_zpi_hpi_path)?;
+
+    fn msa_hpi_path(&self) -> Option<&str> {
+        self.msa_hpis_path.as_deref()
     }
-    if let Some(county_hpi_path) = hpi_config.county_hpi_path() {
-        hpi_data.county_hpis = read_county_fhfa_hpis(county_hpi_path)?;
+
+    fn national_hpi_path(&self) -> Option<&str> {
+        self.national_hpis_path.as_deref()
     }
-    Ok(hpi_data)
 }
 
-fn read_three_zip_fhfa_hpis(three_zip_path: &str) -> Result<Hpis, DomainError> {
-    let mut rdr = csv::ReaderBuilder::new()
-        .has_headers(true)
-        .from_path(three_zip_path)?;
-    let entries: Vec<CsvRecord> = rdr.deserialize().filter_map(Result::ok).collect();
-    Ok(entries
-        .into_iter()
-        .map(|entry| {
-            let region_type = RegionType::ThreeZip;
-            let region_name = entry.0[0].clone();
-            let year = entry.0[1].parse().unwrap();
-            let annual_change = entry.0[2].parse().ok();
-            let hpi = entry.0[3].parse().ok();
-            let hpi_1990_base = entry.0[4].parse().ok();
-            let hpi_2000_base = entry.0[5].parse().ok();
-            Hpi {
-                region_type,
-                region_name,
-                year,
-                annual_change,
-                hpi,
-                hpi_1990_base,
-                hpi_2000_base,
-            }
-        })
-        .collect())
+/// Where a FHFA annual file keeps its region. `id` is `None` for the national
+/// file, which covers a single region.
+struct FhfaColumns {
+    id: Option<&'static str>,
+    name: Option<&'static str>,
+    state: Option<&'static str>,
 }
 
-fn read_five_zip_fhfa_hpis(five_zip_path: &str) -> Result<Hpis, DomainError> {
-    let mut rdr = csv::ReaderBuilder::new()
-        .has_headers(true)
-        .from_path(five_zip_path)?;
-
-    let entries: Vec<CsvRecord> = rdr.deserialize().filter_map(Result::ok).collect();
-    Ok(entries
-        .into_iter()
-        .map(|entry| {
-            let region_type = RegionType::FiveZip;
-            let region_name = entry.0[0].clone();
-            let year = entry.0[1].parse().unwrap();
-            let annual_change = entry.0[2].parse().ok();
-            let hpi = entry.0[3].parse().ok();
-            let hpi_1990_base = entry.0[4].parse().ok();
-            let hpi_2000_base = entry.0[5].parse().ok();
-            Hpi {
-                region_type,
-                region_name,
-                year,
-                annual_change,
-                hpi,
-                hpi_1990_base,
-                hpi_2000_base,
-            }
-        })
-        .collect())
+impl FhfaColumns {
+    fn of(region_type: &RegionType) -> Self {
+        let (id, name, state) = match region_type {
+            RegionType::ThreeZip => (Some("Three-Digit ZIP Code"), None, None),
+            RegionType::FiveZip => (Some("Five-Digit ZIP Code"), None, None),
+            RegionType::City => (Some("City"), None, None),
+            RegionType::County => (Some("FIPS code"), Some("County"), Some("State")),
+            RegionType::State => (Some("Abbreviation"), Some("State"), Some("Abbreviation")),
+            RegionType::Msa => (Some("CBSA"), Some("Name"), None),
+            RegionType::National => (None, None, None),
+        };
+        Self { id, name, state }
+    }
 }
 
-fn read_county_fhfa_hpis(county_path: &str) -> Result<Hpis, DomainError> {
+const NATIONAL_ID: &str = "USA";
+const NATIONAL_NAME: &str = "United States";
+
+pub(crate) fn read_fhfa_hpis(
+    hpi_config: &HpiConfig,
+    report: &mut ImportReport,
+) -> Result<HpiData, DomainError> {
+    let mut hpi_data = HpiData::default();
+
+    if let Some(path) = hpi_config.three_zip_hpi_path() {
+        hpi_data.three_zip_hpis = read_fhfa_hpi_file(path, RegionType::ThreeZip, report)?;
+    }
+    if let Some(path) = hpi_config.five_zip_hpi_path() {
+        hpi_data.five_zip_hpis = read_fhfa_hpi_file(path, RegionType::FiveZip, report)?;
+    }
+    if let Some(path) = hpi_config.county_hpi_path() {
+        hpi_data.county_hpis = read_fhfa_hpi_file(path, RegionType::County, report)?;
+    }
+    if let Some(path) = hpi_config.state_hpi_path() {
+        hpi_data.state_hpis = read_fhfa_hpi_file(path, RegionType::State, report)?;
+    }
+    if let Some(path) = hpi_config.msa_hpi_path() {
+        hpi_data.msa_hpis = read_fhfa_hpi_file(path, RegionType::Msa, report)?;
+    }
+    if let Some(path) = hpi_config.national_hpi_path() {
+        hpi_data.national_hpis = read_fhfa_hpi_file(path, RegionType::National, report)?;
+    }
+    Ok(hpi_data)
+}
+
+fn read_fhfa_hpi_file(
+    path: &str,
+    region_type: RegionType,
+    report: &mut ImportReport,
+) -> Result<Hpis, DomainError> {
     let mut rdr = csv::ReaderBuilder::new()
         .has_headers(true)
-        .from_path(county_path)?;
-
-    let entries: Vec<CsvRecord> = rdr.deserialize().filter_map(Result::ok).collect();
-    Ok(entries
-        .into_iter()
-        .map(|entry| {
-            let region_type = RegionType::County;
-            let region_name = entry.0[1].clone();
-            let year = entry.0[3].parse().unwrap();
-            let annual_change = entry.0[4].parse().ok();
-            let hpi = entry.0[5].parse().ok();
-            let hpi_1990_base = entry.0[6].parse().ok();
-            let hpi_2000_base = entry.0[7].parse().ok();
-            Hpi {
-                region_type,
+        .from_path(path)?;
+    let header = CsvHeader::from_reader(path, &mut rdr)?;
+    let columns = FhfaColumns::of(&region_type);
+    let region_id_col = columns.id.map(|id| header.column(id)).transpose()?;
+    let region_name_col = columns.name.map(|name| header.column(name)).transpose()?;
+    let state_col = columns
+        .state
+        .map(|state| header.column(state))
+        .transpose()?;
+    let year_col = header.column("Year")?;
+    let annual_change_col = header.column("Annual Change (%)")?;
+    let hpi_col = header.column("HPI")?;
+    let hpi_1990_base_col = header.column("HPI with 1990 base")?;
+    let hpi_2000_base_col = header.column("HPI with 2000 base")?;
+
+    let mut hpis = vec![];
+    for entry in header.records(&mut rdr, report) {
+        let mut row = header.row(&entry, report);
+        let region_id = match region_id_col {
+            Some(col) => row.text(col),
+            None => NATIONAL_ID.to_string(),
+        };
+        let region_name = match (region_name_col, region_id_col) {
+            (Some(col), _) => row.text(col),
+            (None, Some(_)) => region_id.clone(),
+            (None, None) => NATIONAL_NAME.to_string(),
+        };
+        let state = state_col.map(|col| row.text(col));
+        let year = row.required(year_col);
+        let annual_change = row.optional(annual_change_col);
+        let hpi = row.optional(hpi_col);
+        let hpi_1990_base = row.optional(hpi_1990_base_col);
+        let hpi_2000_base = row.optional(hpi_2000_base_col);
+        if let (true, Some(year)) = (row.is_valid(), year) {
+            hpis.push(Hpi {
+                region_type: region_type.clone(),
+                region_id,
                 region_name,
+                state,
                 year,
                 annual_change,
                 hpi,
                 hpi_1990_base,
                 hpi_2000_base,
-            }
-        })
-        .collect())
+            });
+        }
+    }
+    Ok(hpis)
 }
@@ -0,0 +1,342 @@
+use std::fs;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use super::common::RegionType;
+use crate::adapter::importer::ImportReport;
+use crate::adapter::repository::Persist;
+use crate::domain::util::CsvHeader;
+use crate::error::DomainError;
+
+/// Median household income from the Census ACS table B19013, in dollars of
+/// the survey year
+#[derive(Clone, Debug, Default, Deserialize, Serialize, sqlx::FromRow)]
+pub struct Income {
+    pub(crate) region_type: RegionType,
+    /// Zip code, county or place FIPS, state FIPS or CBSA code, unique within
+    /// `region_type`
+    pub(crate) region_id: String,
+    /// Named the way Zillow names regions where the ACS allows, e.g. "Orange
+    /// County" or "Irvine"
+    pub(crate) region_name: String,
+    pub(crate) year: i32,
+    pub(crate) median_income: Option<f64>,
+    pub(crate) margin_of_error: Option<f64>,
+}
+
+impl Income {
+    pub(crate) fn region_type(&self) -> &RegionType {
+        &self.region_type
+    }
+
+    pub(crate) fn region_id(&self) -> &str {
+        &self.region_id
+    }
+
+    pub(crate) fn region_name(&self) -> &str {
+        &self.region_name
+    }
+
+    pub(crate) fn year(&self) -> i32 {
+        self.year
+    }
+
+    pub(crate) fn median_income(&self) -> Option<f64> {
+        self.median_income
+    }
+
+    pub(crate) fn margin_of_error(&self) -> Option<f64> {
+        self.margin_of_error
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct IncomeData {
+    incomes: Incomes,
+}
+
+impl IncomeData {
+    pub fn incomes(&self) -> &Incomes {
+        &self.incomes
+    }
+}
+
+pub type Incomes = Vec<Income>;
+/// `(region_type, region_id, year)`
+pub type IncomeId = (RegionType, String, i32);
+
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct IncomeQuery {
+    region_type: RegionType,
+    /// Matched against both the region id and name, place names repeat
+    /// across states
+    region: String,
+    start_year: i32,
+    end_year: i32,
+}
+
+impl IncomeQuery {
+    pub fn new(region_type: RegionType, region: String, start_year: i32, end_year: i32) -> Self {
+        Self {
+            region_type,
+            region,
+            start_year,
+            end_year,
+        }
+    }
+
+    pub(crate) fn region_type(&self) -> &RegionType {
+        &self.region_type
+    }
+
+    pub(crate) fn region(&self) -> &str {
+        &self.region
+    }
+
+    // Only the SQL backends bind the years
+    #[cfg(any(feature = "postgres", feature = "sqlite"))]
+    pub(crate) fn start_year(&self) -> i32 {
+        self.start_year
+    }
+
+    #[cfg(any(feature = "postgres", feature = "sqlite"))]
+    pub(crate) fn end_year(&self) -> i32 {
+        self.end_year
+    }
+
+    pub(crate) fn matches(&self, income: &Income) -> bool {
+        income.region_type() == self.region_type()
+            && (income.region_id() == self.region() || income.region_name() == self.region())
+            && (self.start_year..=self.end_year).contains(&income.year())
+    }
+}
+
+#[async_trait]
+pub trait IncomePersist: Send + Sync {
+    async fn create_income(&self, income: &Income) -> Result<IncomeId, DomainError>;
+    /// Inserts in bulk, skipping incomes that already exist
+    async fn create_incomes(&self, incomes: &[Income]) -> Result<(), DomainError>;
+    async fn read_income_by_id(&self, id: (&RegionType, &str, i32)) -> Result<Income, DomainError>;
+    async fn update_income(&self, income: &Income) -> Result<(), DomainError>;
+    async fn delete_income_by_id(&self, id: (&RegionType, &str, i32)) -> Result<(), DomainError>;
+    async fn read_incomes_by_query(&self, query: &IncomeQuery) -> Result<Incomes, DomainError>;
+}
+
+impl Income {
+    pub async fn create(&self, client: &dyn Persist) -> Result<IncomeId, DomainError> {
+        client.create_income(self).await
+    }
+
+    pub async fn create_many(client: &dyn Persist, incomes: &[Income]) -> Result<(), DomainError> {
+        client.create_incomes(incomes).await
+    }
+
+    pub async fn read(
+        client: &dyn Persist,
+        id: (&RegionType, &str, i32),
+    ) -> Result<Income, DomainError> {
+        client.read_income_by_id(id).await
+    }
+
+    pub async fn update(&self, client: &dyn Persist) -> Result<(), DomainError> {
+        client.update_income(self).await
+    }
+
+    pub async fn delete(
+        client: &dyn Persist,
+        id: (&RegionType, &str, i32),
+    ) -> Result<(), DomainError> {
+        client.delete_income_by_id(id).await
+    }
+
+    pub async fn read_by_query(
+        client: &dyn Persist,
+        query: &IncomeQuery,
+    ) -> Result<Incomes, DomainError> {
+        client.read_incomes_by_query(query).await
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct IncomeConfig {
+    acs_income_dir: Option<String>,
+}
+
+impl IncomeConfig {
+    pub(crate) fn new(acs_income_dir: Option<String>) -> Self {
+        IncomeConfig { acs_income_dir }
+    }
+
+    fn acs_income_dir(&self) -> Option<&str> {
+        self.acs_income_dir.as_deref()
+    }
+}
+
+const NATIONAL_ID: &str = "USA";
+
+/// Reads every B19013 download under the configured directory. A year found
+/// in both the 1-year and 5-year surveys keeps the 1-year estimate, which
+/// sorts first.
+pub(crate) fn read_census_incomes(
+    income_config: &IncomeConfig,
+    report: &mut ImportReport,
+) -> Result<IncomeData, DomainError> {
+    let mut income_data = IncomeData::default();
+    let Some(dir) = income_config.acs_income_dir() else {
+        return Ok(income_data);
+    };
+
+    let entries = fs::read_dir(dir)
+        .map_err(|e| DomainError::Parse(format!("Failed to read {}: {}", dir, e)))?;
+    let mut paths = vec![];
+    for entry in entries {
+        let path = entry.map_err(|e| DomainError::Parse(e.to_string()))?.path();
+        if path.is_file() {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+
+    for path in paths {
+        let name = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or_default();
+        // data.census.gov zips ship metadata and notes next to the data
+        if !name.ends_with("-Data.csv") {
+            continue;
+        }
+        let path = path.to_string_lossy().to_string();
+        match parse_acs_filename(name) {
+            Some(year) => {
+                let mut incomes = read_acs_income_file(&path, year, report)?;
+                income_data.incomes.append(&mut incomes);
+            }
+            None => report.add_error(&path, 0, None, "not an ACS B19013 table".to_string()),
+        }
+    }
+    Ok(income_data)
+}
+
+/// Survey year of a data.census.gov export such as
+/// `ACSDT5Y2022.B19013-Data.csv`
+pub(crate) fn parse_acs_filename(name: &str) -> Option<i32> {
+    let (dataset, table) = name.strip_suffix("-Data.csv")?.split_once('.')?;
+    if table != "B19013" {
+        return None;
+    }
+    let year = dataset
+        .strip_prefix("ACSDT1Y")
+        .or_else(|| dataset.strip_prefix("ACSDT5Y"))?;
+    year.parse().ok()
+}
+
+// Place names end in their legal description, e.g. "Irvine city"
+const PLACE_SUFFIXES: [&str; 6] = [
+    " city",
+    " town",
+    " village",
+    " borough",
+    " CDP",
+    " municipality",
+];
+
+/// Region for an ACS `GEO_ID` such as `0500000US06059`, keyed by its summary
+/// level
+fn to_region(geo_id: &str, name: &str) -> Option<(RegionType, String, String)> {
+    let (prefix, id) = geo_id.split_once("US")?;
+    // Drops the state from "Orange County, California"
+    let local_name = name.split(", ").next().unwrap_or(name);
+    let region = match prefix.get(..3)? {
+        "010" => (
+            RegionType::National,
+            NATIONAL_ID.to_string(),
+            name.to_string(),
+        ),
+        "040" => (RegionType::State, id.to_string(), name.to_string()),
+        "050" => (RegionType::County, id.to_string(), local_name.to_string()),
+        "160" => {
+            let place = PLACE_SUFFIXES
+                .iter()
+                .find_map(|suffix| local_name.strip_suffix(suffix))
+                .unwrap_or(local_name);
+            (RegionType::City, id.to_string(), place.to_string())
+        }
+        "310" => {
+            let metro = name
+                .strip_suffix(" Metro Area")
+                .or_else(|| name.strip_suffix(" Micro Area"))
+                .unwrap_or(name);
+            (RegionType::Msa, id.to_string(), metro.to_string())
+        }
+        "860" => (RegionType::FiveZip, id.to_string(), id.to_string()),
+        _ => return None,
+    };
+    Some(region)
+}
+
+/// ACS estimates are top and bottom coded, e.g. "250,000+", and suppressed
+/// values are "-", "N", "(X)" or a run of asterisks
+fn to_acs_value(value: &str) -> Result<Option<f64>, String> {
+    if matches!(value, "" | "-" | "N" | "(X)" | "null") || value.starts_with('*') {
+        return Ok(None);
+    }
+    value
+        .trim_end_matches(['+', '-'])
+        .replace(',', "")
+        .parse()
+        .map(Some)
+        .map_err(|e| format!("invalid value '{}': {}", value, e))
+}
+
+fn read_acs_income_file(
+    path: &str,
+    year: i32,
+    report: &mut ImportReport,
+) -> Result<Incomes, DomainError> {
+    let mut rdr = csv::ReaderBuilder::new()
+        .has_headers(true)
+        .flexible(true)
+        .from_path(path)?;
+    let header = CsvHeader::from_reader(path, &mut rdr)?;
+    let geo_id_col = header.column("GEO_ID")?;
+    let name_col = header.column("NAME")?;
+    let estimate_col = header.column("B19013_001E")?;
+    let margin_col = header.column("B19013_001M")?;
+
+    let mut incomes = vec![];
+    for entry in header.records(&mut rdr, report) {
+        // The row under the header labels each column
+        if entry.get(geo_id_col) == "Geography" {
+            continue;
+        }
+        let mut row = header.row(&entry, report);
+        let geo_id = row.text(geo_id_col);
+        let name = row.text(name_col);
+        let region = to_region(&geo_id, &name);
+        if region.is_none() && !geo_id.is_empty() {
+            row.error(geo_id_col, format!("unsupported geography '{}'", geo_id));
+        }
+        let mut value = |col: usize| match to_acs_value(entry.get(col)) {
+            Ok(value) => value,
+            Err(message) => {
+                row.error(col, message);
+                None
+            }
+        };
+        let median_income = value(estimate_col);
+        let margin_of_error = value(margin_col);
+        if let (true, Some((region_type, region_id, region_name))) = (row.is_valid(), region) {
+            incomes.push(Income {
+                region_type,
+                region_id,
+                region_name,
+                year,
+                median_income,
+                margin_of_error,
+            });
+        }
+    }
+    Ok(incomes)
+}
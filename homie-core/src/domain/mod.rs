@@ -1,6 +1,17 @@
+pub mod affordability;
+pub mod aggregate;
+pub mod analytics;
 pub mod common;
+pub mod cpi;
+pub mod fair_value;
+pub mod forecast;
 pub mod hpi;
+pub mod income;
+pub mod mortgage;
+pub mod mortgage_rate;
 pub mod region;
+pub mod resample;
+pub mod spread;
 pub mod t_yield;
 mod util;
 pub mod zhvi;
@@ -0,0 +1,252 @@
+use std::collections::BTreeMap;
+
+use chrono::{Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::adapter::repository::Persist;
+use crate::domain::common::DateInterval;
+use crate::domain::mortgage_rate::{MortgageRate, MortgageRateQuery, MortgageTerm};
+use crate::domain::t_yield::{TYield, TYieldQuery, Term};
+use crate::error::DomainError;
+
+// How far back a rate lookup searches for the latest observation
+const RATE_LOOKBACK_MONTHS: u32 = 12;
+/// Longest term accepted, which also bounds the schedule's length
+pub const MAX_TERM_YEARS: u32 = 50;
+
+/// A fixed-rate loan on a home bought at `price`, e.g. a ZHVI value
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Mortgage {
+    price: f64,
+    down_payment: f64,
+    /// Annual rate in percent
+    rate: f64,
+    term_years: u32,
+}
+
+/// One monthly payment and the balance left after it
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Installment {
+    pub month: u32,
+    pub payment: f64,
+    pub principal: f64,
+    pub interest: f64,
+    pub balance: f64,
+}
+
+pub type Schedule = Vec<Installment>;
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct MortgageSummary {
+    pub principal: f64,
+    pub rate: f64,
+    pub term_years: u32,
+    pub monthly_payment: f64,
+    pub total_interest: f64,
+    pub total_paid: f64,
+    /// Balance after the requested number of payments
+    pub remaining_balance: Option<f64>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Amortization {
+    pub summary: MortgageSummary,
+    pub schedule: Schedule,
+}
+
+impl Mortgage {
+    pub fn new(
+        price: f64,
+        down_payment: f64,
+        rate: f64,
+        term_years: u32,
+    ) -> Result<Self, DomainError> {
+        if !(price > 0.0 && price.is_finite()) {
+            return Err(DomainError::ConvertDomain(format!(
+                "Invalid price {}",
+                price
+            )));
+        }
+        if !(0.0..=price).contains(&down_payment) {
+            return Err(DomainError::ConvertDomain(format!(
+                "Down payment {} must be between 0 and the price",
+                down_payment
+            )));
+        }
+        if !(0.0..100.0).contains(&rate) {
+            return Err(DomainError::ConvertDomain(format!("Invalid rate {}", rate)));
+        }
+        if !(1..=MAX_TERM_YEARS).contains(&term_years) {
+            return Err(DomainError::ConvertDomain(format!(
+                "Term {} must be between 1 and {} years",
+                term_years, MAX_TERM_YEARS
+            )));
+        }
+        Ok(Mortgage {
+            price,
+            down_payment,
+            rate,
+            term_years,
+        })
+    }
+
+    /// Amount borrowed
+    pub fn principal(&self) -> f64 {
+        self.price - self.down_payment
+    }
+
+    pub fn months(&self) -> u32 {
+        self.term_years * 12
+    }
+
+    fn monthly_rate(&self) -> f64 {
+        self.rate / 100.0 / 12.0
+    }
+
+    pub fn monthly_payment(&self) -> f64 {
+        let principal = self.principal();
+        let rate = self.monthly_rate();
+        let months = self.months() as f64;
+        if rate == 0.0 {
+            return principal / months;
+        }
+        principal * rate / (1.0 - (1.0 + rate).powf(-months))
+    }
+
+    /// Balance owed after `months_paid` payments
+    pub fn remaining_balance(&self, months_paid: u32) -> f64 {
+        let principal = self.principal();
+        let rate = self.monthly_rate();
+        let paid = months_paid.min(self.months()) as f64;
+        if rate == 0.0 {
+            return (principal - self.monthly_payment() * paid).max(0.0);
+        }
+        let growth = (1.0 + rate).powf(paid);
+        (principal * growth - self.monthly_payment() * (growth - 1.0) / rate).max(0.0)
+    }
+
+    pub fn total_interest(&self) -> f64 {
+        self.monthly_payment() * self.months() as f64 - self.principal()
+    }
+
+    pub fn schedule(&self) -> Schedule {
+        let payment = self.monthly_payment();
+        let rate = self.monthly_rate();
+        let mut balance = self.principal();
+        (1..=self.months())
+            .map(|month| {
+                let interest = balance * rate;
+                // The last payment clears whatever rounding left over
+                let principal = match month == self.months() {
+                    true => balance,
+                    false => payment - interest,
+                };
+                balance = (balance - principal).max(0.0);
+                Installment {
+                    month,
+                    payment: principal + interest,
+                    principal,
+                    interest,
+                    balance,
+                }
+            })
+            .collect()
+    }
+
+    pub fn summary(&self, months_paid: Option<u32>) -> MortgageSummary {
+        let monthly_payment = self.monthly_payment();
+        MortgageSummary {
+            principal: self.principal(),
+            rate: self.rate,
+            term_years: self.term_years,
+            monthly_payment,
+            total_interest: self.total_interest(),
+            total_paid: monthly_payment * self.months() as f64,
+            remaining_balance: months_paid.map(|months| self.remaining_balance(months)),
+        }
+    }
+
+    pub fn amortization(&self) -> Amortization {
+        Amortization {
+            summary: self.summary(None),
+            schedule: self.schedule(),
+        }
+    }
+}
+
+/// Stored series a mortgage rate can be taken from
+#[derive(Clone, Debug, PartialEq)]
+pub enum RateSource {
+    /// The PMMS rate for the loan's term, 30 or 15 years
+    MortgageRate,
+    /// A Treasury yield plus a spread in percentage points, e.g. the mean
+    /// spread from `/spreads`
+    TYield { term: Term, spread: f64 },
+}
+
+/// Latest rate on or before `date`, looking back up to a year
+pub async fn read_rate(
+    client: &dyn Persist,
+    source: &RateSource,
+    term_years: u32,
+    date: NaiveDate,
+) -> Result<f64, DomainError> {
+    let start_date = date - Months::new(RATE_LOOKBACK_MONTHS);
+    read_rates(
+        client,
+        source,
+        term_years,
+        start_date,
+        date,
+        DateInterval::Day,
+    )
+    .await?
+    .into_values()
+    .next_back()
+    .ok_or_else(|| DomainError::ConvertDomain(format!("No rate found on or before {}", date)))
+}
+
+/// Rates from `source` averaged over each interval between the two dates,
+/// keyed by the interval's start. Missing observations are left out.
+pub async fn read_rates(
+    client: &dyn Persist,
+    source: &RateSource,
+    term_years: u32,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+    date_interval: DateInterval,
+) -> Result<BTreeMap<NaiveDate, f64>, DomainError> {
+    let rates = match source {
+        RateSource::MortgageRate => {
+            let term = match term_years {
+                15 => MortgageTerm::FifteenYear,
+                30 => MortgageTerm::ThirtyYear,
+                _ => {
+                    return Err(DomainError::ConvertDomain(format!(
+                        "No mortgage rate for a {}-year term",
+                        term_years
+                    )))
+                }
+            };
+            let query = MortgageRateQuery::new(Some(term), start_date, end_date, date_interval);
+            MortgageRate::read_by_query(client, &query)
+                .await?
+                .iter()
+                .filter_map(|mortgage_rate| {
+                    (*mortgage_rate.rate()).map(|rate| (*mortgage_rate.date(), rate as f64))
+                })
+                .collect()
+        }
+        RateSource::TYield { term, spread } => {
+            let query = TYieldQuery::new(Some(term.clone()), start_date, end_date, date_interval);
+            TYield::read_by_query(client, &query)
+                .await?
+                .iter()
+                .filter_map(|t_yield| {
+                    (*t_yield.yield_return()).map(|value| (*t_yield.date(), value as f64 + spread))
+                })
+                .collect()
+        }
+    };
+    Ok(rates)
+}
@@ -0,0 +1,324 @@
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use super::common::DateInterval;
+use crate::adapter::importer::ImportReport;
+use crate::adapter::repository::Persist;
+use crate::domain::resample::{resample, Resampling, Sample};
+use crate::domain::util::CsvHeader;
+use crate::error::DomainError;
+
+/// Fixed-rate terms surveyed by Freddie Mac's PMMS
+#[derive(Clone, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize, sqlx::Type)]
+#[sqlx(type_name = "mortgage_term", rename_all = "lowercase")]
+pub enum MortgageTerm {
+    #[default]
+    ThirtyYear,
+    FifteenYear,
+}
+
+impl MortgageTerm {
+    pub(crate) const ALL: [MortgageTerm; 2] = [MortgageTerm::ThirtyYear, MortgageTerm::FifteenYear];
+
+    /// PMMS columns holding the rate and the fees and points
+    fn columns(&self) -> (&'static str, &'static str) {
+        match self {
+            MortgageTerm::ThirtyYear => ("pmms30", "pmms30p"),
+            MortgageTerm::FifteenYear => ("pmms15", "pmms15p"),
+        }
+    }
+}
+
+impl TryFrom<&str> for MortgageTerm {
+    type Error = crate::error::DomainError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        match s {
+            "thirtyyear" | "30y" => Ok(MortgageTerm::ThirtyYear),
+            "fifteenyear" | "15y" => Ok(MortgageTerm::FifteenYear),
+            _ => Err(DomainError::Parse(
+                "Failed to parse MortgageTerm".to_string(),
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for MortgageTerm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MortgageTerm::ThirtyYear => write!(f, "thirtyyear"),
+            MortgageTerm::FifteenYear => write!(f, "fifteenyear"),
+        }
+    }
+}
+
+/// Average weekly rate on a conforming fixed-rate mortgage, in percent
+#[derive(Clone, Debug, Default, Deserialize, Serialize, sqlx::FromRow)]
+pub struct MortgageRate {
+    pub(crate) term: MortgageTerm,
+    pub(crate) date: NaiveDate,
+    pub(crate) rate: Option<f32>,
+    /// Fees and points paid, as a percent of the loan
+    pub(crate) points: Option<f32>,
+}
+
+impl MortgageRate {
+    pub(crate) fn term(&self) -> &MortgageTerm {
+        &self.term
+    }
+
+    pub(crate) fn date(&self) -> &NaiveDate {
+        &self.date
+    }
+
+    pub(crate) fn rate(&self) -> &Option<f32> {
+        &self.rate
+    }
+
+    pub(crate) fn points(&self) -> &Option<f32> {
+        &self.points
+    }
+}
+
+impl Sample for MortgageRate {
+    fn series(&self) -> String {
+        self.term.to_string()
+    }
+
+    fn date(&self) -> NaiveDate {
+        self.date
+    }
+
+    fn values(&self) -> Vec<Option<f64>> {
+        vec![self.rate.map(f64::from), self.points.map(f64::from)]
+    }
+
+    fn resampled(&self, date: NaiveDate, values: Vec<Option<f64>>) -> Self {
+        Self {
+            term: self.term.clone(),
+            date,
+            rate: values[0].map(|v| v as f32),
+            points: values[1].map(|v| v as f32),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct MortgageRateData {
+    mortgage_rates: MortgageRates,
+}
+
+impl MortgageRateData {
+    pub fn mortgage_rates(&self) -> &MortgageRates {
+        &self.mortgage_rates
+    }
+}
+
+pub type MortgageRates = Vec<MortgageRate>;
+
+pub type MortgageRateId = (MortgageTerm, NaiveDate);
+
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct MortgageRateQuery {
+    /// All terms when unset
+    term: Option<MortgageTerm>,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+    date_interval: DateInterval,
+    resampling: Resampling,
+}
+
+impl MortgageRateQuery {
+    pub fn new(
+        term: Option<MortgageTerm>,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+        date_interval: DateInterval,
+    ) -> Self {
+        Self {
+            term,
+            start_date,
+            end_date,
+            date_interval,
+            resampling: Resampling::default(),
+        }
+    }
+
+    /// Combines the rates in each interval some other way than the mean
+    pub fn with_resampling(mut self, resampling: Resampling) -> Self {
+        self.resampling = resampling;
+        self
+    }
+
+    // Only the SQL backends bind the query's fields
+    #[cfg(any(feature = "postgres", feature = "sqlite"))]
+    pub(crate) fn term(&self) -> Option<&MortgageTerm> {
+        self.term.as_ref()
+    }
+
+    #[cfg(any(feature = "postgres", feature = "sqlite"))]
+    pub(crate) fn start_date(&self) -> &NaiveDate {
+        &self.start_date
+    }
+
+    #[cfg(any(feature = "postgres", feature = "sqlite"))]
+    pub(crate) fn end_date(&self) -> &NaiveDate {
+        &self.end_date
+    }
+
+    pub(crate) fn contains(&self, term: &MortgageTerm, date: &NaiveDate) -> bool {
+        self.term.as_ref().map_or(true, |t| t == term)
+            && &self.start_date <= date
+            && date <= &self.end_date
+    }
+}
+
+#[async_trait]
+pub trait MortgageRatePersist: Send + Sync {
+    async fn create_mortgage_rate(
+        &self,
+        mortgage_rate: &MortgageRate,
+    ) -> Result<MortgageRateId, DomainError>;
+    /// Inserts in bulk, skipping rates that already exist
+    async fn create_mortgage_rates(
+        &self,
+        mortgage_rates: &[MortgageRate],
+    ) -> Result<(), DomainError>;
+    async fn read_mortgage_rate_by_id(
+        &self,
+        id: (&MortgageTerm, &NaiveDate),
+    ) -> Result<MortgageRate, DomainError>;
+    async fn update_mortgage_rate(&self, mortgage_rate: &MortgageRate) -> Result<(), DomainError>;
+    async fn delete_mortgage_rate_by_id(
+        &self,
+        id: (&MortgageTerm, &NaiveDate),
+    ) -> Result<(), DomainError>;
+    async fn read_mortgage_rates_by_query(
+        &self,
+        query: &MortgageRateQuery,
+    ) -> Result<MortgageRates, DomainError>;
+}
+
+impl MortgageRate {
+    pub async fn create(&self, client: &dyn Persist) -> Result<MortgageRateId, DomainError> {
+        client.create_mortgage_rate(self).await
+    }
+
+    pub async fn create_many(
+        client: &dyn Persist,
+        mortgage_rates: &[MortgageRate],
+    ) -> Result<(), DomainError> {
+        client.create_mortgage_rates(mortgage_rates).await
+    }
+
+    pub async fn read(
+        client: &dyn Persist,
+        id: (&MortgageTerm, &NaiveDate),
+    ) -> Result<MortgageRate, DomainError> {
+        client.read_mortgage_rate_by_id(id).await
+    }
+
+    pub async fn update(&self, client: &dyn Persist) -> Result<(), DomainError> {
+        client.update_mortgage_rate(self).await
+    }
+
+    pub async fn delete(
+        client: &dyn Persist,
+        id: (&MortgageTerm, &NaiveDate),
+    ) -> Result<(), DomainError> {
+        client.delete_mortgage_rate_by_id(id).await
+    }
+
+    pub async fn read_by_query(
+        client: &dyn Persist,
+        query: &MortgageRateQuery,
+    ) -> Result<MortgageRates, DomainError> {
+        let mortgage_rates = client.read_mortgage_rates_by_query(query).await?;
+        Ok(resample(
+            &mortgage_rates,
+            &query.date_interval,
+            &query.resampling,
+        ))
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct MortgageRateConfig {
+    pmms_path: Option<String>,
+}
+
+impl MortgageRateConfig {
+    pub(crate) fn new(pmms_path: Option<String>) -> Self {
+        MortgageRateConfig { pmms_path }
+    }
+
+    fn pmms_path(&self) -> Option<&str> {
+        self.pmms_path.as_deref()
+    }
+}
+
+pub(crate) fn read_freddie_mac_rates(
+    mortgage_rate_config: &MortgageRateConfig,
+    report: &mut ImportReport,
+) -> Result<MortgageRateData, DomainError> {
+    let mut mortgage_rate_data = MortgageRateData::default();
+    if let Some(pmms_path) = mortgage_rate_config.pmms_path() {
+        mortgage_rate_data.mortgage_rates = read_freddie_mac_pmms(pmms_path, report)?;
+    }
+    Ok(mortgage_rate_data)
+}
+
+// Freddie Mac's export dates weeks as `M/D/YYYY`
+fn to_week_date(date: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(date, "%m/%d/%Y")
+        .or_else(|_| NaiveDate::parse_from_str(date, "%Y-%m-%d"))
+        .ok()
+}
+
+/// Reads the PMMS history, one row per week. The 15-year survey began in 1991,
+/// so earlier weeks only carry a 30-year rate.
+fn read_freddie_mac_pmms(
+    pmms: &str,
+    report: &mut ImportReport,
+) -> Result<MortgageRates, DomainError> {
+    let mut rdr = csv::ReaderBuilder::new()
+        .has_headers(true)
+        .from_path(pmms)?;
+
+    let header = CsvHeader::from_reader(pmms, &mut rdr)?;
+    let date_col = header.column("date")?;
+    let mut term_cols = vec![];
+    for term in MortgageTerm::ALL {
+        let (rate, points) = term.columns();
+        term_cols.push((term, header.column(rate)?, header.column(points)?));
+    }
+
+    let mut mortgage_rates = vec![];
+    for entry in header.records(&mut rdr, report) {
+        let mut row = header.row(&entry, report);
+        let text = row.text(date_col);
+        let date = to_week_date(&text);
+        if date.is_none() && !text.is_empty() {
+            row.error(date_col, format!("invalid value '{}'", text));
+        }
+        let rates: Vec<(MortgageTerm, f32, Option<f32>)> = term_cols
+            .iter()
+            .filter_map(|(term, rate_col, points_col)| {
+                let rate = row.optional(*rate_col);
+                let points = row.optional(*points_col);
+                rate.map(|rate| (term.clone(), rate, points))
+            })
+            .collect();
+        if let (true, Some(date)) = (row.is_valid(), date) {
+            mortgage_rates.extend(rates.into_iter().map(|(term, rate, points)| MortgageRate {
+                term,
+                date,
+                rate: Some(rate),
+                points,
+            }));
+        }
+    }
+
+    Ok(mortgage_rates)
+}
@@ -6,17 +6,33 @@ use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
+use crate::adapter::importer::ImportReport;
 use crate::adapter::repository::Persist;
-use crate::domain::util::CsvRecord;
+use crate::domain::util::CsvHeader;
 use crate::error::DomainError;
 
 pub type City = String;
 pub type Zipcode = String;
+pub type State = String;
+pub type CountyFips = String;
 
+/// A zip code's share of a county from the HUD crosswalk. A zip spanning
+/// several counties has one `Region` per county, and its ratios sum to 1 across
+/// them.
 #[derive(Clone, Debug, Default, Deserialize, Serialize, sqlx::FromRow, ToSchema)]
 pub struct Region {
     pub(crate) city: City,
     pub(crate) zipcode: Zipcode,
+    /// USPS state abbreviation, e.g. `CA`
+    pub(crate) state: State,
+    /// Five digit county FIPS code, e.g. `06059`
+    pub(crate) county_fips: CountyFips,
+    /// Share of the zip's residential addresses in the county
+    pub(crate) res_ratio: f64,
+    /// Share of the zip's business addresses in the county
+    pub(crate) bus_ratio: f64,
+    /// Share of all the zip's addresses in the county
+    pub(crate) tot_ratio: f64,
 }
 
 #[derive(Debug, Default, Serialize, Deserialize)]
@@ -33,16 +49,31 @@ impl RegionData {
 pub type Regions = Vec<Region>;
 pub type Zipcodes = Vec<Zipcode>;
 pub type Cities = Vec<City>;
+pub type States = Vec<State>;
 
-#[derive(Clone, Debug, Default)]
+/// Regions matching any of the cities, zipcodes or counties (every region if
+/// none are given), restricted to `states` when that is not empty
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
 pub struct RegionQuery {
     cities: Cities,
     zipcodes: Zipcodes,
+    county_fips: Vec<CountyFips>,
+    states: States,
 }
 
 impl RegionQuery {
-    pub fn new(cities: Cities, zipcodes: Zipcodes) -> Self {
-        Self { cities, zipcodes }
+    pub fn new(
+        cities: Cities,
+        zipcodes: Zipcodes,
+        county_fips: Vec<CountyFips>,
+        states: States,
+    ) -> Self {
+        Self {
+            cities,
+            zipcodes,
+            county_fips,
+            states,
+        }
     }
 
     pub(crate) fn cities(&self) -> &Cities {
@@ -52,14 +83,38 @@ impl RegionQuery {
     pub(crate) fn zipcodes(&self) -> &Zipcodes {
         &self.zipcodes
     }
+
+    pub(crate) fn county_fips(&self) -> &[CountyFips] {
+        &self.county_fips
+    }
+
+    // Only the SQL backends bind the states
+    #[cfg(any(feature = "postgres", feature = "sqlite"))]
+    pub(crate) fn states(&self) -> &States {
+        &self.states
+    }
+
+    pub(crate) fn matches(&self, region: &Region) -> bool {
+        let selected =
+            (self.cities.is_empty() && self.zipcodes.is_empty() && self.county_fips.is_empty())
+                || self.cities.iter().any(|city| city == region.city())
+                || self.zipcodes.iter().any(|zip| zip == region.zipcode())
+                || self
+                    .county_fips
+                    .iter()
+                    .any(|fips| fips == region.county_fips());
+        selected && (self.states.is_empty() || self.states.iter().any(|s| s == region.state()))
+    }
 }
 
 #[async_trait]
 pub trait RegionPersist: Send + Sync {
     async fn create_region(&self, region: &Region) -> Result<Zipcode, DomainError>;
+    /// The county holding most of the zip's addresses
     async fn read_region_by_id(&self, id: &str) -> Result<Region, DomainError>;
     async fn read_regions_by_city(&self, id: &str) -> Result<Regions, DomainError>;
     async fn read_regions_by_query(&self, query: &RegionQuery) -> Result<Regions, DomainError>;
+    /// Removes the zip from every county
     async fn delete_region_by_id(&self, id: &str) -> Result<Zipcode, DomainError>;
 }
 
@@ -90,19 +145,46 @@ impl Region {
     pub fn zipcode(&self) -> &str {
         &self.zipcode
     }
+
+    pub fn state(&self) -> &str {
+        &self.state
+    }
+
+    pub fn county_fips(&self) -> &str {
+        &self.county_fips
+    }
+
+    pub fn res_ratio(&self) -> f64 {
+        self.res_ratio
+    }
+
+    pub fn bus_ratio(&self) -> f64 {
+        self.bus_ratio
+    }
+
+    pub fn tot_ratio(&self) -> f64 {
+        self.tot_ratio
+    }
 }
 
 #[derive(Clone, Debug, Default)]
 pub struct RegionConfig {
     cities_path: Option<String>,
     zip_county_path: Option<String>,
+    states: Option<States>,
 }
 
 impl RegionConfig {
-    pub(crate) fn new(cities_path: Option<String>, zip_county_path: Option<String>) -> Self {
+    /// `states` of `None` imports every state in the crosswalk
+    pub(crate) fn new(
+        cities_path: Option<String>,
+        zip_county_path: Option<String>,
+        states: Option<States>,
+    ) -> Self {
         RegionConfig {
             cities_path,
             zip_county_path,
+            states,
         }
     }
 
@@ -113,28 +195,34 @@ impl RegionConfig {
     fn zip_county_path(&self) -> Option<&str> {
         self.zip_county_path.as_deref()
     }
+
+    fn includes_state(&self, state: &str) -> bool {
+        self.states.as_ref().map_or(true, |states| {
+            states.iter().any(|s| s.eq_ignore_ascii_case(state))
+        })
+    }
 }
 
-pub fn read_huduser_regions(region_config: &RegionConfig) -> Result<RegionData, DomainError> {
+pub fn read_huduser_regions(
+    region_config: &RegionConfig,
+    report: &mut ImportReport,
+) -> Result<RegionData, DomainError> {
     let mut region_data = RegionData::default();
 
-    if let (Some(cities_path), Some(zip_county_path)) =
-        (region_config.cities_path(), region_config.zip_county_path())
-    {
-        let cities = read_select_cities(cities_path);
-        let cities_map: HashSet<_> = cities.iter().collect();
-
-        let zip_city_pairs = read_csv_city_zip_pairs(zip_county_path)?;
-        let mut regions = vec![];
-        for pair in zip_city_pairs {
-            if cities_map.contains(&pair.0) {
-                regions.push(Region {
-                    city: pair.0,
-                    zipcode: pair.1,
-                })
-            }
-        }
-        region_data.regions = regions;
+    if let Some(zip_county_path) = region_config.zip_county_path() {
+        // Without a city list, every city in the selected states is kept
+        let cities: Option<HashSet<String>> = region_config
+            .cities_path()
+            .map(|cities_path| read_select_cities(cities_path).into_iter().collect());
+
+        region_data.regions = read_csv_regions(zip_county_path, region_config, report)?
+            .into_iter()
+            .filter(|region| {
+                cities
+                    .as_ref()
+                    .map_or(true, |cities| cities.contains(region.city()))
+            })
+            .collect();
     }
 
     Ok(region_data)
@@ -151,18 +239,49 @@ fn read_select_cities(cities_path: &str) -> Vec<String> {
     cities
 }
 
-fn read_csv_city_zip_pairs(zip_county_path: &str) -> Result<Vec<(String, String)>, DomainError> {
+fn read_csv_regions(
+    zip_county_path: &str,
+    region_config: &RegionConfig,
+    report: &mut ImportReport,
+) -> Result<Regions, DomainError> {
     let mut rdr = csv::ReaderBuilder::new()
         .has_headers(true)
         .from_path(zip_county_path)?;
-    let mut pairs = vec![];
-    let entries: Vec<CsvRecord> = rdr.deserialize().filter_map(Result::ok).collect();
-    for entry in entries.into_iter() {
-        if entry.0[3] == "CA" {
-            let zipcode = entry.0[0].clone();
-            let city = entry.0[2].to_lowercase().clone();
-            pairs.push((city, zipcode));
+    let header = CsvHeader::from_reader(zip_county_path, &mut rdr)?;
+    let zipcode_col = header.column("ZIP")?;
+    let county_col = header.column("COUNTY")?;
+    let city_col = header.column("USPS_ZIP_PREF_CITY")?;
+    let state_col = header.column("USPS_ZIP_PREF_STATE")?;
+    let res_ratio_col = header.column("RES_RATIO")?;
+    let bus_ratio_col = header.column("BUS_RATIO")?;
+    let tot_ratio_col = header.column("TOT_RATIO")?;
+
+    let mut regions = vec![];
+    for entry in header.records(&mut rdr, report) {
+        let mut row = header.row(&entry, report);
+        let state = row.text(state_col);
+        if !region_config.includes_state(&state) {
+            continue;
+        }
+        let zipcode = row.text(zipcode_col);
+        let county_fips = row.text(county_col);
+        let city = row.text(city_col).to_lowercase();
+        let res_ratio = row.required(res_ratio_col);
+        let bus_ratio = row.required(bus_ratio_col);
+        let tot_ratio = row.required(tot_ratio_col);
+        if let (true, Some(res_ratio), Some(bus_ratio), Some(tot_ratio)) =
+            (row.is_valid(), res_ratio, bus_ratio, tot_ratio)
+        {
+            regions.push(Region {
+                city,
+                zipcode,
+                state,
+                county_fips,
+                res_ratio,
+                bus_ratio,
+                tot_ratio,
+            });
         }
     }
-    Ok(pairs)
+    Ok(regions)
 }
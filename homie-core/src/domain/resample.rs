@@ -0,0 +1,130 @@
+use std::collections::BTreeMap;
+
+use chrono::{Datelike, Days, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::common::DateInterval;
+use crate::error::DomainError;
+
+/// How the observations falling in one interval are combined
+#[derive(Clone, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub enum Resampling {
+    First,
+    Last,
+    #[default]
+    Mean,
+    Median,
+    Min,
+    Max,
+}
+
+impl TryFrom<&str> for Resampling {
+    type Error = crate::error::DomainError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        match s {
+            "first" => Ok(Resampling::First),
+            "last" => Ok(Resampling::Last),
+            "mean" => Ok(Resampling::Mean),
+            "median" => Ok(Resampling::Median),
+            "min" => Ok(Resampling::Min),
+            "max" => Ok(Resampling::Max),
+            _ => Err(DomainError::Parse("Failed to parse Resampling".to_string())),
+        }
+    }
+}
+
+impl std::fmt::Display for Resampling {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Resampling::First => write!(f, "first"),
+            Resampling::Last => write!(f, "last"),
+            Resampling::Mean => write!(f, "mean"),
+            Resampling::Median => write!(f, "median"),
+            Resampling::Min => write!(f, "min"),
+            Resampling::Max => write!(f, "max"),
+        }
+    }
+}
+
+/// A dated observation with one or more values, such as a rate and its points
+pub(crate) trait Sample: Sized {
+    /// Observations only share an interval with others of the same series
+    fn series(&self) -> String;
+    fn date(&self) -> NaiveDate;
+    fn values(&self) -> Vec<Option<f64>>;
+    /// A copy dated `date` holding `values`, in the order of `values()`
+    fn resampled(&self, date: NaiveDate, values: Vec<Option<f64>>) -> Self;
+}
+
+/// Start of the interval `date` falls in. Weeks start on Monday.
+pub fn interval_start(date: NaiveDate, date_interval: &DateInterval) -> NaiveDate {
+    let start = match date_interval {
+        DateInterval::Day => Some(date),
+        DateInterval::Week => {
+            date.checked_sub_days(Days::new(date.weekday().num_days_from_monday() as u64))
+        }
+        DateInterval::Month => date.with_day(1),
+        DateInterval::Quarter => NaiveDate::from_ymd_opt(date.year(), date.month0() / 3 * 3 + 1, 1),
+        DateInterval::Year => NaiveDate::from_ymd_opt(date.year(), 1, 1),
+    };
+    start.unwrap_or(date)
+}
+
+/// One observation per series and interval, dated at the interval's start and
+/// ordered by date. Each value is combined over the observations that have
+/// it, and stays missing when none do.
+pub(crate) fn resample<T: Sample>(
+    samples: &[T],
+    date_interval: &DateInterval,
+    resampling: &Resampling,
+) -> Vec<T> {
+    let mut buckets: BTreeMap<(NaiveDate, String), Vec<&T>> = BTreeMap::new();
+    for sample in samples {
+        buckets
+            .entry((
+                interval_start(sample.date(), date_interval),
+                sample.series(),
+            ))
+            .or_default()
+            .push(sample);
+    }
+
+    buckets
+        .into_iter()
+        .map(|((date, _), mut bucket)| {
+            bucket.sort_by_key(|sample| sample.date());
+            let columns: Vec<Vec<Option<f64>>> = bucket.iter().map(|s| s.values()).collect();
+            let values = (0..columns[0].len())
+                .map(|i| {
+                    let values: Vec<f64> = columns.iter().filter_map(|column| column[i]).collect();
+                    combine(values, resampling)
+                })
+                .collect();
+            bucket[0].resampled(date, values)
+        })
+        .collect()
+}
+
+/// `values` are in date order
+fn combine(mut values: Vec<f64>, resampling: &Resampling) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    let value = match resampling {
+        Resampling::First => values[0],
+        Resampling::Last => values[values.len() - 1],
+        Resampling::Mean => values.iter().sum::<f64>() / values.len() as f64,
+        Resampling::Median => {
+            values.sort_by(f64::total_cmp);
+            let middle = values.len() / 2;
+            match values.len() % 2 {
+                0 => (values[middle - 1] + values[middle]) / 2.0,
+                _ => values[middle],
+            }
+        }
+        Resampling::Min => values.iter().copied().fold(f64::INFINITY, f64::min),
+        Resampling::Max => values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+    };
+    Some(value)
+}
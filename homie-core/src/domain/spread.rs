@@ -0,0 +1,155 @@
+use std::collections::BTreeMap;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::adapter::repository::Persist;
+use crate::domain::common::DateInterval;
+use crate::domain::mortgage_rate::{MortgageRate, MortgageRateQuery, MortgageTerm};
+use crate::domain::t_yield::{TYield, TYieldQuery, Term};
+use crate::error::DomainError;
+
+/// Mortgage rate over the 10-year Treasury yield at one date, in percentage
+/// points
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Spread {
+    pub date: NaiveDate,
+    pub mortgage_rate: f32,
+    pub t_yield: f32,
+    pub spread: f32,
+}
+
+pub type Spreads = Vec<Spread>;
+
+/// Where the latest spread sits in its history, and the mortgage rate that the
+/// historical mean spread implies today
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct SpreadSummary {
+    pub mean: f32,
+    pub std_dev: f32,
+    pub min: f32,
+    pub max: f32,
+    pub current: Spread,
+    /// Share of the history at or below the current spread, 0 to 100
+    pub percentile: f32,
+    /// `None` when the spread never moved
+    pub z_score: Option<f32>,
+    /// Latest 10-year yield plus the mean spread
+    pub fair_rate: f32,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct SpreadAnalysis {
+    pub term: MortgageTerm,
+    pub spreads: Spreads,
+    /// `None` without any aligned dates
+    pub summary: Option<SpreadSummary>,
+}
+
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct SpreadQuery {
+    term: MortgageTerm,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+    date_interval: DateInterval,
+}
+
+impl SpreadQuery {
+    pub fn new(
+        term: MortgageTerm,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+        date_interval: DateInterval,
+    ) -> Self {
+        Self {
+            term,
+            start_date,
+            end_date,
+            date_interval,
+        }
+    }
+}
+
+impl SpreadAnalysis {
+    /// Reads both series averaged over the query's interval, so weekly PMMS
+    /// rates line up with daily or monthly H.15 yields. Dates missing from
+    /// either series are left out.
+    pub async fn read_by_query(
+        client: &dyn Persist,
+        query: &SpreadQuery,
+    ) -> Result<SpreadAnalysis, DomainError> {
+        let mortgage_rate_query = MortgageRateQuery::new(
+            Some(query.term.clone()),
+            query.start_date,
+            query.end_date,
+            query.date_interval.clone(),
+        );
+        let t_yield_query = TYieldQuery::new(
+            Some(Term::TenYear),
+            query.start_date,
+            query.end_date,
+            query.date_interval.clone(),
+        );
+        let mortgage_rates = MortgageRate::read_by_query(client, &mortgage_rate_query).await?;
+        let t_yields = TYield::read_by_query(client, &t_yield_query).await?;
+
+        let spreads = spreads(&mortgage_rates, &t_yields);
+        let summary = summarize(&spreads);
+        Ok(SpreadAnalysis {
+            term: query.term.clone(),
+            spreads,
+            summary,
+        })
+    }
+}
+
+/// Aligns rates and 10-year yields by date, skipping dates where either value
+/// is missing
+pub fn spreads(mortgage_rates: &[MortgageRate], t_yields: &[TYield]) -> Spreads {
+    let ten_years: BTreeMap<&NaiveDate, f32> = t_yields
+        .iter()
+        .filter(|t_yield| t_yield.term() == &Term::TenYear)
+        .filter_map(|t_yield| t_yield.yield_return().map(|value| (t_yield.date(), value)))
+        .collect();
+
+    let mut spreads: Spreads = mortgage_rates
+        .iter()
+        .filter_map(|mortgage_rate| {
+            let rate = (*mortgage_rate.rate())?;
+            let t_yield = *ten_years.get(mortgage_rate.date())?;
+            Some(Spread {
+                date: *mortgage_rate.date(),
+                mortgage_rate: rate,
+                t_yield,
+                spread: rate - t_yield,
+            })
+        })
+        .collect();
+    spreads.sort_by_key(|spread| spread.date);
+    spreads
+}
+
+/// Summary statistics of a date-ordered spread series, judged at its last date
+pub fn summarize(spreads: &[Spread]) -> Option<SpreadSummary> {
+    let current = spreads.last()?.clone();
+    let values: Vec<f64> = spreads.iter().map(|s| s.spread as f64).collect();
+    let count = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / count;
+    let std_dev = (values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / count).sqrt();
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let latest = current.spread as f64;
+    let at_or_below = values.iter().filter(|v| **v <= latest).count() as f64;
+    let z_score = (std_dev > 0.0).then(|| ((latest - mean) / std_dev) as f32);
+
+    Some(SpreadSummary {
+        mean: mean as f32,
+        std_dev: std_dev as f32,
+        min: min as f32,
+        max: max as f32,
+        percentile: (at_or_below / count * 100.0) as f32,
+        z_score,
+        fair_rate: (current.t_yield as f64 + mean) as f32,
+        current,
+    })
+}
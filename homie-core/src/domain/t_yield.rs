@@ -3,21 +3,120 @@ use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 
 use super::common::DateInterval;
+use crate::adapter::importer::ImportReport;
 use crate::adapter::repository::Persist;
-use crate::domain::util::{to_ymd_date, CsvRecord};
+use crate::domain::resample::{resample, Resampling, Sample};
+use crate::domain::util::{to_ymd_date, CsvHeader};
 use crate::error::DomainError;
 
-#[derive(Clone, Debug, Default, Deserialize, Serialize, sqlx::Type)]
+/// Constant maturities published in the Fed's H.15 release
+#[derive(Clone, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize, sqlx::Type)]
 #[sqlx(type_name = "term", rename_all = "lowercase")]
 pub enum Term {
+    OneMonth,
+    TwoMonth,
+    ThreeMonth,
+    FourMonth,
+    SixMonth,
+    OneYear,
+    TwoYear,
+    ThreeYear,
+    FiveYear,
+    SevenYear,
     #[default]
     TenYear,
+    TwentyYear,
+    ThirtyYear,
+}
+
+impl Term {
+    pub(crate) const ALL: [Term; 13] = [
+        Term::OneMonth,
+        Term::TwoMonth,
+        Term::ThreeMonth,
+        Term::FourMonth,
+        Term::SixMonth,
+        Term::OneYear,
+        Term::TwoYear,
+        Term::ThreeYear,
+        Term::FiveYear,
+        Term::SevenYear,
+        Term::TenYear,
+        Term::TwentyYear,
+        Term::ThirtyYear,
+    ];
+
+    /// H.15 series code, without the frequency suffix
+    fn series(&self) -> &'static str {
+        match self {
+            Term::OneMonth => "RIFLGFCM01",
+            Term::TwoMonth => "RIFLGFCM02",
+            Term::ThreeMonth => "RIFLGFCM03",
+            Term::FourMonth => "RIFLGFCM04",
+            Term::SixMonth => "RIFLGFCM06",
+            Term::OneYear => "RIFLGFCY01",
+            Term::TwoYear => "RIFLGFCY02",
+            Term::ThreeYear => "RIFLGFCY03",
+            Term::FiveYear => "RIFLGFCY05",
+            Term::SevenYear => "RIFLGFCY07",
+            Term::TenYear => "RIFLGFCY10",
+            Term::TwentyYear => "RIFLGFCY20",
+            Term::ThirtyYear => "RIFLGFCY30",
+        }
+    }
+
+    /// Matches a column such as `RIFLGFCY10_N.B` (business daily) or
+    /// `RIFLGFCY10_N.M` (monthly average)
+    fn from_column(name: &str) -> Option<Term> {
+        let (series, frequency) = name.split_once('_')?;
+        if !matches!(frequency, "N.B" | "N.M") {
+            return None;
+        }
+        Term::ALL
+            .into_iter()
+            .find(|term| term.series().eq_ignore_ascii_case(series))
+    }
+}
+
+impl TryFrom<&str> for Term {
+    type Error = crate::error::DomainError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        match s {
+            "onemonth" | "1m" => Ok(Term::OneMonth),
+            "twomonth" | "2m" => Ok(Term::TwoMonth),
+            "threemonth" | "3m" => Ok(Term::ThreeMonth),
+            "fourmonth" | "4m" => Ok(Term::FourMonth),
+            "sixmonth" | "6m" => Ok(Term::SixMonth),
+            "oneyear" | "1y" => Ok(Term::OneYear),
+            "twoyear" | "2y" => Ok(Term::TwoYear),
+            "threeyear" | "3y" => Ok(Term::ThreeYear),
+            "fiveyear" | "5y" => Ok(Term::FiveYear),
+            "sevenyear" | "7y" => Ok(Term::SevenYear),
+            "tenyear" | "10y" => Ok(Term::TenYear),
+            "twentyyear" | "20y" => Ok(Term::TwentyYear),
+            "thirtyyear" | "30y" => Ok(Term::ThirtyYear),
+            _ => Err(DomainError::Parse("Failed to parse Term".to_string())),
+        }
+    }
 }
 
 impl std::fmt::Display for Term {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Term::TenYear => write!(f, "ten_year"),
+            Term::OneMonth => write!(f, "onemonth"),
+            Term::TwoMonth => write!(f, "twomonth"),
+            Term::ThreeMonth => write!(f, "threemonth"),
+            Term::FourMonth => write!(f, "fourmonth"),
+            Term::SixMonth => write!(f, "sixmonth"),
+            Term::OneYear => write!(f, "oneyear"),
+            Term::TwoYear => write!(f, "twoyear"),
+            Term::ThreeYear => write!(f, "threeyear"),
+            Term::FiveYear => write!(f, "fiveyear"),
+            Term::SevenYear => write!(f, "sevenyear"),
+            Term::TenYear => write!(f, "tenyear"),
+            Term::TwentyYear => write!(f, "twentyyear"),
+            Term::ThirtyYear => write!(f, "thirtyyear"),
         }
     }
 }
@@ -42,63 +141,123 @@ impl TYield {
         &self.yield_return
     }
 }
+
+impl Sample for TYield {
+    fn series(&self) -> String {
+        self.term.to_string()
+    }
+
+    fn date(&self) -> NaiveDate {
+        self.date
+    }
+
+    fn values(&self) -> Vec<Option<f64>> {
+        vec![self.yield_return.map(f64::from)]
+    }
+
+    fn resampled(&self, date: NaiveDate, values: Vec<Option<f64>>) -> Self {
+        Self {
+            term: self.term.clone(),
+            date,
+            yield_return: values[0].map(|v| v as f32),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct TYieldData {
-    ten_year_yields: TYields,
+    t_yields: TYields,
 }
 
 impl TYieldData {
-    pub fn ten_year_yields(&self) -> &TYields {
-        &self.ten_year_yields
+    pub fn t_yields(&self) -> &TYields {
+        &self.t_yields
     }
 }
 
 pub type TYields = Vec<TYield>;
 
-#[derive(Clone, Debug, Default)]
+pub type TYieldId = (Term, NaiveDate);
+
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
 pub struct TYieldQuery {
+    /// All terms when unset
+    term: Option<Term>,
     start_date: NaiveDate,
     end_date: NaiveDate,
     date_interval: DateInterval,
+    resampling: Resampling,
 }
 
 impl TYieldQuery {
-    pub fn new(start_date: NaiveDate, end_date: NaiveDate, date_interval: DateInterval) -> Self {
+    pub fn new(
+        term: Option<Term>,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+        date_interval: DateInterval,
+    ) -> Self {
         Self {
+            term,
             start_date,
             end_date,
             date_interval,
+            resampling: Resampling::default(),
         }
     }
 
+    /// Combines the yields in each interval some other way than the mean
+    pub fn with_resampling(mut self, resampling: Resampling) -> Self {
+        self.resampling = resampling;
+        self
+    }
+
+    // Only the SQL backends bind the query's fields
+    #[cfg(any(feature = "postgres", feature = "sqlite"))]
+    pub(crate) fn term(&self) -> Option<&Term> {
+        self.term.as_ref()
+    }
+
+    #[cfg(any(feature = "postgres", feature = "sqlite"))]
     pub(crate) fn start_date(&self) -> &NaiveDate {
         &self.start_date
     }
 
+    #[cfg(any(feature = "postgres", feature = "sqlite"))]
     pub(crate) fn end_date(&self) -> &NaiveDate {
         &self.end_date
     }
 
-    pub(crate) fn date_interval(&self) -> &DateInterval {
-        &self.date_interval
+    pub(crate) fn contains(&self, term: &Term, date: &NaiveDate) -> bool {
+        self.term.as_ref().map_or(true, |t| t == term)
+            && &self.start_date <= date
+            && date <= &self.end_date
     }
 }
 
 #[async_trait]
 pub trait TYieldPersist: Send + Sync {
-    async fn create_t_yield(&self, t_yield: &TYield) -> Result<(String, NaiveDate), DomainError>;
-    async fn read_t_yield_by_id(&self, id: (&str, &NaiveDate)) -> Result<TYield, DomainError>;
+    async fn create_t_yield(&self, t_yield: &TYield) -> Result<TYieldId, DomainError>;
+    /// Inserts in bulk, skipping yields that already exist
+    async fn create_t_yields(&self, t_yields: &[TYield]) -> Result<(), DomainError>;
+    async fn read_t_yield_by_id(&self, id: (&Term, &NaiveDate)) -> Result<TYield, DomainError>;
     async fn update_t_yield(&self, t_yield: &TYield) -> Result<(), DomainError>;
-    async fn delete_t_yield_by_id(&self, id: (&str, &NaiveDate)) -> Result<(), DomainError>;
+    async fn delete_t_yield_by_id(&self, id: (&Term, &NaiveDate)) -> Result<(), DomainError>;
     async fn read_t_yields_by_query(&self, query: &TYieldQuery) -> Result<TYields, DomainError>;
 }
 
 impl TYield {
-    pub async fn create(&self, client: &dyn Persist) -> Result<(String, NaiveDate), DomainError> {
+    pub async fn create(&self, client: &dyn Persist) -> Result<TYieldId, DomainError> {
         client.create_t_yield(self).await
     }
 
-    pub async fn read(client: &dyn Persist, id: (&str, &NaiveDate)) -> Result<TYield, DomainError> {
+    pub async fn create_many(client: &dyn Persist, t_yields: &[TYield]) -> Result<(), DomainError> {
+        client.create_t_yields(t_yields).await
+    }
+
+    pub async fn read(
+        client: &dyn Persist,
+        id: (&Term, &NaiveDate),
+    ) -> Result<TYield, DomainError> {
         client.read_t_yield_by_id(id).await
     }
 
@@ -106,7 +265,7 @@ impl TYield {
         client.update_t_yield(self).await
     }
 
-    pub async fn delete(client: &dyn Persist, id: (&str, &NaiveDate)) -> Result<(), DomainError> {
+    pub async fn delete(client: &dyn Persist, id: (&Term, &NaiveDate)) -> Result<(), DomainError> {
         client.delete_t_yield_by_id(id).await
     }
 
@@ -114,56 +273,85 @@ impl TYield {
         client: &dyn Persist,
         query: &TYieldQuery,
     ) -> Result<TYields, DomainError> {
-        client.read_t_yields_by_query(query).await
+        let t_yields = client.read_t_yields_by_query(query).await?;
+        Ok(resample(&t_yields, &query.date_interval, &query.resampling))
     }
 }
 
 #[derive(Clone, Debug, Default)]
 pub(crate) struct TYieldConfig {
-    ten_year_yield_path: Option<String>,
+    t_yield_path: Option<String>,
 }
 
 impl TYieldConfig {
-    pub(crate) fn new(ten_year_yield_path: Option<String>) -> Self {
-        TYieldConfig {
-            ten_year_yield_path,
-        }
+    pub(crate) fn new(t_yield_path: Option<String>) -> Self {
+        TYieldConfig { t_yield_path }
     }
 
-    fn ten_year_yield_path(&self) -> Option<&str> {
-        self.ten_year_yield_path.as_deref()
+    fn t_yield_path(&self) -> Option<&str> {
+        self.t_yield_path.as_deref()
     }
 }
 
-pub(crate) fn read_fed_yields(t_yield_config: &TYieldConfig) -> Result<TYieldData, DomainError> {
+pub(crate) fn read_fed_yields(
+    t_yield_config: &TYieldConfig,
+    report: &mut ImportReport,
+) -> Result<TYieldData, DomainError> {
     let mut t_yield_data = TYieldData::default();
-    if let Some(ten_year_yield_path) = t_yield_config.ten_year_yield_path() {
-        t_yield_data.ten_year_yields = read_fed_ten_yields(ten_year_yield_path)?;
+    if let Some(t_yield_path) = t_yield_config.t_yield_path() {
+        t_yield_data.t_yields = read_fed_h15(t_yield_path, report)?;
     }
     Ok(t_yield_data)
 }
 
-fn read_fed_ten_yields(fed_h15: &str) -> Result<TYields, DomainError> {
+// Daily periods are `YYYY-MM-DD`; monthly averages are `YYYY-MM` and dated to
+// the first of the month
+fn to_period_date(period: &str) -> Option<NaiveDate> {
+    if let Ok(date) = NaiveDate::parse_from_str(period, "%Y-%m-%d") {
+        return Some(date);
+    }
+    let (year, month) = period.split_once('-')?;
+    to_ymd_date(year.parse().ok()?, month.parse().ok()?, 1).ok()
+}
+
+/// Reads an H.15 download with any of the Treasury constant maturity columns,
+/// daily or monthly. The metadata rows above the `Time Period` header are
+/// skipped, as are `ND` (no data) cells.
+fn read_fed_h15(fed_h15: &str, report: &mut ImportReport) -> Result<TYields, DomainError> {
     let mut rdr = csv::ReaderBuilder::new()
-        .has_headers(true)
+        .has_headers(false)
         .from_path(fed_h15)?;
 
-    let mut ten_year_yields = vec![];
-    let entries: Vec<CsvRecord> = rdr.deserialize().filter_map(Result::ok).collect();
-
-    for entry in entries.into_iter() {
-        let parts: Vec<&str> = entry.0[0].split('-').collect();
-        let year = parts[0].parse()?;
-        let month = parts[1].parse()?;
-        let term = Term::TenYear;
-        let date = to_ymd_date(year, month, 1)?; // TODO: Random day here... Why?
-        let yield_return = entry.0[1].parse().ok();
-        ten_year_yields.push(TYield {
-            term,
-            date,
-            yield_return,
-        });
+    let header = CsvHeader::find_in_reader(fed_h15, &mut rdr, "Time Period")?;
+    let period_col = header.column("Time Period")?;
+    let term_cols = header.matching_columns(Term::from_column);
+    if term_cols.is_empty() {
+        return Err(DomainError::Parse(format!(
+            "{}: no Treasury constant maturity columns found",
+            fed_h15
+        )));
+    }
+
+    let mut t_yields = vec![];
+    for entry in header.records(&mut rdr, report) {
+        let mut row = header.row(&entry, report);
+        let period = row.text(period_col);
+        let date = to_period_date(&period);
+        if date.is_none() && !period.is_empty() {
+            row.error(period_col, format!("invalid value '{}'", period));
+        }
+        let yields: Vec<(Term, f32)> = term_cols
+            .iter()
+            .filter_map(|(col, term)| row.optional(*col).map(|value| (term.clone(), value)))
+            .collect();
+        if let (true, Some(date)) = (row.is_valid(), date) {
+            t_yields.extend(yields.into_iter().map(|(term, value)| TYield {
+                term,
+                date,
+                yield_return: Some(value),
+            }));
+        }
     }
 
-    Ok(ten_year_yields)
+    Ok(t_yields)
 }
@@ -1,10 +1,22 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
 use chrono::NaiveDate;
-use serde::{Deserialize, Serialize};
 
+use crate::adapter::importer::ImportReport;
 use crate::error::DomainError;
 
-#[derive(Debug, Serialize, Deserialize)]
-pub(crate) struct CsvRecord(pub(crate) Vec<String>);
+#[derive(Debug)]
+pub(crate) struct CsvRecord {
+    line: u64,
+    fields: csv::StringRecord,
+}
+
+impl CsvRecord {
+    pub(crate) fn get(&self, index: usize) -> &str {
+        self.fields.get(index).unwrap_or_default().trim()
+    }
+}
 
 // TODO:
 // impl From<Entry> for HPI
@@ -13,6 +25,193 @@ pub(crate) struct CsvRecord(pub(crate) Vec<String>);
 // impl From<Entry> for Zhvi
 // Unit tests
 
+/// Resolves columns by header name, so upstream reordering can't shift the
+/// data. A missing or renamed column is reported against the file it came from.
+pub(crate) struct CsvHeader {
+    path: String,
+    names: Vec<String>,
+}
+
+impl CsvHeader {
+    pub(crate) fn new(path: &str, headers: &csv::StringRecord) -> Self {
+        CsvHeader {
+            path: path.to_string(),
+            names: headers.iter().map(|name| name.trim().to_string()).collect(),
+        }
+    }
+
+    pub(crate) fn from_reader<R: std::io::Read>(
+        path: &str,
+        rdr: &mut csv::Reader<R>,
+    ) -> Result<Self, DomainError> {
+        Ok(CsvHeader::new(path, rdr.headers()?))
+    }
+
+    /// Skips any preamble rows up to the one starting with `first_column`,
+    /// for readers built without `has_headers`
+    pub(crate) fn find_in_reader<R: std::io::Read>(
+        path: &str,
+        rdr: &mut csv::Reader<R>,
+        first_column: &str,
+    ) -> Result<Self, DomainError> {
+        for result in rdr.records() {
+            let record = result?;
+            if record
+                .get(0)
+                .is_some_and(|name| name.trim().eq_ignore_ascii_case(first_column))
+            {
+                return Ok(CsvHeader::new(path, &record));
+            }
+        }
+        Err(DomainError::Parse(format!(
+            "{}: missing header row starting with '{}'",
+            path, first_column
+        )))
+    }
+
+    /// Reads every row, recording the ones the csv reader rejects
+    pub(crate) fn records<R: std::io::Read>(
+        &self,
+        rdr: &mut csv::Reader<R>,
+        report: &mut ImportReport,
+    ) -> Vec<CsvRecord> {
+        let mut records = vec![];
+        for result in rdr.records() {
+            report.add_row();
+            match result {
+                Ok(fields) => records.push(CsvRecord {
+                    line: fields.position().map_or(0, |p| p.line()),
+                    fields,
+                }),
+                Err(e) => {
+                    let line = e.position().map_or(0, |p| p.line());
+                    report.add_error(&self.path, line, None, e.to_string());
+                }
+            }
+        }
+        records
+    }
+
+    pub(crate) fn row<'a>(
+        &'a self,
+        record: &'a CsvRecord,
+        report: &'a mut ImportReport,
+    ) -> CsvRow<'a> {
+        CsvRow {
+            header: self,
+            record,
+            report,
+            valid: true,
+        }
+    }
+
+    pub(crate) fn column(&self, name: &str) -> Result<usize, DomainError> {
+        self.names
+            .iter()
+            .position(|header| header.eq_ignore_ascii_case(name))
+            .ok_or_else(|| {
+                DomainError::Parse(format!(
+                    "{}: missing column '{}' (found {})",
+                    self.path,
+                    name,
+                    self.names.join(", ")
+                ))
+            })
+    }
+
+    /// Columns whose header `parse` accepts, with what it parsed to
+    pub(crate) fn matching_columns<T>(&self, parse: impl Fn(&str) -> Option<T>) -> Vec<(usize, T)> {
+        self.names
+            .iter()
+            .enumerate()
+            .filter_map(|(i, name)| parse(name).map(|value| (i, value)))
+            .collect()
+    }
+
+    /// Columns whose header is a `YYYY-MM-DD` date, such as Zillow's monthly
+    /// values
+    pub(crate) fn date_columns(&self) -> Result<Vec<(usize, NaiveDate)>, DomainError> {
+        let dates = self.matching_columns(|name| NaiveDate::parse_from_str(name, "%Y-%m-%d").ok());
+        if dates.is_empty() {
+            return Err(DomainError::Parse(format!(
+                "{}: no date columns found",
+                self.path
+            )));
+        }
+        Ok(dates)
+    }
+}
+
+/// Parses the fields of one row, recording each failure against its column.
+/// A row with any failure should be skipped once parsing finishes.
+pub(crate) struct CsvRow<'a> {
+    header: &'a CsvHeader,
+    record: &'a CsvRecord,
+    report: &'a mut ImportReport,
+    valid: bool,
+}
+
+impl CsvRow<'_> {
+    pub(crate) fn is_valid(&self) -> bool {
+        self.valid
+    }
+
+    pub(crate) fn error(&mut self, column: usize, message: String) {
+        let name = self.header.names.get(column).map(String::as_str);
+        self.report
+            .add_error(&self.header.path, self.record.line, name, message);
+        self.valid = false;
+    }
+
+    pub(crate) fn text(&mut self, column: usize) -> String {
+        let value = self.record.get(column);
+        if value.is_empty() {
+            self.error(column, "missing value".to_string());
+        }
+        value.to_string()
+    }
+
+    pub(crate) fn required<T>(&mut self, column: usize) -> Option<T>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        match self.optional(column) {
+            Some(value) => Some(value),
+            None if is_missing(self.record.get(column)) => {
+                self.error(column, "missing value".to_string());
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// Blank, `.` and `ND` cells are missing values, anything else must parse
+    pub(crate) fn optional<T>(&mut self, column: usize) -> Option<T>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let value = self.record.get(column);
+        if is_missing(value) {
+            return None;
+        }
+        match value.parse() {
+            Ok(value) => Some(value),
+            Err(e) => {
+                let message = format!("invalid value '{}': {}", value, e);
+                self.error(column, message);
+                None
+            }
+        }
+    }
+}
+
+// FRED marks missing observations with `.`, the Fed's H.15 with `ND`
+fn is_missing(value: &str) -> bool {
+    value.is_empty() || value == "." || value == "ND"
+}
+
 pub(crate) fn to_ymd_date(year: u32, month: u32, day: u32) -> Result<NaiveDate, DomainError> {
     // If day is not present, default to 15
     let year = year as i32;
@@ -0,0 +1,92 @@
+use chrono::{Datelike, Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use super::{ZhviPrice, ZhviPrices};
+use crate::error::DomainError;
+
+/// How months missing between two stored prices are filled on query
+#[derive(Clone, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub enum GapFill {
+    /// Missing months stay missing
+    #[default]
+    None,
+    /// Interpolated by date between the prices on either side
+    Linear,
+    /// The last price before the gap carried forward
+    Forward,
+}
+
+impl TryFrom<&str> for GapFill {
+    type Error = crate::error::DomainError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        match s {
+            "none" => Ok(GapFill::None),
+            "linear" => Ok(GapFill::Linear),
+            "ffill" | "forward" => Ok(GapFill::Forward),
+            _ => Err(DomainError::Parse("Failed to parse GapFill".to_string())),
+        }
+    }
+}
+
+impl std::fmt::Display for GapFill {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GapFill::None => write!(f, "none"),
+            GapFill::Linear => write!(f, "linear"),
+            GapFill::Forward => write!(f, "ffill"),
+        }
+    }
+}
+
+/// Prices in date order with a `filled` price for each month missing between
+/// two stored ones. Months before the first or after the last price are not
+/// extrapolated.
+pub(crate) fn fill_gaps(prices: &[ZhviPrice], gap_fill: &GapFill) -> ZhviPrices {
+    let mut prices = prices.to_vec();
+    prices.sort_by_key(|price| price.date);
+    if gap_fill == &GapFill::None {
+        return prices;
+    }
+
+    let mut filled = vec![];
+    for pair in prices.windows(2) {
+        let (before, after) = (&pair[0], &pair[1]);
+        filled.push(before.clone());
+        let span = (after.date - before.date).num_days() as f64;
+        let dates = (1..)
+            .map_while(|months| month_after(before.date, months))
+            .take_while(|date| month_index(*date) < month_index(after.date));
+        for date in dates {
+            let value = match gap_fill {
+                GapFill::Linear => {
+                    let elapsed = (date - before.date).num_days() as f64;
+                    before.value + (after.value - before.value) * elapsed / span
+                }
+                _ => before.value,
+            };
+            filled.push(ZhviPrice {
+                date,
+                value,
+                filled: true,
+            });
+        }
+    }
+    filled.extend(prices.last().cloned());
+    filled
+}
+
+/// `date` plus `months`, keeping Zillow's month-end dates on month ends
+pub(crate) fn month_after(date: NaiveDate, months: u32) -> Option<NaiveDate> {
+    let next = date.checked_add_months(Months::new(months))?;
+    if date.succ_opt()?.month() == date.month() {
+        return Some(next);
+    }
+    next.with_day(1)?
+        .checked_add_months(Months::new(1))?
+        .pred_opt()
+}
+
+pub(crate) fn month_index(date: NaiveDate) -> i32 {
+    date.year() * 12 + date.month0() as i32
+}
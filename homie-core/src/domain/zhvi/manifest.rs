@@ -0,0 +1,128 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::{HomeType, Percentile};
+use crate::domain::common::RegionType;
+use crate::error::DomainError;
+
+/// A Zillow ZHVI export and the series it holds
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct ZhviFile {
+    pub(crate) home_type: HomeType,
+    pub(crate) region_type: RegionType,
+    pub(crate) percentile: Percentile,
+    pub(crate) path: String,
+}
+
+/// Zillow exports found under a directory, and the files that could not be
+/// identified
+#[derive(Clone, Debug, Default)]
+pub struct ZhviManifest {
+    files: Vec<ZhviFile>,
+    unknown: Vec<String>,
+}
+
+impl ZhviManifest {
+    pub(crate) fn files(&self) -> &[ZhviFile] {
+        &self.files
+    }
+
+    pub fn unknown(&self) -> &[String] {
+        &self.unknown
+    }
+}
+
+impl std::fmt::Display for ZhviManifest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for file in &self.files {
+            writeln!(
+                f,
+                "{:<18} {:<10} {:<7} {}",
+                file.home_type.to_string(),
+                file.region_type.to_string(),
+                file.percentile.to_string(),
+                file.path
+            )?;
+        }
+        for path in &self.unknown {
+            writeln!(f, "{:<37} {}", "unknown", path)?;
+        }
+        Ok(())
+    }
+}
+
+/// Walks `dir` for Zillow exports, sorted by path so imports are repeatable
+pub(crate) fn discover_zhvi_files(dir: &str) -> Result<ZhviManifest, DomainError> {
+    let mut paths = vec![];
+    collect_files(Path::new(dir), &mut paths)?;
+    paths.sort();
+
+    let mut manifest = ZhviManifest::default();
+    for path in paths {
+        let name = path.file_name().and_then(|name| name.to_str());
+        let path = path.to_string_lossy().to_string();
+        match name.and_then(parse_zhvi_filename) {
+            Some((home_type, region_type, percentile)) => manifest.files.push(ZhviFile {
+                home_type,
+                region_type,
+                percentile,
+                path,
+            }),
+            None => manifest.unknown.push(path),
+        }
+    }
+    Ok(manifest)
+}
+
+fn collect_files(dir: &Path, paths: &mut Vec<PathBuf>) -> Result<(), DomainError> {
+    let entries = fs::read_dir(dir)
+        .map_err(|e| DomainError::Parse(format!("Failed to read {}: {}", dir.display(), e)))?;
+    for entry in entries {
+        let path = entry
+            .map_err(|e| DomainError::Parse(format!("Failed to read {}: {}", dir.display(), e)))?
+            .path();
+        let hidden = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with('.'));
+        if hidden {
+            continue;
+        }
+        if path.is_dir() {
+            collect_files(&path, paths)?;
+        } else {
+            paths.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Reads Zillow's naming convention, e.g.
+/// `City_zhvi_uc_sfrcondo_tier_0.0_0.33_sm_sa_month.csv` is City / AllHomes /
+/// Bottom
+pub(crate) fn parse_zhvi_filename(name: &str) -> Option<(HomeType, RegionType, Percentile)> {
+    let stem = name.strip_suffix("_month.csv")?;
+    let parts: Vec<&str> = stem.split('_').collect();
+    let [region, "zhvi", "uc", home, "tier", low, high, ..] = parts.as_slice() else {
+        return None;
+    };
+    let region_type = match *region {
+        "Zip" => RegionType::FiveZip,
+        "City" => RegionType::City,
+        "County" => RegionType::County,
+        _ => return None,
+    };
+    let home_type = match *home {
+        "sfrcondo" => HomeType::AllHomes,
+        "condo" => HomeType::CondoCoOps,
+        "sfr" => HomeType::SingleFamilyHomes,
+        _ => return None,
+    };
+    let percentile = match (*low, *high) {
+        ("0.0", "0.33") => Percentile::Bottom,
+        ("0.33", "0.67") => Percentile::Middle,
+        ("0.67", "1.0") => Percentile::Top,
+        _ => return None,
+    };
+    Some((home_type, region_type, percentile))
+}
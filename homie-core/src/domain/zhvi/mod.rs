@@ -1,23 +1,44 @@
+use std::collections::HashSet;
+
 use async_trait::async_trait;
 use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
+use self::fill::fill_gaps;
+pub use self::fill::GapFill;
+pub(crate) use self::fill::{month_after, month_index};
+use self::manifest::discover_zhvi_files;
+#[cfg(test)]
+pub(crate) use self::manifest::parse_zhvi_filename;
+pub use self::manifest::ZhviManifest;
+use crate::adapter::importer::ImportReport;
 use crate::adapter::repository::Persist;
 use crate::domain::common::{DateInterval, RegionType};
-use crate::domain::util::{to_ymd_date, CsvRecord};
+use crate::domain::cpi::{CpiBase, Deflator};
+use crate::domain::resample::{interval_start, resample, Resampling, Sample};
+use crate::domain::util::CsvHeader;
 use crate::error::DomainError;
 
+mod fill;
+mod manifest;
+
 #[derive(Clone, Debug, Default, Deserialize, Serialize, ToSchema)]
 pub struct Zhvi {
     pub region_name: String,
     pub region_type: RegionType,
+    /// USPS state abbreviation of city and county ZHVIs, whose names repeat
+    /// across states
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
     pub home_type: HomeType,
     pub percentile: Percentile,
     pub prices: ZhviPrices,
 }
 
-#[derive(Clone, Debug, Default, Deserialize, Serialize, sqlx::Type, ToSchema)]
+#[derive(
+    Clone, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize, sqlx::Type, ToSchema,
+)]
 #[sqlx(type_name = "home_type", rename_all = "lowercase")]
 pub enum HomeType {
     #[default]
@@ -26,6 +47,16 @@ pub enum HomeType {
     SingleFamilyHomes,
 }
 
+impl HomeType {
+    // Only the Postgres enum-drift check needs every label
+    #[cfg(feature = "postgres")]
+    pub(crate) const ALL: [HomeType; 3] = [
+        HomeType::AllHomes,
+        HomeType::CondoCoOps,
+        HomeType::SingleFamilyHomes,
+    ];
+}
+
 impl TryFrom<&str> for HomeType {
     type Error = crate::error::DomainError;
 
@@ -49,7 +80,9 @@ impl std::fmt::Display for HomeType {
     }
 }
 
-#[derive(Clone, Debug, Default, Deserialize, Serialize, sqlx::Type, ToSchema)]
+#[derive(
+    Clone, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize, sqlx::Type, ToSchema,
+)]
 #[sqlx(type_name = "percentile", rename_all = "lowercase")]
 pub enum Percentile {
     Bottom,
@@ -58,6 +91,11 @@ pub enum Percentile {
     Top,
 }
 
+impl Percentile {
+    pub(crate) const ALL: [Percentile; 3] =
+        [Percentile::Bottom, Percentile::Middle, Percentile::Top];
+}
+
 impl TryFrom<&str> for Percentile {
     type Error = crate::error::DomainError;
 
@@ -85,10 +123,42 @@ impl std::fmt::Display for Percentile {
 pub struct ZhviPrice {
     pub date: NaiveDate,
     pub value: f64,
+    /// Not stored but filled in on query, only serialized when set
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub filled: bool,
+}
+
+pub(crate) fn is_false(value: &bool) -> bool {
+    !value
+}
+
+impl Sample for ZhviPrice {
+    fn series(&self) -> String {
+        String::new()
+    }
+
+    fn date(&self) -> NaiveDate {
+        self.date
+    }
+
+    fn values(&self) -> Vec<Option<f64>> {
+        vec![Some(self.value)]
+    }
+
+    fn resampled(&self, date: NaiveDate, values: Vec<Option<f64>>) -> Self {
+        Self {
+            date,
+            value: values[0].unwrap_or_default(),
+            filled: false,
+        }
+    }
 }
 
 pub type ZhviPrices = Vec<ZhviPrice>;
 pub type Zhvis = Vec<Zhvi>;
+/// Region name, region type, home type and percentile labels, and the state
+/// of city and county ZHVIs
+pub type ZhviId<'a> = (&'a str, &'a str, &'a str, &'a str, Option<&'a str>);
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct ZhviData {
@@ -111,7 +181,7 @@ impl ZhviData {
     }
 }
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
 pub struct ZhviQuery {
     start_date: NaiveDate,
     end_date: NaiveDate,
@@ -120,6 +190,10 @@ pub struct ZhviQuery {
     region_type: RegionType,
     home_type: HomeType,
     percentile: Percentile,
+    state: Option<String>,
+    resampling: Resampling,
+    gap_fill: GapFill,
+    real: Option<CpiBase>,
 }
 
 impl ZhviQuery {
@@ -140,9 +214,49 @@ impl ZhviQuery {
             region_type,
             home_type,
             percentile,
+            state: None,
+            resampling: Resampling::default(),
+            gap_fill: GapFill::default(),
+            real: None,
+        }
+    }
+
+    /// The same query for another region, in any state
+    pub(crate) fn for_region(&self, region_name: String, region_type: RegionType) -> Self {
+        ZhviQuery {
+            region_name,
+            region_type,
+            state: None,
+            ..self.clone()
         }
     }
 
+    /// Reads only the region in `state`, to tell apart same-named cities and
+    /// counties
+    pub fn with_state(mut self, state: String) -> Self {
+        self.state = Some(state);
+        self
+    }
+
+    /// Combines the prices in each interval some other way than the mean
+    pub fn with_resampling(mut self, resampling: Resampling) -> Self {
+        self.resampling = resampling;
+        self
+    }
+
+    /// Fills months missing between stored prices before resampling
+    pub fn with_gap_fill(mut self, gap_fill: GapFill) -> Self {
+        self.gap_fill = gap_fill;
+        self
+    }
+
+    /// Deflates prices to dollars of `base` with the CPI. Months without a
+    /// CPI are left out.
+    pub fn with_real(mut self, base: CpiBase) -> Self {
+        self.real = Some(base);
+        self
+    }
+
     pub(crate) fn start_date(&self) -> &NaiveDate {
         &self.start_date
     }
@@ -170,15 +284,26 @@ impl ZhviQuery {
     pub(crate) fn percentile(&self) -> &Percentile {
         &self.percentile
     }
+
+    pub(crate) fn state(&self) -> Option<&str> {
+        self.state.as_deref()
+    }
+
+    /// Whether a ZHVI in `state` is one the query reads
+    pub(crate) fn includes_state(&self, state: Option<&str>) -> bool {
+        self.state.is_none() || self.state.as_deref() == state
+    }
 }
 
 #[async_trait]
 pub trait ZhviPersist: Send + Sync {
     // TODO: Return Keys instead of unit type
     async fn create_zhvi(&self, zhvi: &Zhvi) -> Result<(), DomainError>;
-    async fn read_zhvi_by_id(&self, id: (&str, &str, &str, &str)) -> Result<Zhvi, DomainError>;
+    /// Inserts in bulk, skipping ZHVIs and prices that already exist
+    async fn create_zhvis(&self, zhvis: &[Zhvi]) -> Result<(), DomainError>;
+    async fn read_zhvi_by_id(&self, id: ZhviId<'_>) -> Result<Zhvi, DomainError>;
     async fn update_zhvi(&self, zhvi: &Zhvi) -> Result<(), DomainError>;
-    async fn delete_zhvi_by_id(&self, id: (&str, &str, &str, &str)) -> Result<(), DomainError>;
+    async fn delete_zhvi_by_id(&self, id: ZhviId<'_>) -> Result<(), DomainError>;
     async fn read_zhvi_by_query(&self, query: &ZhviQuery) -> Result<Zhvis, DomainError>;
 }
 
@@ -199,6 +324,10 @@ impl Zhvi {
         &self.percentile
     }
 
+    pub fn state(&self) -> Option<&str> {
+        self.state.as_deref()
+    }
+
     pub fn prices(&self) -> &ZhviPrices {
         &self.prices
     }
@@ -208,10 +337,11 @@ impl Zhvi {
         client.create_zhvi(self).await
     }
 
-    pub async fn read(
-        client: &dyn Persist,
-        id: (&str, &str, &str, &str),
-    ) -> Result<Zhvi, DomainError> {
+    pub async fn create_many(client: &dyn Persist, zhvis: &[Zhvi]) -> Result<(), DomainError> {
+        client.create_zhvis(zhvis).await
+    }
+
+    pub async fn read(client: &dyn Persist, id: ZhviId<'_>) -> Result<Zhvi, DomainError> {
         client.read_zhvi_by_id(id).await
     }
 
@@ -219,10 +349,7 @@ impl Zhvi {
         client.update_zhvi(self).await
     }
 
-    pub async fn delete(
-        client: &dyn Persist,
-        id: (&str, &str, &str, &str),
-    ) -> Result<(), DomainError> {
+    pub async fn delete(client: &dyn Persist, id: ZhviId<'_>) -> Result<(), DomainError> {
         client.delete_zhvi_by_id(id).await
     }
 
@@ -230,249 +357,143 @@ impl Zhvi {
         client: &dyn Persist,
         query: &ZhviQuery,
     ) -> Result<Zhvis, DomainError> {
-        client.read_zhvi_by_query(query).await
+        let mut zhvis = client.read_zhvi_by_query(query).await?;
+        let deflator = match &query.real {
+            Some(base) => {
+                Some(Deflator::read(client, base, query.start_date, query.end_date).await?)
+            }
+            None => None,
+        };
+        for zhvi in zhvis.iter_mut() {
+            let mut prices = fill_gaps(&zhvi.prices, &query.gap_fill);
+            if let Some(deflator) = &deflator {
+                prices.retain_mut(|price| match deflator.deflate(price.date, price.value) {
+                    Some(value) => {
+                        price.value = value;
+                        true
+                    }
+                    None => false,
+                });
+            }
+            // An interval holding any filled price is flagged as filled
+            let filled: Vec<NaiveDate> = prices
+                .iter()
+                .filter(|price| price.filled)
+                .map(|price| interval_start(price.date, &query.date_interval))
+                .collect();
+            zhvi.prices = resample(&prices, &query.date_interval, &query.resampling);
+            for price in zhvi.prices.iter_mut() {
+                price.filled = filled.contains(&price.date);
+            }
+        }
+        Ok(zhvis)
     }
 }
 
 #[derive(Clone, Debug, Default)]
 pub(crate) struct ZhviConfig {
-    bot_city_all_homes_path: Option<String>,
-    mid_zip_all_homes_path: Option<String>,
-    mid_city_all_homes_path: Option<String>,
-    mid_county_all_homes_path: Option<String>,
+    zhvi_dir: Option<String>,
 }
 
 impl ZhviConfig {
-    pub fn new(
-        bot_city_all_homes_path: Option<String>,
-        mid_zip_all_homes_path: Option<String>,
-        mid_city_all_homes_path: Option<String>,
-        mid_county_all_homes_path: Option<String>,
-    ) -> Self {
-        ZhviConfig {
-            bot_city_all_homes_path,
-            mid_zip_all_homes_path,
-            mid_city_all_homes_path,
-            mid_county_all_homes_path,
-        }
+    pub fn new(zhvi_dir: Option<String>) -> Self {
+        ZhviConfig { zhvi_dir }
     }
 
-    fn bot_city_all_homes_path(&self) -> Option<&str> {
-        self.bot_city_all_homes_path.as_deref()
-    }
-
-    fn mid_zip_all_homes_path(&self) -> Option<&str> {
-        self.mid_zip_all_homes_path.as_deref()
-    }
-
-    fn mid_city_all_homes_path(&self) -> Option<&str> {
-        self.mid_city_all_homes_path.as_deref()
-    }
-
-    fn mid_county_all_homes_path(&self) -> Option<&str> {
-        self.mid_county_all_homes_path.as_deref()
+    fn zhvi_dir(&self) -> Option<&str> {
+        self.zhvi_dir.as_deref()
     }
 }
 
-pub(crate) fn read_zillow_zhvis(zhvi_config: &ZhviConfig) -> Result<ZhviData, DomainError> {
-    let zhvi_data = ZhviData {
-        all_homes_zhvis: read_all_homes_zhvis(zhvi_config)?,
-        // condo_coops_zhvis = read_condo_coops_zhvis(zhvi_config)?;
-        // single_family_homes_zhvis = read_single_family_homes_zhvis(zhvi_config)?;
-        ..Default::default()
-    };
-
-    Ok(zhvi_data)
-}
-
-fn read_all_homes_zhvis(zhvi_config: &ZhviConfig) -> Result<Zhvis, DomainError> {
-    let mut all_homes = Zhvis::default();
-    if let Some(mid_zip_all_homes_path) = zhvi_config.mid_zip_all_homes_path() {
-        all_homes.append(&mut read_mid_zip_all_homes(mid_zip_all_homes_path)?);
-    }
-
-    if let Some(mid_city_all_homes_path) = zhvi_config.mid_city_all_homes_path() {
-        all_homes.append(&mut read_mid_city_all_homes(mid_city_all_homes_path)?);
-    }
-
-    if let Some(mid_county_all_homes_path) = zhvi_config.mid_county_all_homes_path() {
-        all_homes.append(&mut read_mid_county_all_homes(mid_county_all_homes_path)?);
-    }
-
-    if let Some(bot_city_all_homes_path) = zhvi_config.bot_city_all_homes_path() {
-        all_homes.append(&mut read_bot_city_all_homes(bot_city_all_homes_path)?);
+pub(crate) fn read_zhvi_manifest(zhvi_config: &ZhviConfig) -> Result<ZhviManifest, DomainError> {
+    match zhvi_config.zhvi_dir() {
+        Some(zhvi_dir) => discover_zhvi_files(zhvi_dir),
+        None => Ok(ZhviManifest::default()),
     }
-    Ok(all_homes)
 }
 
-fn read_mid_city_all_homes(mid_city_all_homes_path: &str) -> Result<Zhvis, DomainError> {
-    let mut rdr = csv::ReaderBuilder::new()
-        .has_headers(true)
-        .from_path(mid_city_all_homes_path)?;
-    let mut mid_all_homes = vec![];
-    let entries: Vec<CsvRecord> = rdr.deserialize().filter_map(Result::ok).collect();
-    let headers = rdr.headers()?;
-
-    for entry in entries.into_iter() {
-        // start at 8
-        let mut prices = vec![];
-        for i in 8..entry.0.len() {
-            let parts: Vec<&str> = headers
-                .iter()
-                .nth(i)
-                .ok_or(DomainError::Parse(
-                    "Failed to parse string to date".to_string(),
-                ))?
-                .split('-')
-                .collect();
-            let year = parts[0].parse()?;
-            let month = parts[1].parse()?;
-            let day = parts[2].parse()?;
-            let date = to_ymd_date(year, month, day)?;
-            let value = entry.0[i].parse().unwrap_or_default();
-            prices.push(ZhviPrice { date, value });
+pub(crate) fn read_zillow_zhvis(
+    zhvi_config: &ZhviConfig,
+    report: &mut ImportReport,
+) -> Result<ZhviData, DomainError> {
+    let manifest = read_zhvi_manifest(zhvi_config)?;
+    for path in manifest.unknown() {
+        report.add_error(path, 0, None, "unrecognized Zillow filename".to_string());
+    }
+
+    let mut zhvi_data = ZhviData::default();
+    for file in manifest.files() {
+        let mut zhvis = read_zillow_zhvi_file(
+            &file.path,
+            file.home_type.clone(),
+            file.region_type.clone(),
+            file.percentile.clone(),
+            report,
+        )?;
+        match file.home_type {
+            HomeType::AllHomes => zhvi_data.all_homes_zhvis.append(&mut zhvis),
+            HomeType::CondoCoOps => zhvi_data.condo_coops_zhvis.append(&mut zhvis),
+            HomeType::SingleFamilyHomes => zhvi_data.single_family_homes_zhvis.append(&mut zhvis),
         }
-        let home_type = HomeType::AllHomes;
-        let region_type = RegionType::City;
-        let region_name = entry.0[2].clone();
-        let percentile = Percentile::Middle;
-        mid_all_homes.push(Zhvi {
-            home_type,
-            region_type,
-            region_name,
-            percentile,
-            prices,
-        });
     }
 
-    Ok(mid_all_homes)
-}
-
-fn read_mid_county_all_homes(mid_county_all_homes_path: &str) -> Result<Zhvis, DomainError> {
-    let mut rdr = csv::ReaderBuilder::new()
-        .has_headers(true)
-        .from_path(mid_county_all_homes_path)?;
-
-    let mut mid_all_homes = vec![];
-    let entries: Vec<CsvRecord> = rdr.deserialize().filter_map(Result::ok).collect();
-    let headers = rdr.headers()?;
-    for entry in entries.into_iter() {
-        // start at 8
-        let mut prices = vec![];
-        for i in 9..entry.0.len() {
-            let parts: Vec<&str> = headers
-                .iter()
-                .nth(i)
-                .ok_or(DomainError::Parse(
-                    "Failed to parse string to date".to_string(),
-                ))?
-                .split('-')
-                .collect();
-            let year = parts[0].parse()?;
-            let month = parts[1].parse()?;
-            let day = parts[2].parse()?;
-            let date = to_ymd_date(year, month, day)?;
-            let value = entry.0[i].parse().unwrap_or_default();
-            prices.push(ZhviPrice { date, value });
-        }
-        let home_type = HomeType::AllHomes;
-        let region_type = RegionType::County;
-        let region_name = entry.0[2].clone();
-        let percentile = Percentile::Middle;
-        mid_all_homes.push(Zhvi {
-            home_type,
-            region_type,
-            region_name,
-            percentile,
-            prices,
-        });
-    }
-
-    Ok(mid_all_homes)
+    Ok(zhvi_data)
 }
 
-fn read_mid_zip_all_homes(mid_zip_all_homes_path: &str) -> Result<Zhvis, DomainError> {
+fn read_zillow_zhvi_file(
+    path: &str,
+    home_type: HomeType,
+    region_type: RegionType,
+    percentile: Percentile,
+    report: &mut ImportReport,
+) -> Result<Zhvis, DomainError> {
     let mut rdr = csv::ReaderBuilder::new()
         .has_headers(true)
-        .from_path(mid_zip_all_homes_path)?;
-
-    let mut mid_all_homes = vec![];
-    let entries: Vec<CsvRecord> = rdr.deserialize().filter_map(Result::ok).collect();
-    let headers = rdr.headers()?;
-    for entry in entries.into_iter() {
-        let mut prices = vec![];
-        // start at 8
-        for i in 9..entry.0.len() {
-            let parts: Vec<&str> = headers
-                .iter()
-                .nth(i)
-                .ok_or(DomainError::Parse(
-                    "Failed to parse string to date".to_string(),
-                ))?
-                .split('-')
-                .collect();
-            let year = parts[0].parse()?;
-            let month = parts[1].parse()?;
-            let day = parts[2].parse()?;
-            let date = to_ymd_date(year, month, day)?;
-            let value = entry.0[i].parse().unwrap_or_default();
-            prices.push(ZhviPrice { date, value });
+        .from_path(path)?;
+    let header = CsvHeader::from_reader(path, &mut rdr)?;
+    let region_name_col = header.column("RegionName")?;
+    // City and county names repeat across states
+    let state_col = match region_type {
+        RegionType::City | RegionType::County => Some(header.column("State")?),
+        _ => None,
+    };
+    let date_cols = header.date_columns()?;
+
+    let mut zhvis = vec![];
+    // A repeated key would be merged into the first region when stored
+    let mut keys = HashSet::new();
+    for entry in header.records(&mut rdr, report) {
+        let mut row = header.row(&entry, report);
+        let region_name = row.text(region_name_col);
+        let state = state_col.map(|col| row.text(col));
+        // Months before a region was tracked are blank, so they are left out
+        let prices = date_cols
+            .iter()
+            .filter_map(|(i, date)| {
+                row.optional(*i).map(|value| ZhviPrice {
+                    date: *date,
+                    value,
+                    filled: false,
+                })
+            })
+            .collect();
+        if row.is_valid() && !keys.insert((region_name.clone(), state.clone())) {
+            let message = match &state {
+                Some(state) => format!("duplicate region '{}' in {}", region_name, state),
+                None => format!("duplicate region '{}'", region_name),
+            };
+            row.error(region_name_col, message);
         }
-        let home_type = HomeType::AllHomes;
-        let region_type = RegionType::FiveZip;
-        let region_name = entry.0[2].clone();
-        let percentile = Percentile::Middle;
-        mid_all_homes.push(Zhvi {
-            home_type,
-            region_type,
-            region_name,
-            percentile,
-            prices,
-        });
-    }
-
-    Ok(mid_all_homes)
-}
-
-fn read_bot_city_all_homes(bot_city_all_homes_path: &str) -> Result<Zhvis, DomainError> {
-    let mut rdr = csv::ReaderBuilder::new()
-        .has_headers(true)
-        .from_path(bot_city_all_homes_path)?;
-
-    let mut bot_all_homes = vec![];
-    let entries: Vec<CsvRecord> = rdr.deserialize().filter_map(Result::ok).collect();
-    let headers = rdr.headers()?;
-    for entry in entries.into_iter() {
-        let mut prices = vec![];
-        // start at 8
-        for i in 9..entry.0.len() {
-            let parts: Vec<&str> = headers
-                .iter()
-                .nth(i)
-                .ok_or(DomainError::Parse(
-                    "Failed to parse string to date".to_string(),
-                ))?
-                .split('-')
-                .collect();
-            let year = parts[0].parse()?;
-            let month = parts[1].parse()?;
-            let day = parts[2].parse()?;
-            let date = to_ymd_date(year, month, day)?;
-            let value = entry.0[i].parse().unwrap_or_default();
-            prices.push(ZhviPrice { date, value });
+        if row.is_valid() {
+            zhvis.push(Zhvi {
+                home_type: home_type.clone(),
+                region_type: region_type.clone(),
+                region_name,
+                state,
+                percentile: percentile.clone(),
+                prices,
+            });
         }
-        let home_type = HomeType::AllHomes;
-        let region_type = RegionType::City;
-        let region_name = entry.0[2].clone();
-        let percentile = Percentile::Bottom;
-        bot_all_homes.push(Zhvi {
-            home_type,
-            region_type,
-            region_name,
-            percentile,
-            prices,
-        });
     }
-
-    Ok(bot_all_homes)
+    Ok(zhvis)
 }
@@ -2,7 +2,9 @@
 pub enum DomainError {
     ConvertDomain(String),
     Database(String),
+    Http(String),
     Parse(String),
+    ReadOnly(String),
 }
 
 impl std::fmt::Display for DomainError {
@@ -10,7 +12,9 @@ impl std::fmt::Display for DomainError {
         match *self {
             DomainError::ConvertDomain(ref message) => write!(f, "Parse error: {}", message),
             DomainError::Database(ref message) => write!(f, "Database error: {}", message),
+            DomainError::Http(ref message) => write!(f, "HTTP error: {}", message),
             DomainError::Parse(ref message) => write!(f, "Parse error: {}", message),
+            DomainError::ReadOnly(ref message) => write!(f, "Read-only source: {}", message),
         }
     }
 }
@@ -27,6 +31,18 @@ impl From<sqlx::Error> for DomainError {
     }
 }
 
+impl From<reqwest::Error> for DomainError {
+    fn from(value: reqwest::Error) -> Self {
+        DomainError::Http(format!("Failed HTTP request: {}", value))
+    }
+}
+
+impl From<serde_json::Error> for DomainError {
+    fn from(value: serde_json::Error) -> Self {
+        DomainError::Parse(format!("Failed to read from json: {}", value))
+    }
+}
+
 impl From<std::num::ParseIntError> for DomainError {
     fn from(value: std::num::ParseIntError) -> Self {
         DomainError::Parse(format!("Failed to parse integer: {}", value))
@@ -0,0 +1,121 @@
+use super::date;
+use crate::adapter::repository::database::memory::InMemoryClient;
+use crate::domain::affordability::{Affordability, AffordabilityQuery, Financing};
+use crate::domain::common::RegionType;
+use crate::domain::income::Income;
+use crate::domain::mortgage::{Mortgage, RateSource};
+use crate::domain::mortgage_rate::{MortgageRate, MortgageTerm};
+use crate::domain::zhvi::{HomeType, Percentile, Zhvi, ZhviPrice};
+
+fn income(region_id: &str, year: i32, median_income: f64) -> Income {
+    Income {
+        region_type: RegionType::City,
+        region_id: region_id.to_string(),
+        region_name: "Irvine".to_string(),
+        year,
+        median_income: Some(median_income),
+        margin_of_error: None,
+    }
+}
+
+fn query(percentile: Option<Percentile>, income_region: Option<&str>) -> AffordabilityQuery {
+    let query = AffordabilityQuery::new(
+        "Irvine".to_string(),
+        RegionType::City,
+        HomeType::AllHomes,
+        percentile,
+        date(2023, 1, 1),
+        date(2024, 12, 31),
+        Financing::new(RateSource::MortgageRate, 30, 20.0).unwrap(),
+    );
+    match income_region {
+        Some(income_region) => query.with_income_region(income_region.to_string()),
+        None => query,
+    }
+}
+
+#[tokio::test]
+async fn test_affordability_per_tier() {
+    let client = InMemoryClient::new();
+    for (percentile, value) in [
+        (Percentile::Middle, 1_000_000.0),
+        (Percentile::Top, 2_000_000.0),
+    ] {
+        let zhvi = Zhvi {
+            region_name: "Irvine".to_string(),
+            region_type: RegionType::City,
+            state: None,
+            home_type: HomeType::AllHomes,
+            percentile,
+            prices: [(2023, 1), (2023, 2), (2024, 3)]
+                .map(|(year, month)| ZhviPrice {
+                    date: date(year, month, 28),
+                    value,
+                    filled: false,
+                })
+                .into(),
+        };
+        zhvi.create(&client).await.unwrap();
+    }
+    // No rate before February, and the 2022 income carries into 2024
+    let mortgage_rates: Vec<MortgageRate> = [(2, 6.0), (3, 7.0)]
+        .map(|(month, rate)| MortgageRate {
+            term: MortgageTerm::ThirtyYear,
+            date: date(2023, month, 2),
+            rate: Some(rate),
+            points: None,
+        })
+        .into();
+    MortgageRate::create_many(&client, &mortgage_rates)
+        .await
+        .unwrap();
+    Income::create_many(
+        &client,
+        &[
+            income("0636770", 2021, 100_000.0),
+            income("0636770", 2022, 120_000.0),
+        ],
+    )
+    .await
+    .unwrap();
+
+    let affordabilities = Affordability::read_by_query(&client, &query(None, None))
+        .await
+        .unwrap();
+    assert_eq!(affordabilities.len(), 2);
+    let middle = &affordabilities[0];
+    assert_eq!(middle.percentile, Percentile::Middle);
+    assert_eq!(middle.points.len(), 2);
+
+    let point = &middle.points[0];
+    assert_eq!(point.date, date(2023, 2, 28));
+    assert_eq!(point.rate, 6.0);
+    assert_eq!(point.income_year, 2022);
+    let payment = Mortgage::new(1_000_000.0, 200_000.0, 6.0, 30)
+        .unwrap()
+        .monthly_payment();
+    assert!((point.monthly_payment - payment).abs() < 1e-9);
+    assert!((point.payment_to_income - payment * 12.0 / 120_000.0 * 100.0).abs() < 1e-9);
+    assert_eq!(middle.points[1].rate, 7.0);
+
+    let top = &affordabilities[1];
+    assert_eq!(top.percentile, Percentile::Top);
+    assert!((top.points[0].payment_to_income - 2.0 * point.payment_to_income).abs() < 1e-9);
+
+    // A second Irvine needs narrowing by id
+    income("4836770", 2022, 60_000.0)
+        .create(&client)
+        .await
+        .unwrap();
+    assert!(Affordability::read_by_query(&client, &query(None, None))
+        .await
+        .is_err());
+    let affordabilities =
+        Affordability::read_by_query(&client, &query(Some(Percentile::Middle), Some("0636770")))
+            .await
+            .unwrap();
+    assert_eq!(affordabilities.len(), 1);
+    assert_eq!(affordabilities[0].points[0].median_income, 120_000.0);
+
+    assert!(Financing::new(RateSource::MortgageRate, 30, 100.0).is_err());
+}
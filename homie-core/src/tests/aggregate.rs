@@ -0,0 +1,186 @@
+use chrono::NaiveDate;
+
+use crate::adapter::repository::database::memory::InMemoryClient;
+use crate::domain::aggregate::{Aggregation, Aggregator};
+use crate::domain::common::{DateInterval, RegionType};
+use crate::domain::hpi::{Hpi, HpiQuery};
+use crate::domain::region::{Region, RegionQuery};
+use crate::domain::zhvi::{HomeType, Percentile, Zhvi, ZhviPrice, ZhviQuery};
+
+fn region(zipcode: &str, city: &str, county_fips: &str, res_ratio: f64) -> Region {
+    Region {
+        city: city.to_string(),
+        zipcode: zipcode.to_string(),
+        state: "CA".to_string(),
+        county_fips: county_fips.to_string(),
+        res_ratio,
+        ..Default::default()
+    }
+}
+
+fn zip_zhvi(zipcode: &str, value: f64) -> Zhvi {
+    Zhvi {
+        region_name: zipcode.to_string(),
+        region_type: RegionType::FiveZip,
+        prices: vec![ZhviPrice {
+            date: NaiveDate::from_ymd_opt(2024, 1, 31).unwrap(),
+            value,
+            filled: false,
+        }],
+        ..Default::default()
+    }
+}
+
+// 92602 lies wholly in Orange County, while 92780 is split 3:1 with Los Angeles
+// County
+fn regions() -> Vec<Region> {
+    vec![
+        region("92602", "irvine", "06059", 1.0),
+        region("92780", "tustin", "06059", 0.75),
+        region("92780", "tustin", "06037", 0.25),
+    ]
+}
+
+#[test]
+fn test_aggregate_zhvis_by_residential_ratio() {
+    let zhvis = vec![
+        zip_zhvi("92602", 100.0),
+        zip_zhvi("92780", 200.0),
+        // Not a five-zip series, so it is left out
+        Zhvi {
+            region_type: RegionType::City,
+            ..zip_zhvi("92602", 1000.0)
+        },
+    ];
+
+    let aggregator = Aggregator::new(&regions(), RegionType::County).unwrap();
+    let derived = aggregator.zhvis(&zhvis, Aggregation::Mean);
+    assert_eq!(derived.len(), 2);
+    assert_eq!(derived[0].series.region_name, "06037");
+    assert_eq!(derived[0].series.prices[0].value, 200.0);
+    let orange = &derived[1];
+    assert_eq!(orange.series.region_type, RegionType::County);
+    assert_eq!(orange.aggregation, Aggregation::Mean);
+    assert_eq!(orange.zipcodes, vec!["92602", "92780"]);
+    assert!((orange.series.prices[0].value - (100.0 + 0.75 * 200.0) / 1.75).abs() < 1e-9);
+
+    let derived = aggregator.zhvis(&zhvis, Aggregation::Median);
+    assert_eq!(derived[1].series.prices[0].value, 100.0);
+
+    let aggregator = Aggregator::new(&regions(), RegionType::City).unwrap();
+    let derived = aggregator.zhvis(&zhvis, Aggregation::Mean);
+    assert_eq!(derived[1].series.region_name, "tustin");
+    assert_eq!(derived[1].series.prices[0].value, 200.0);
+
+    assert!(Aggregator::new(&regions(), RegionType::ThreeZip).is_err());
+}
+
+#[test]
+fn test_aggregate_hpis_skips_missing_values() {
+    let hpis = vec![
+        Hpi {
+            region_type: RegionType::FiveZip,
+            region_id: "92602".to_string(),
+            year: 2020,
+            hpi: Some(300.0),
+            annual_change: Some(4.0),
+            ..Default::default()
+        },
+        Hpi {
+            region_type: RegionType::FiveZip,
+            region_id: "92780".to_string(),
+            year: 2020,
+            hpi: Some(200.0),
+            ..Default::default()
+        },
+    ];
+
+    let aggregator = Aggregator::new(&regions(), RegionType::County).unwrap();
+    let derived = aggregator.hpis(&hpis, Aggregation::Mean);
+    let orange = &derived[1].series[0];
+    assert_eq!(orange.region_id(), "06059");
+    assert_eq!(orange.state(), Some("CA"));
+    assert!((orange.hpi().unwrap() - (300.0 + 0.75 * 200.0) / 1.75).abs() < 1e-3);
+    assert_eq!(orange.annual_change(), Some(4.0));
+    assert_eq!(orange.hpi_1990_base(), None);
+}
+
+#[tokio::test]
+async fn test_aggregator_reads_regions_and_zip_series() {
+    let client = InMemoryClient::new();
+    let boston = Region {
+        state: "MA".to_string(),
+        ..region("02108", "boston", "25025", 1.0)
+    };
+    for region in regions().into_iter().chain([boston]) {
+        region.create(&client).await.unwrap();
+    }
+    // Zillow drops the leading zero of 02108
+    for zhvi in [
+        zip_zhvi("92602", 100.0),
+        zip_zhvi("92780", 200.0),
+        zip_zhvi("2108", 300.0),
+    ] {
+        zhvi.create(&client).await.unwrap();
+    }
+    Hpi {
+        region_type: RegionType::FiveZip,
+        region_id: "92602".to_string(),
+        year: 2024,
+        hpi: Some(300.0),
+        ..Default::default()
+    }
+    .create(&client)
+    .await
+    .unwrap();
+
+    let zhvi_query = ZhviQuery::new(
+        NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+        NaiveDate::from_ymd_opt(2024, 12, 31).unwrap(),
+        DateInterval::Day,
+        String::new(),
+        RegionType::County,
+        HomeType::default(),
+        Percentile::default(),
+    );
+    let orange = RegionQuery::new(vec![], vec![], vec!["06059".to_string()], vec![]);
+    let aggregator = Aggregator::read(&client, &orange, RegionType::County)
+        .await
+        .unwrap();
+    let derived = aggregator
+        .read_zhvis(&client, &zhvi_query, Aggregation::Mean)
+        .await
+        .unwrap();
+    assert_eq!(derived.len(), 1);
+    assert_eq!(derived[0].series.region_name, "06059");
+    assert_eq!(derived[0].zipcodes, vec!["92602", "92780"]);
+    assert!((derived[0].series.prices[0].value - (100.0 + 0.75 * 200.0) / 1.75).abs() < 1e-9);
+
+    let derived = aggregator
+        .read_hpis(
+            &client,
+            &HpiQuery::new(RegionType::County, String::new(), 2024, 2024),
+            Aggregation::Mean,
+        )
+        .await
+        .unwrap();
+    assert_eq!(derived[0].zipcodes, vec!["92602"]);
+    assert_eq!(derived[0].series[0].hpi(), Some(300.0));
+
+    let boston = RegionQuery::new(vec!["boston".to_string()], vec![], vec![], vec![]);
+    let aggregator = Aggregator::read(&client, &boston, RegionType::City)
+        .await
+        .unwrap();
+    let derived = aggregator
+        .read_zhvis(&client, &zhvi_query, Aggregation::Median)
+        .await
+        .unwrap();
+    assert_eq!(derived[0].zipcodes, vec!["02108"]);
+    assert_eq!(derived[0].series.prices[0].value, 300.0);
+
+    // Without a selection every stored region would be aggregated
+    let everything = RegionQuery::new(vec![], vec![], vec![], vec![]);
+    assert!(Aggregator::read(&client, &everything, RegionType::County)
+        .await
+        .is_err());
+}
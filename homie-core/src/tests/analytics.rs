@@ -0,0 +1,142 @@
+use chrono::NaiveDate;
+
+use super::date;
+use crate::domain::analytics::{
+    analyze, analyze_hpis, analyze_t_yields, cagr, change, drawdown, rolling_mean,
+    rolling_volatility, Observations, Point, Transform,
+};
+use crate::domain::common::RegionType;
+use crate::domain::hpi::Hpi;
+use crate::domain::t_yield::{TYield, Term};
+
+fn point(date: NaiveDate, value: f64) -> Point {
+    Point { date, value }
+}
+
+/// Month-end points from January 2022, as ZHVI prices are dated
+fn monthly(values: &[f64]) -> Vec<Point> {
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| {
+            let month_start = date(2022 + i as i32 / 12, i as u32 % 12 + 1, 1);
+            let month_end = month_start + chrono::Months::new(1) - chrono::Days::new(1);
+            point(month_end, *value)
+        })
+        .collect()
+}
+
+#[test]
+fn test_analytics_change() {
+    let values: Vec<f64> = (0..14).map(|i| 100.0 + i as f64 * 10.0).collect();
+    let points = monthly(&values);
+
+    let yoy = change(&points, 12);
+    assert_eq!(yoy.len(), 2);
+    assert_eq!(yoy[0].date, date(2023, 1, 31));
+    assert!((yoy[0].value - 120.0).abs() < 1e-9);
+
+    let mom = change(&points, 1);
+    assert_eq!(mom.len(), 13);
+    // February 28th still finds January 31st a month back
+    assert_eq!(mom[0].date, date(2022, 2, 28));
+    assert!((mom[0].value - 10.0).abs() < 1e-9);
+
+    // A daily series missing the exact date a year back uses the day before
+    let daily = [point(date(2023, 3, 3), 4.0), point(date(2024, 3, 4), 5.0)];
+    assert!((change(&daily, 12)[0].value - 25.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_analytics_cagr() {
+    let points = [
+        point(date(2020, 1, 1), 100.0),
+        point(date(2021, 1, 1), 150.0),
+        point(date(2022, 1, 1), 121.0),
+    ];
+    assert!((cagr(&points).unwrap() - 10.0).abs() < 0.01);
+    assert_eq!(cagr(&points[..1]), None);
+    assert_eq!(cagr(&[]), None);
+}
+
+#[test]
+fn test_analytics_rolling() {
+    let points = monthly(&[100.0, 110.0, 99.0, 108.9]);
+    let means = rolling_mean(&points, 2);
+    assert_eq!(means.len(), 3);
+    assert_eq!(means[0].date, points[1].date);
+    assert!((means[0].value - 105.0).abs() < 1e-9);
+
+    // Changes of 10%, -10% and 10%
+    let volatility = rolling_volatility(&points, 2);
+    assert_eq!(volatility.len(), 2);
+    assert!((volatility[0].value - 200f64.sqrt()).abs() < 1e-9);
+    assert!(rolling_volatility(&points, 4).is_empty());
+    assert!(rolling_mean(&points, 0).is_empty());
+}
+
+#[test]
+fn test_analytics_drawdown() {
+    let points = monthly(&[100.0, 120.0, 90.0, 60.0, 100.0, 130.0, 110.0]);
+    let (series, deepest) = drawdown(&points);
+    assert_eq!(series[1].value, 0.0);
+    assert!((series[3].value + 50.0).abs() < 1e-9);
+    assert!((series[6].value - (110.0 / 130.0 - 1.0) * 100.0).abs() < 1e-9);
+
+    let deepest = deepest.unwrap();
+    assert!((deepest.max_drawdown + 50.0).abs() < 1e-9);
+    assert_eq!(deepest.peak, points[1].date);
+    assert_eq!(deepest.trough, points[3].date);
+    assert_eq!(deepest.peak_to_trough_months, 2);
+    assert_eq!(deepest.recovery, Some(points[5].date));
+    assert_eq!(deepest.recovery_months, Some(2));
+
+    let (_, unrecovered) = drawdown(&points[..5]);
+    assert_eq!(unrecovered.unwrap().recovery, None);
+    assert_eq!(drawdown(&[]).1, None);
+}
+
+#[test]
+fn test_analytics_by_series() {
+    let hpi = |region_id: &str, year: i32, hpi: Option<f32>| Hpi {
+        region_type: RegionType::FiveZip,
+        region_id: region_id.to_string(),
+        region_name: region_id.to_string(),
+        year,
+        hpi,
+        ..Default::default()
+    };
+    let hpis = [
+        hpi("92618", 2021, Some(200.0)),
+        hpi("92602", 2020, Some(100.0)),
+        hpi("92618", 2020, Some(100.0)),
+        hpi("92602", 2021, None),
+    ];
+    assert_eq!(hpis[..3].observations()[0].date, date(2020, 1, 1));
+    let analyses = analyze_hpis(&hpis, &Transform::Yoy, 12);
+    assert_eq!(analyses.len(), 2);
+    assert_eq!(analyses[0].region_id, "92602");
+    assert!(analyses[0].analysis.points.is_empty());
+    assert_eq!(analyses[1].analysis.points[0].value, 100.0);
+
+    let t_yield = |term: Term, day: u32, yield_return: f32| TYield {
+        term,
+        date: date(2024, 1, day),
+        yield_return: Some(yield_return),
+    };
+    let t_yields = [
+        t_yield(Term::TenYear, 2, 4.0),
+        t_yield(Term::OneMonth, 2, 5.0),
+        t_yield(Term::TenYear, 3, 3.0),
+    ];
+    let analyses = analyze_t_yields(&t_yields, &Transform::Drawdown, 12);
+    assert_eq!(analyses.len(), 2);
+    assert_eq!(analyses[0].term, Term::OneMonth);
+    let ten_year = &analyses[1].analysis;
+    assert_eq!(ten_year.points.len(), 2);
+    assert!((ten_year.drawdown.as_ref().unwrap().max_drawdown + 25.0).abs() < 1e-6);
+
+    let rolling = analyze(&monthly(&[1.0, 2.0, 3.0]), &Transform::RollingMean, 3);
+    assert_eq!(rolling.window, Some(3));
+    assert_eq!(rolling.points, vec![point(date(2022, 3, 31), 2.0)]);
+}
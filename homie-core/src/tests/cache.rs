@@ -0,0 +1,62 @@
+use std::time::Duration;
+
+use crate::adapter::repository::cache::CachedPersist;
+use crate::adapter::repository::database::memory::InMemoryClient;
+use crate::domain::common::RegionType;
+use crate::domain::hpi::{Hpi, HpiQuery};
+
+fn hpi(region_id: &str, year: i32) -> Hpi {
+    Hpi {
+        region_type: RegionType::FiveZip,
+        region_id: region_id.to_string(),
+        region_name: region_id.to_string(),
+        year,
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_cache_memoizes_and_invalidates_on_write() {
+    let cache = CachedPersist::new(Box::new(InMemoryClient::new()), Duration::from_secs(60), 10);
+    let query = HpiQuery::new(RegionType::FiveZip, "92841".to_string(), 2000, 2010);
+
+    hpi("92841", 2000).create(&cache).await.unwrap();
+    assert_eq!(Hpi::read_by_query(&cache, &query).await.unwrap().len(), 1);
+
+    // Writes that bypass the cache are not seen until the entry is dropped
+    hpi("92841", 2001).create(cache.inner()).await.unwrap();
+    assert_eq!(Hpi::read_by_query(&cache, &query).await.unwrap().len(), 1);
+
+    // Writes to another region keep the entry
+    hpi("92602", 2001).create(&cache).await.unwrap();
+    assert_eq!(Hpi::read_by_query(&cache, &query).await.unwrap().len(), 1);
+
+    hpi("92841", 2002).create(&cache).await.unwrap();
+    assert_eq!(Hpi::read_by_query(&cache, &query).await.unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn test_cache_ttl_and_size_bounds() {
+    let expired = CachedPersist::new(Box::new(InMemoryClient::new()), Duration::ZERO, 10);
+    let query = HpiQuery::new(RegionType::FiveZip, "92841".to_string(), 2000, 2010);
+    assert!(Hpi::read_by_query(&expired, &query)
+        .await
+        .unwrap()
+        .is_empty());
+    hpi("92841", 2000).create(expired.inner()).await.unwrap();
+    assert_eq!(Hpi::read_by_query(&expired, &query).await.unwrap().len(), 1);
+
+    let bounded = CachedPersist::new(Box::new(InMemoryClient::new()), Duration::from_secs(60), 1);
+    let other_query = HpiQuery::new(RegionType::FiveZip, "92602".to_string(), 2000, 2010);
+    assert!(Hpi::read_by_query(&bounded, &query)
+        .await
+        .unwrap()
+        .is_empty());
+    // Caching a second query evicts the first
+    assert!(Hpi::read_by_query(&bounded, &other_query)
+        .await
+        .unwrap()
+        .is_empty());
+    hpi("92841", 2000).create(bounded.inner()).await.unwrap();
+    assert_eq!(Hpi::read_by_query(&bounded, &query).await.unwrap().len(), 1);
+}
@@ -0,0 +1,119 @@
+use chrono::NaiveDate;
+
+use super::date;
+use crate::adapter::repository::database::memory::InMemoryClient;
+use crate::domain::common::{DateInterval, RegionType};
+use crate::domain::cpi::{Cpi, CpiBase};
+use crate::domain::hpi::{Hpi, HpiQuery};
+use crate::domain::zhvi::{HomeType, Percentile, Zhvi, ZhviPrice, ZhviQuery};
+use crate::error::DomainError;
+
+/// CPIs of 100 through 2022, then 110 for January to March 2023
+async fn client_with_cpis() -> InMemoryClient {
+    let client = InMemoryClient::new();
+    let mut cpis: Vec<Cpi> = (1..=12)
+        .map(|month| Cpi {
+            date: date(2022, month, 1),
+            value: 100.0,
+        })
+        .collect();
+    cpis.extend((1..=3).map(|month| Cpi {
+        date: date(2023, month, 1),
+        value: 110.0,
+    }));
+    Cpi::create_many(&client, &cpis).await.unwrap();
+    client
+}
+
+fn zhvi_query() -> ZhviQuery {
+    ZhviQuery::new(
+        date(2022, 1, 1),
+        date(2023, 12, 31),
+        DateInterval::Day,
+        "Irvine".to_string(),
+        RegionType::City,
+        HomeType::AllHomes,
+        Percentile::Middle,
+    )
+}
+
+#[tokio::test]
+async fn test_cpi_deflates_zhvis() {
+    let client = client_with_cpis().await;
+    let zhvi = Zhvi {
+        region_name: "Irvine".to_string(),
+        region_type: RegionType::City,
+        state: None,
+        home_type: HomeType::AllHomes,
+        percentile: Percentile::Middle,
+        prices: [
+            (2022, 12, 31, 500.0),
+            (2023, 3, 31, 550.0),
+            (2023, 4, 30, 560.0),
+        ]
+        .map(|(year, month, day, value)| ZhviPrice {
+            date: date(year, month, day),
+            value,
+            filled: false,
+        })
+        .into(),
+    };
+    zhvi.create(&client).await.unwrap();
+
+    // Latest CPI month by default, April 2023 has no CPI yet
+    let query = zhvi_query().with_real(CpiBase::default());
+    let zhvis = Zhvi::read_by_query(&client, &query).await.unwrap();
+    let prices: Vec<(NaiveDate, f64)> = zhvis[0]
+        .prices()
+        .iter()
+        .map(|p| (p.date, p.value))
+        .collect();
+    assert_eq!(prices.len(), 2);
+    assert_eq!(prices[0].0, date(2022, 12, 31));
+    assert!((prices[0].1 - 550.0).abs() < 1e-9);
+    assert!((prices[1].1 - 550.0).abs() < 1e-9);
+
+    let query = zhvi_query().with_real(CpiBase::Month(date(2022, 6, 15)));
+    let zhvis = Zhvi::read_by_query(&client, &query).await.unwrap();
+    assert!((zhvis[0].prices()[1].value - 500.0).abs() < 1e-9);
+
+    let query = zhvi_query().with_real(CpiBase::Month(date(2021, 6, 1)));
+    let result = Zhvi::read_by_query(&client, &query).await;
+    assert!(matches!(result, Err(DomainError::ConvertDomain(_))));
+}
+
+#[tokio::test]
+async fn test_cpi_deflates_hpis() {
+    let client = client_with_cpis().await;
+    let hpi = |year: i32, value: f32, annual_change: f32| Hpi {
+        region_type: RegionType::County,
+        region_id: "06059".to_string(),
+        region_name: "Orange".to_string(),
+        year,
+        hpi: Some(value),
+        annual_change: Some(annual_change),
+        ..Default::default()
+    };
+    Hpi::create_many(
+        &client,
+        &[
+            hpi(2021, 180.0, 5.0),
+            hpi(2022, 200.0, 11.1),
+            hpi(2023, 231.0, 15.5),
+        ],
+    )
+    .await
+    .unwrap();
+
+    let query = HpiQuery::new(RegionType::County, "06059".to_string(), 2021, 2023)
+        .with_real(CpiBase::Month(date(2023, 1, 1)));
+    let hpis = Hpi::read_by_query(&client, &query).await.unwrap();
+    // 2021 has no CPI
+    assert_eq!(hpis.len(), 2);
+    assert!((hpis[0].hpi().unwrap() - 220.0).abs() < 1e-3);
+    // Without a 2021 CPI the 2022 change can't be netted
+    assert_eq!(hpis[0].annual_change(), None);
+    // 2023 averages a CPI of 110 over the months on hand, 10% above 2022
+    assert!((hpis[1].hpi().unwrap() - 231.0).abs() < 1e-3);
+    assert!((hpis[1].annual_change().unwrap() - 5.0).abs() < 1e-3);
+}
@@ -0,0 +1,127 @@
+use super::date;
+use crate::adapter::repository::database::memory::InMemoryClient;
+use crate::domain::affordability::{AffordabilityPoint, AffordabilityQuery, Financing};
+use crate::domain::common::RegionType;
+use crate::domain::fair_value::{fair_values, FairValue, FairValueModel, FairValueQuery};
+use crate::domain::income::Income;
+use crate::domain::mortgage::RateSource;
+use crate::domain::t_yield::{TYield, Term};
+use crate::domain::zhvi::{HomeType, Percentile, Zhvi, ZhviPrice};
+
+fn point(month: u32, price: f64, rate: f64) -> AffordabilityPoint {
+    AffordabilityPoint {
+        date: date(2023, month, 28),
+        price,
+        rate,
+        median_income: 100_000.0,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_fair_value_price_to_income() {
+    let points = [
+        point(1, 400_000.0, 6.0),
+        point(2, 500_000.0, 6.0),
+        point(3, 600_000.0, 6.0),
+    ];
+    let (fit, values) = fair_values(&points, &FairValueModel::PriceToIncome).unwrap();
+    assert_eq!(fit.intercept, 5.0);
+    assert_eq!(fit.slope, 0.0);
+    assert_eq!(fit.std_error, 1.0);
+    assert_eq!(fit.r_squared, None);
+    assert_eq!(values[0].fair_value, 500_000.0);
+    assert!((values[0].residual.unwrap() + 20.0).abs() < 1e-9);
+    assert!((values[2].residual.unwrap() - 20.0).abs() < 1e-9);
+    assert!((values[1].upper - 696_000.0).abs() < 1e-6);
+    assert!((values[1].lower - 304_000.0).abs() < 1e-6);
+
+    assert!(fair_values(&points[..1], &FairValueModel::PriceToIncome).is_none());
+}
+
+#[test]
+fn test_fair_value_rate_adjusted() {
+    // Each point of rate takes half a year of income off the ratio
+    let points: Vec<AffordabilityPoint> = [(1, 3.0), (2, 4.0), (3, 5.0), (4, 7.0)]
+        .map(|(month, rate)| point(month, (8.0 - 0.5 * rate) * 100_000.0, rate))
+        .into();
+    let (fit, values) = fair_values(&points, &FairValueModel::RateAdjusted).unwrap();
+    assert!((fit.intercept - 8.0).abs() < 1e-9);
+    assert!((fit.slope + 0.5).abs() < 1e-9);
+    assert!((fit.r_squared.unwrap() - 1.0).abs() < 1e-9);
+    assert!(values
+        .iter()
+        .all(|value| value.residual.unwrap().abs() < 1e-9));
+
+    assert!(fair_values(&points[..2], &FairValueModel::RateAdjusted).is_none());
+}
+
+#[test]
+fn test_fair_value_without_residual_below_zero() {
+    // The fit crosses zero before the highest rate
+    let points: Vec<AffordabilityPoint> =
+        [(1, 1.0, 5.0), (2, 2.0, 3.0), (3, 3.0, 1.0), (4, 4.0, 0.1)]
+            .map(|(month, rate, ratio)| point(month, ratio * 100_000.0, rate))
+            .into();
+    let (_, values) = fair_values(&points, &FairValueModel::RateAdjusted).unwrap();
+    assert!(values[0].residual.is_some());
+    assert!(values[3].fair_value <= 0.0);
+    assert_eq!(values[3].residual, None);
+    assert_eq!(values[3].lower, 0.0);
+}
+
+#[tokio::test]
+async fn test_fair_value_reads_by_query() {
+    let client = InMemoryClient::new();
+    let zhvi = Zhvi {
+        region_name: "Orange County".to_string(),
+        region_type: RegionType::County,
+        state: None,
+        home_type: HomeType::AllHomes,
+        percentile: Percentile::Middle,
+        prices: [900_000.0, 1_000_000.0, 1_100_000.0]
+            .iter()
+            .enumerate()
+            .map(|(i, value)| ZhviPrice {
+                date: date(2023, i as u32 + 1, 28),
+                value: *value,
+                filled: false,
+            })
+            .collect(),
+    };
+    zhvi.create(&client).await.unwrap();
+    let t_yield = TYield {
+        term: Term::TenYear,
+        date: date(2022, 12, 30),
+        yield_return: Some(3.9),
+    };
+    t_yield.create(&client).await.unwrap();
+    let income = Income {
+        region_type: RegionType::County,
+        region_id: "06059".to_string(),
+        region_name: "Orange County".to_string(),
+        year: 2022,
+        median_income: Some(100_000.0),
+        margin_of_error: None,
+    };
+    income.create(&client).await.unwrap();
+
+    let source = RateSource::TYield {
+        term: Term::TenYear,
+        spread: 1.7,
+    };
+    let affordability = AffordabilityQuery::new(
+        "Orange County".to_string(),
+        RegionType::County,
+        HomeType::AllHomes,
+        None,
+        date(2023, 1, 1),
+        date(2023, 12, 31),
+        Financing::new(source, 30, 20.0).unwrap(),
+    );
+    let query = FairValueQuery::new(affordability, FairValueModel::PriceToIncome);
+    let fair_values = FairValue::read_by_query(&client, &query).await.unwrap();
+    assert_eq!(fair_values.len(), 1);
+    assert_eq!(fair_values[0].fit.as_ref().unwrap().intercept, 10.0);
+    assert_eq!(fair_values[0].points[1].residual, Some(0.0));
+}
@@ -0,0 +1,199 @@
+use chrono::NaiveDate;
+
+use super::date;
+use crate::adapter::repository::database::memory::InMemoryClient;
+use crate::domain::common::{DateInterval, RegionType};
+use crate::domain::forecast::{backtest, project, ForecastModel, ForecastQuery, ZhviForecast};
+use crate::domain::zhvi::{month_after, GapFill, HomeType, Percentile, Zhvi, ZhviPrice, ZhviQuery};
+use crate::error::DomainError;
+
+/// Quarterly values on a straight line with a fixed seasonal pattern
+fn trending_quarters(quarters: usize) -> Vec<f64> {
+    let season = [5.0, -5.0, 3.0, -3.0];
+    (0..quarters)
+        .map(|t| 100.0 + 2.0 * t as f64 + season[t % 4])
+        .collect()
+}
+
+#[test]
+fn test_seasonal_naive_repeats_last_season() {
+    let values = [10.0, 20.0, 30.0, 40.0, 12.0, 22.0, 32.0, 42.0];
+    let (fit, projections) = project(&values, 4, 6, &ForecastModel::SeasonalNaive).unwrap();
+    let forecast: Vec<f64> = projections.iter().map(|p| p.value).collect();
+    assert_eq!(forecast, vec![12.0, 22.0, 32.0, 42.0, 12.0, 22.0]);
+    // Every seasonal difference is 2
+    assert!((fit.std_error - 2.0).abs() < 1e-9);
+    assert!(fit.smoothing.is_none());
+
+    // A second season out adds a second random walk error
+    let first = projections[0].upper - projections[0].value;
+    let fifth = projections[4].upper - projections[4].value;
+    assert!((first - 1.96 * 2.0).abs() < 1e-9);
+    assert!((fifth - first * 2f64.sqrt()).abs() < 1e-9);
+    assert!((projections[0].value - projections[0].lower - first).abs() < 1e-9);
+
+    assert!(project(&values[..4], 4, 6, &ForecastModel::SeasonalNaive).is_none());
+}
+
+#[test]
+fn test_holt_winters_beats_seasonal_naive_on_trend() {
+    let values = trending_quarters(40);
+    let (fit, projections) = project(&values, 4, 8, &ForecastModel::HoltWinters).unwrap();
+    assert_eq!(fit.season, 4);
+    assert_eq!(fit.observations, 40);
+    assert!(fit.smoothing.is_some());
+
+    let actual = trending_quarters(48);
+    for (h, projection) in projections.iter().enumerate() {
+        assert!((projection.value - actual[40 + h]).abs() < 2.0);
+        assert!(projection.lower < projection.value && projection.value < projection.upper);
+    }
+    let widths: Vec<f64> = projections.iter().map(|p| p.upper - p.lower).collect();
+    assert!(widths.windows(2).all(|pair| pair[0] <= pair[1]));
+
+    let holt_winters = backtest(&values, 4, 4, &ForecastModel::HoltWinters).unwrap();
+    let naive = backtest(&values, 4, 4, &ForecastModel::SeasonalNaive).unwrap();
+    // Twelve origins, each with up to four steps observed after it
+    assert_eq!(naive.errors, 4 * 9 + 3 + 2 + 1);
+    // The naive forecast trails the trend by 2 a quarter for a year
+    assert!((naive.mae - 8.0).abs() < 1e-9);
+    assert!((naive.rmse - 8.0).abs() < 1e-9);
+    assert!(holt_winters.mae < naive.mae);
+    assert!(holt_winters.mape < naive.mape);
+
+    // Annual series have no season to smooth
+    let (fit, _) = project(&[1.0, 2.0, 3.0, 4.0], 1, 2, &ForecastModel::HoltWinters).unwrap();
+    assert_eq!(fit.smoothing.unwrap().gamma, 0.0);
+}
+
+#[tokio::test]
+async fn test_zhvi_forecast_flags_forecast_points() {
+    let client = InMemoryClient::new();
+    let zhvi = Zhvi {
+        region_name: "Irvine".to_string(),
+        region_type: RegionType::City,
+        state: None,
+        home_type: HomeType::AllHomes,
+        percentile: Percentile::Middle,
+        // Month ends from January 2023 through February 2024
+        prices: (0..14)
+            .map(|i| ZhviPrice {
+                date: month_after(date(2023, 1, 31), i).unwrap(),
+                value: 100.0 + i as f64,
+                filled: false,
+            })
+            .collect(),
+    };
+    zhvi.create(&client).await.unwrap();
+    let zhvi_query = |date_interval| {
+        ZhviQuery::new(
+            date(2023, 1, 1),
+            date(2024, 12, 31),
+            date_interval,
+            "Irvine".to_string(),
+            RegionType::City,
+            HomeType::AllHomes,
+            Percentile::Middle,
+        )
+    };
+
+    let query = ForecastQuery::new(
+        zhvi_query(DateInterval::Month),
+        3,
+        ForecastModel::SeasonalNaive,
+    );
+    let forecasts = ZhviForecast::read_by_query(&client, &query).await.unwrap();
+    let points = &forecasts[0].points;
+    assert_eq!(points.len(), 17);
+    assert!(points[..14]
+        .iter()
+        .all(|p| !p.forecast && p.lower.is_none()));
+    let forecast: Vec<(NaiveDate, f64)> = points[14..]
+        .iter()
+        .inspect(|p| assert!(p.forecast && p.lower.is_some() && p.upper.is_some()))
+        .map(|p| (p.date, p.value))
+        .collect();
+    // Monthly resampling dates each price at the start of its month
+    assert_eq!(
+        forecast,
+        vec![
+            (date(2024, 3, 1), 102.0),
+            (date(2024, 4, 1), 103.0),
+            (date(2024, 5, 1), 104.0),
+        ]
+    );
+    let json = serde_json::to_string(&points[0]).unwrap();
+    assert!(json.contains("\"forecast\":false") && !json.contains("lower"));
+
+    // Too short a history for Holt-Winters leaves the observed prices only
+    let query = ForecastQuery::new(
+        zhvi_query(DateInterval::Month),
+        3,
+        ForecastModel::HoltWinters,
+    );
+    let forecasts = ZhviForecast::read_by_query(&client, &query).await.unwrap();
+    assert!(forecasts[0].fit.is_none());
+    assert_eq!(forecasts[0].points.len(), 14);
+    assert_eq!(
+        forecasts[0].backtests[0].model,
+        ForecastModel::SeasonalNaive
+    );
+
+    let query = ForecastQuery::new(zhvi_query(DateInterval::Week), 3, ForecastModel::default());
+    assert!(matches!(
+        ZhviForecast::read_by_query(&client, &query).await,
+        Err(DomainError::ConvertDomain(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_zhvi_forecast_skips_gapped_series() {
+    let client = InMemoryClient::new();
+    let zhvi = Zhvi {
+        region_name: "Irvine".to_string(),
+        region_type: RegionType::City,
+        state: None,
+        home_type: HomeType::AllHomes,
+        percentile: Percentile::Middle,
+        // Month ends from January 2021 through February 2024 without June 2022
+        prices: (0..38)
+            .filter(|i| *i != 17)
+            .map(|i| ZhviPrice {
+                date: month_after(date(2021, 1, 31), i).unwrap(),
+                value: 100.0 + i as f64,
+                filled: false,
+            })
+            .collect(),
+    };
+    zhvi.create(&client).await.unwrap();
+    let zhvi_query = ZhviQuery::new(
+        date(2021, 1, 1),
+        date(2024, 12, 31),
+        DateInterval::Month,
+        "Irvine".to_string(),
+        RegionType::City,
+        HomeType::AllHomes,
+        Percentile::Middle,
+    );
+
+    let query = ForecastQuery::new(zhvi_query.clone(), 3, ForecastModel::HoltWinters);
+    let forecasts = ZhviForecast::read_by_query(&client, &query).await.unwrap();
+    assert!(forecasts[0].fit.is_none());
+    assert!(forecasts[0].backtests.is_empty());
+    assert_eq!(forecasts[0].points.len(), 37);
+    assert!(forecasts[0].points.iter().all(|p| !p.forecast));
+
+    // Filling the missing month leaves an evenly spaced series to fit
+    let query = ForecastQuery::new(
+        zhvi_query.with_gap_fill(GapFill::Linear),
+        3,
+        ForecastModel::HoltWinters,
+    );
+    let forecasts = ZhviForecast::read_by_query(&client, &query).await.unwrap();
+    assert_eq!(forecasts[0].fit.as_ref().unwrap().observations, 38);
+    assert_eq!(forecasts[0].backtests.len(), 2);
+    let points = &forecasts[0].points;
+    assert_eq!(points.len(), 41);
+    assert!(points[17].filled && !points[17].forecast);
+    assert!(points[38..].iter().all(|p| p.forecast));
+}
@@ -0,0 +1,102 @@
+use chrono::NaiveDate;
+
+use super::date;
+use crate::adapter::repository::database::memory::InMemoryClient;
+use crate::domain::common::{DateInterval, RegionType};
+use crate::domain::resample::Resampling;
+use crate::domain::zhvi::{GapFill, HomeType, Percentile, Zhvi, ZhviPrice, ZhviQuery};
+
+/// Month-end prices for January, April and May 2024, missing February and
+/// March
+async fn client_with_gap() -> InMemoryClient {
+    let client = InMemoryClient::new();
+    let zhvi = Zhvi {
+        region_name: "Irvine".to_string(),
+        region_type: RegionType::City,
+        state: None,
+        home_type: HomeType::AllHomes,
+        percentile: Percentile::Middle,
+        prices: [(5, 31, 500.0), (1, 31, 100.0), (4, 30, 400.0)]
+            .map(|(month, day, value)| ZhviPrice {
+                date: date(2024, month, day),
+                value,
+                filled: false,
+            })
+            .into(),
+    };
+    zhvi.create(&client).await.unwrap();
+    client
+}
+
+fn query(date_interval: DateInterval, gap_fill: GapFill) -> ZhviQuery {
+    ZhviQuery::new(
+        date(2023, 1, 1),
+        date(2024, 12, 31),
+        date_interval,
+        "Irvine".to_string(),
+        RegionType::City,
+        HomeType::AllHomes,
+        Percentile::Middle,
+    )
+    .with_gap_fill(gap_fill)
+}
+
+fn prices(zhvis: &[Zhvi]) -> Vec<(NaiveDate, f64, bool)> {
+    zhvis[0]
+        .prices()
+        .iter()
+        .map(|price| (price.date, price.value, price.filled))
+        .collect()
+}
+
+#[tokio::test]
+async fn test_gap_fill_by_month() {
+    let client = client_with_gap().await;
+
+    let zhvis = Zhvi::read_by_query(&client, &query(DateInterval::Day, GapFill::None))
+        .await
+        .unwrap();
+    assert_eq!(prices(&zhvis).len(), 3);
+
+    let zhvis = Zhvi::read_by_query(&client, &query(DateInterval::Day, GapFill::Forward))
+        .await
+        .unwrap();
+    assert_eq!(
+        prices(&zhvis),
+        vec![
+            (date(2024, 1, 31), 100.0, false),
+            (date(2024, 2, 29), 100.0, true),
+            (date(2024, 3, 31), 100.0, true),
+            (date(2024, 4, 30), 400.0, false),
+            (date(2024, 5, 31), 500.0, false),
+        ]
+    );
+
+    let zhvis = Zhvi::read_by_query(&client, &query(DateInterval::Day, GapFill::Linear))
+        .await
+        .unwrap();
+    let linear = prices(&zhvis);
+    // 29 of the 90 days from January 31st to April 30th
+    assert!((linear[1].1 - (100.0 + 300.0 * 29.0 / 90.0)).abs() < 1e-9);
+    assert!((linear[2].1 - (100.0 + 300.0 * 60.0 / 90.0)).abs() < 1e-9);
+    assert!(linear[2].2);
+}
+
+#[tokio::test]
+async fn test_gap_fill_flags_resampled_intervals() {
+    let client = client_with_gap().await;
+    let query = query(DateInterval::Quarter, GapFill::Forward).with_resampling(Resampling::Mean);
+    let zhvis = Zhvi::read_by_query(&client, &query).await.unwrap();
+    assert_eq!(
+        prices(&zhvis),
+        vec![
+            (date(2024, 1, 1), 100.0, true),
+            (date(2024, 4, 1), 450.0, false),
+        ]
+    );
+
+    let json = serde_json::to_string(&zhvis[0].prices()[1]).unwrap();
+    assert!(!json.contains("filled"));
+    assert_eq!(GapFill::try_from("ffill").unwrap(), GapFill::Forward);
+    assert!(GapFill::try_from("spline").is_err());
+}
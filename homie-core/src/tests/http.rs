@@ -0,0 +1,81 @@
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+use super::date;
+use crate::adapter::repository::database::http::{HttpClient, ReqwestTransport};
+use crate::adapter::repository::database::memory::InMemoryClient;
+use crate::domain::common::{DateInterval, RegionType};
+use crate::domain::zhvi::{HomeType, Percentile, Zhvi, ZhviQuery};
+use crate::error::DomainError;
+
+const ZILLOW_BODY: &str = r#"{
+    "bundle": [
+        {"regionName": "Irvine", "regionType": "city", "homeType": "allhomes", "percentile": "middle", "date": "2023-02-28", "dataValue": 1200000.0},
+        {"regionName": "Irvine", "regionType": "city", "homeType": "allhomes", "percentile": "middle", "date": "2023-01-31", "dataValue": 1100000.0},
+        {"regionName": "Irvine", "regionType": "city", "homeType": "allhomes", "percentile": "middle", "date": "2023-03-31", "dataValue": null}
+    ]
+}"#;
+
+/// Serves a single canned response and returns the request line it received
+async fn fake_zillow_server(body: &'static str) -> (String, tokio::task::JoinHandle<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    let handle = tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut buf = vec![0; 4096];
+        let n = socket.read(&mut buf).await.unwrap();
+        let request = String::from_utf8_lossy(&buf[..n]).to_string();
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: \
+             {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        socket.write_all(response.as_bytes()).await.unwrap();
+        request.lines().next().unwrap_or_default().to_string()
+    });
+    (base_url, handle)
+}
+
+fn http_client(base_url: &str) -> HttpClient {
+    HttpClient::with_transport(
+        base_url,
+        Some("secret"),
+        Box::new(ReqwestTransport::new()),
+        Box::new(InMemoryClient::new()),
+    )
+}
+
+#[tokio::test]
+async fn test_http_read_zhvis_from_fake_server() {
+    let (base_url, server) = fake_zillow_server(ZILLOW_BODY).await;
+    let client = http_client(&base_url);
+
+    let query = ZhviQuery::new(
+        date(2023, 1, 1),
+        date(2023, 12, 31),
+        DateInterval::Month,
+        "Irvine".to_string(),
+        RegionType::City,
+        HomeType::AllHomes,
+        Percentile::Middle,
+    );
+    let zhvis = Zhvi::read_by_query(&client, &query).await.unwrap();
+
+    let request_line = server.await.unwrap();
+    assert!(request_line.starts_with("GET /zhvi?"));
+    assert!(request_line.contains("regionName=Irvine"));
+    assert!(request_line.contains("access_token=secret"));
+
+    assert_eq!(zhvis.len(), 1);
+    assert_eq!(zhvis[0].region_name(), "Irvine");
+    let values: Vec<f64> = zhvis[0].prices().iter().map(|p| p.value).collect();
+    assert_eq!(values, vec![1100000.0, 1200000.0]);
+}
+
+#[tokio::test]
+async fn test_http_writes_are_read_only() {
+    let client = http_client("http://127.0.0.1:1");
+    let result = Zhvi::default().create(&client).await;
+    assert!(matches!(result, Err(DomainError::ReadOnly(_))));
+}
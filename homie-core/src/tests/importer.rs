@@ -0,0 +1,470 @@
+use std::path::PathBuf;
+
+use chrono::NaiveDate;
+
+use crate::adapter::importer::ImportReport;
+use crate::domain::common::RegionType;
+use crate::domain::cpi::{read_bls_cpis, CpiConfig};
+use crate::domain::hpi::{read_fhfa_hpis, HpiConfig};
+use crate::domain::income::{parse_acs_filename, read_census_incomes, IncomeConfig};
+use crate::domain::mortgage_rate::{read_freddie_mac_rates, MortgageRateConfig, MortgageTerm};
+use crate::domain::region::{read_huduser_regions, RegionConfig};
+use crate::domain::t_yield::{read_fed_yields, TYieldConfig, Term};
+use crate::domain::zhvi::{read_zillow_zhvis, HomeType, Percentile, ZhviConfig};
+
+fn write_csv(name: &str, contents: &str) -> String {
+    let path: PathBuf = std::env::temp_dir().join(format!("homie_{}_{}", std::process::id(), name));
+    std::fs::write(&path, contents).unwrap();
+    path.to_string_lossy().to_string()
+}
+
+// Lays out downloads the way `local/datasets` does, e.g. `zillow-zhvi`
+fn write_dir(name: &str, files: &[(&str, &str)]) -> String {
+    let dir = std::env::temp_dir().join(format!("homie_{}_{}", std::process::id(), name));
+    for (file, contents) in files {
+        let path = dir.join(file);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    }
+    dir.to_string_lossy().to_string()
+}
+
+#[test]
+fn test_importer_maps_columns_by_header() {
+    let hpi_path = write_csv(
+        "county_hpi.csv",
+        "Year,County,FIPS code,State,HPI with 2000 base,HPI with 1990 base,HPI,Annual Change \
+         (%)\n2020,Orange,06059,CA,300.5,290.0,500.0,.\n",
+    );
+    let hpi_config = HpiConfig::new(None, None, Some(hpi_path), None, None, None);
+    let hpis = read_fhfa_hpis(&hpi_config, &mut ImportReport::default()).unwrap();
+    let hpi = &hpis.county_hpis()[0];
+    assert_eq!(hpi.region_id(), "06059");
+    assert_eq!(hpi.region_name(), "Orange");
+    assert_eq!(hpi.state(), Some("CA"));
+    assert_eq!(hpi.year(), 2020);
+    assert_eq!(hpi.hpi(), Some(500.0));
+    assert_eq!(hpi.hpi_2000_base(), Some(300.5));
+    assert_eq!(hpi.annual_change(), None);
+
+    // City files carry one fewer metadata column than zip and county files
+    let zhvi_dir = write_dir(
+        "header_zhvi",
+        &[
+            (
+                "City_zhvi_uc_sfrcondo_tier_0.33_0.67_sm_sa_month.csv",
+                "RegionID,SizeRank,RegionName,RegionType,StateName,State,Metro,CountyName,\
+                 2000-01-31,2000-02-29\n1,1,Irvine,city,CA,CA,LA,Orange,100.0,200.0\n",
+            ),
+            (
+                "Zip_zhvi_uc_sfrcondo_tier_0.33_0.67_sm_sa_month.csv",
+                "RegionID,SizeRank,RegionName,RegionType,StateName,State,City,Metro,CountyName,\
+                 2000-01-31,2000-02-29\n1,1,92618,zip,CA,CA,Irvine,LA,Orange,300.0,400.0\n",
+            ),
+        ],
+    );
+    let zhvi_config = ZhviConfig::new(Some(zhvi_dir));
+    let zhvis = read_zillow_zhvis(&zhvi_config, &mut ImportReport::default()).unwrap();
+    // Only city and county names repeat across states
+    let states: Vec<Option<&str>> = zhvis.all_homes_zhvis().iter().map(|z| z.state()).collect();
+    assert_eq!(states, vec![Some("CA"), None]);
+    for zhvi in zhvis.all_homes_zhvis() {
+        let dates: Vec<NaiveDate> = zhvi.prices().iter().map(|p| p.date).collect();
+        assert_eq!(
+            dates,
+            vec![
+                NaiveDate::from_ymd_opt(2000, 1, 31).unwrap(),
+                NaiveDate::from_ymd_opt(2000, 2, 29).unwrap(),
+            ]
+        );
+    }
+}
+
+#[test]
+fn test_importer_reports_missing_column() {
+    let hpi_path = write_csv(
+        "renamed_hpi.csv",
+        "Five-Digit ZIP Code,Year,Annual Change (%),HPI,HPI 1990 base,HPI with 2000 \
+         base\n92618,2020,1.0,100.0,90.0,80.0\n",
+    );
+    let hpi_config = HpiConfig::new(None, Some(hpi_path), None, None, None, None);
+    let error = read_fhfa_hpis(&hpi_config, &mut ImportReport::default())
+        .unwrap_err()
+        .to_string();
+    assert!(
+        error.contains("missing column 'HPI with 1990 base'"),
+        "{}",
+        error
+    );
+}
+
+#[test]
+fn test_importer_reports_rejected_rows() {
+    let hpi_path = write_csv(
+        "invalid_hpi.csv",
+        "Three-Digit ZIP Code,Year,Annual Change (%),HPI,HPI with 1990 base,HPI with 2000 \
+         base\n900,1975,.,100.00,15.35,14.21\n900,19x6,18.53,118.53,18.19,16.85\n900,1977,18.53\\
+         n900,1978,abc,140.00,21.00,19.00\n",
+    );
+    let hpi_config = HpiConfig::new(Some(hpi_path.clone()), None, None, None, None, None);
+    let mut report = ImportReport::default();
+    let hpis = read_fhfa_hpis(&hpi_config, &mut report).unwrap();
+
+    assert_eq!(hpis.three_zip_hpis().len(), 1);
+    assert_eq!(report.rows_read(), 4);
+    assert_eq!(report.rows_rejected(), 3);
+    let errors = report.errors();
+    assert_eq!(errors[0].file(), hpi_path);
+    assert_eq!((errors[0].line(), errors[0].column()), (3, Some("Year")));
+    assert_eq!((errors[1].line(), errors[1].column()), (4, None));
+    assert_eq!(
+        (errors[2].line(), errors[2].column()),
+        (5, Some("Annual Change (%)"))
+    );
+
+    let zhvi_dir = write_dir(
+        "invalid_zhvi",
+        &[(
+            "City_zhvi_uc_sfrcondo_tier_0.33_0.67_sm_sa_month.csv",
+            "RegionID,SizeRank,RegionName,RegionType,StateName,State,Metro,CountyName,2000-01-31,\
+             2000-02-29\n1,1,Irvine,city,CA,CA,LA,Orange,,200.0\n2,2,Tustin,city,CA,CA,LA,Orange,\
+             n/a,300.0\n",
+        )],
+    );
+    let zhvi_config = ZhviConfig::new(Some(zhvi_dir));
+    let mut report = ImportReport::default();
+    let zhvis = read_zillow_zhvis(&zhvi_config, &mut report).unwrap();
+    let zhvis = zhvis.all_homes_zhvis();
+    assert_eq!(zhvis.len(), 1);
+    assert_eq!(zhvis[0].prices().len(), 1);
+    assert_eq!(report.errors()[0].column(), Some("2000-01-31"));
+}
+
+#[test]
+fn test_importer_reports_duplicate_zhvi_regions() {
+    let zhvi_dir = write_dir(
+        "duplicate_zhvi",
+        &[(
+            "County_zhvi_uc_sfrcondo_tier_0.33_0.67_sm_sa_month.csv",
+            "RegionID,SizeRank,RegionName,RegionType,StateName,State,Metro,StateCodeFIPS,\
+             MunicipalCodeFIPS,2000-01-31\n1,1,Orange \
+             County,county,CA,CA,LA,06,059,100.0\n2,2,Orange \
+             County,county,FL,FL,Orlando,12,095,200.0\n3,3,Orange \
+             County,county,CA,CA,LA,06,059,300.0\n",
+        )],
+    );
+    let zhvi_config = ZhviConfig::new(Some(zhvi_dir));
+    let mut report = ImportReport::default();
+    let zhvis = read_zillow_zhvis(&zhvi_config, &mut report).unwrap();
+
+    // Same-named counties in two states are kept apart, a repeat is rejected
+    let zhvis: Vec<(Option<&str>, f64)> = zhvis
+        .all_homes_zhvis()
+        .iter()
+        .map(|zhvi| (zhvi.state(), zhvi.prices()[0].value))
+        .collect();
+    assert_eq!(zhvis, vec![(Some("CA"), 100.0), (Some("FL"), 200.0)]);
+    assert_eq!(report.rows_rejected(), 1);
+    let error = &report.errors()[0];
+    assert_eq!((error.line(), error.column()), (4, Some("RegionName")));
+    assert!(error
+        .message()
+        .contains("duplicate region 'Orange County' in CA"));
+}
+
+#[test]
+fn test_importer_discovers_zillow_files() {
+    let csv = "RegionID,SizeRank,RegionName,RegionType,StateName,State,Metro,CountyName,\
+               2000-01-31\n1,1,Irvine,city,CA,CA,LA,Orange,100.0\n";
+    let zhvi_dir = write_dir(
+        "discover_zhvi",
+        &[
+            (
+                "condo-coops/City_zhvi_uc_condo_tier_0.33_0.67_sm_sa_month.csv",
+                csv,
+            ),
+            (
+                "all-homes/top-tier/City_zhvi_uc_sfrcondo_tier_0.67_1.0_sm_sa_month.csv",
+                csv,
+            ),
+            ("all-homes/notes.txt", "not a dataset"),
+        ],
+    );
+    let zhvi_config = ZhviConfig::new(Some(zhvi_dir));
+    let mut report = ImportReport::default();
+    let zhvis = read_zillow_zhvis(&zhvi_config, &mut report).unwrap();
+    assert_eq!(zhvis.condo_coops_zhvis().len(), 1);
+    assert_eq!(zhvis.all_homes_zhvis()[0].percentile, Percentile::Top);
+    assert!(zhvis.single_family_homes_zhvis().is_empty());
+
+    assert_eq!(report.rows_rejected(), 0);
+    let error = &report.errors()[0];
+    assert!(error.file().ends_with("notes.txt"));
+    assert_eq!(error.line(), 0);
+}
+
+#[test]
+fn test_importer_reads_state_msa_and_national_hpis() {
+    let columns = "Year,Annual Change (%),HPI,HPI with 1990 base,HPI with 2000 base";
+    let state_path = write_csv(
+        "state_hpi.csv",
+        &format!(
+            "State,Abbreviation,FIPS,{columns}\nCalifornia,CA,06,2020,5.1,500.0,200.0,150.0\n"
+        ),
+    );
+    let msa_path = write_csv(
+        "msa_hpi.csv",
+        &format!("CBSA,Name,{columns}\n31080,\"Los Angeles, CA\",2020,4.0,400.0,.,.\n"),
+    );
+    let national_path = write_csv(
+        "national_hpi.csv",
+        &format!("{columns}\n2020,5.0,300.0,200.0,180.0\n"),
+    );
+    let hpi_config = HpiConfig::new(
+        None,
+        None,
+        None,
+        Some(state_path),
+        Some(msa_path),
+        Some(national_path),
+    );
+    let hpis = read_fhfa_hpis(&hpi_config, &mut ImportReport::default()).unwrap();
+
+    let state = &hpis.state_hpis()[0];
+    assert_eq!(state.region_type(), &RegionType::State);
+    assert_eq!(state.region_id(), "CA");
+    assert_eq!(state.region_name(), "California");
+    assert_eq!(state.state(), Some("CA"));
+    let msa = &hpis.msa_hpis()[0];
+    assert_eq!(msa.region_id(), "31080");
+    assert_eq!(msa.region_name(), "Los Angeles, CA");
+    assert_eq!(msa.hpi_1990_base(), None);
+    let national = &hpis.national_hpis()[0];
+    assert_eq!(national.region_type(), &RegionType::National);
+    assert_eq!(national.region_id(), "USA");
+    assert_eq!(national.hpi(), Some(300.0));
+}
+
+#[test]
+fn test_importer_reads_fed_h15_downloads() {
+    let daily_path = write_csv(
+        "h15_daily.csv",
+        concat!(
+            "\"Series Description\",\"1-month\",\"10-year\",\"30-year\"\n",
+            "\"Unit:\",\"Percent:_Per_Year\",\"Percent:_Per_Year\",\"Percent:_Per_Year\"\n",
+            "\"Multiplier:\",\"1\",\"1\",\"1\"\n",
+            "\"Currency:\",\"NA\",\"NA\",\"NA\"\n",
+            "\"Unique Identifier: \",\"H15/H15/RIFLGFCM01_N.B\",\"H15/H15/RIFLGFCY10_N.B\",",
+            "\"H15/H15/RIFLGFCY30_N.B\"\n",
+            "\"Time Period\",\"RIFLGFCM01_N.B\",\"RIFLGFCY10_N.B\",\"RIFLGFCY30_N.B\"\n",
+            "2024-01-01,ND,ND,ND\n",
+            "2024-01-02,5.55,3.95,4.08\n",
+            "2024-01-03,5.54,x,4.05\n",
+        ),
+    );
+    let mut report = ImportReport::default();
+    let t_yield_config = TYieldConfig::new(Some(daily_path));
+    let t_yields = read_fed_yields(&t_yield_config, &mut report).unwrap();
+    let t_yields = t_yields.t_yields();
+    assert_eq!(t_yields.len(), 3);
+    assert_eq!(t_yields[0].term(), &Term::OneMonth);
+    assert_eq!(t_yields[1].term(), &Term::TenYear);
+    assert_eq!(t_yields[2].term(), &Term::ThirtyYear);
+    assert_eq!(
+        t_yields[2].date(),
+        &NaiveDate::from_ymd_opt(2024, 1, 2).unwrap()
+    );
+    assert_eq!(t_yields[2].yield_return(), &Some(4.08));
+    assert_eq!((report.rows_read(), report.rows_rejected()), (3, 1));
+    assert_eq!(report.errors()[0].column(), Some("RIFLGFCY10_N.B"));
+
+    // The two-column monthly extract has no metadata rows
+    let monthly_path = write_csv(
+        "h15_monthly.csv",
+        "Time Period,RIFLGFCM03_N.M,RIFLGFCY02_N.M\n2024-02,5.40,4.64\n",
+    );
+    let t_yield_config = TYieldConfig::new(Some(monthly_path));
+    let t_yields = read_fed_yields(&t_yield_config, &mut ImportReport::default()).unwrap();
+    let terms: Vec<&Term> = t_yields.t_yields().iter().map(|t| t.term()).collect();
+    assert_eq!(terms, vec![&Term::ThreeMonth, &Term::TwoYear]);
+    assert_eq!(
+        t_yields.t_yields()[0].date(),
+        &NaiveDate::from_ymd_opt(2024, 2, 1).unwrap()
+    );
+}
+
+#[test]
+fn test_importer_reads_bls_cpi_u() {
+    let cpi_path = write_csv(
+        "cu.data.0.Current",
+        concat!(
+            "series_id        \tyear\tperiod\t       value\tfootnote_codes\n",
+            "CUSR0000SA0      \t2024\tM01\t     309.685\t\n",
+            "CUUR0000SA0      \t2024\tM01\t     308.417\t\n",
+            "CUUR0000SA0      \t2024\tM02\t     310.326\t\n",
+            "CUUR0000SA0      \t2024\tM13\t     313.689\t\n",
+            "CUUR0000SA0      \t2024\tS01\t     311.578\t\n",
+            "CUUR0000SA0      \t2025\tM10\t           -\t\n",
+            "CUUR0000SA0      \t2025\tM11\t        abc\t\n",
+        ),
+    );
+    let mut report = ImportReport::default();
+    let cpi_config = CpiConfig::new(Some(cpi_path.clone()), None);
+    let cpis = read_bls_cpis(&cpi_config, &mut report).unwrap();
+    let cpis = cpis.cpis();
+
+    // Only monthly values of the not seasonally adjusted series
+    assert_eq!(cpis.len(), 2);
+    assert_eq!(
+        cpis[0].date(),
+        &NaiveDate::from_ymd_opt(2024, 1, 1).unwrap()
+    );
+    assert_eq!(cpis[0].value(), 308.417);
+    assert_eq!(cpis[1].value(), 310.326);
+    assert_eq!(report.rows_rejected(), 1);
+    assert_eq!(report.errors()[0].column(), Some("value"));
+
+    let mut report = ImportReport::default();
+    let cpi_config = CpiConfig::new(Some(cpi_path), Some("CUSR0000SA0".to_string()));
+    let cpis = read_bls_cpis(&cpi_config, &mut report).unwrap();
+    assert_eq!(cpis.cpis().len(), 1);
+    assert_eq!(cpis.cpis()[0].value(), 309.685);
+}
+
+#[test]
+fn test_importer_reads_freddie_mac_pmms() {
+    let pmms_path = write_csv(
+        "pmms.csv",
+        concat!(
+            "date,pmms30,pmms30p,pmms15,pmms15p,pmms51,pmms51p,pmms51m,pmms51spread\n",
+            "4/2/1971,7.33,,,,,,,\n",
+            "1/4/2024,6.62,0.6,5.89,0.6,,,,\n",
+            "1/11/2024,6.66,0.7,abc,0.6,,,,\n",
+        ),
+    );
+    let mut report = ImportReport::default();
+    let mortgage_rate_config = MortgageRateConfig::new(Some(pmms_path));
+    let mortgage_rates = read_freddie_mac_rates(&mortgage_rate_config, &mut report).unwrap();
+    let mortgage_rates = mortgage_rates.mortgage_rates();
+
+    // 15-year rates start in 1991, and the row with an invalid rate is rejected
+    assert_eq!(mortgage_rates.len(), 3);
+    assert_eq!(mortgage_rates[0].term(), &MortgageTerm::ThirtyYear);
+    assert_eq!(
+        mortgage_rates[0].date(),
+        &NaiveDate::from_ymd_opt(1971, 4, 2).unwrap()
+    );
+    assert_eq!(mortgage_rates[0].points(), &None);
+    assert_eq!(mortgage_rates[2].term(), &MortgageTerm::FifteenYear);
+    assert_eq!(mortgage_rates[2].rate(), &Some(5.89));
+    assert_eq!((report.rows_read(), report.rows_rejected()), (3, 1));
+    assert_eq!(report.errors()[0].column(), Some("pmms15"));
+}
+
+#[test]
+fn test_importer_reads_census_acs_incomes() {
+    let data = concat!(
+        "\"GEO_ID\",\"NAME\",\"B19013_001E\",\"B19013_001M\",\n",
+        "\"Geography\",\"Geographic Area Name\",\"Estimate!!Median household income\",\"Margin of \
+         Error!!Median household income\",\n",
+        "\"0500000US06059\",\"Orange County, California\",\"109361\",\"1025\",\n",
+        "\"1600000US0636770\",\"Irvine city, California\",\"250,000+\",\"***\",\n",
+        "\"8600000US92618\",\"ZCTA5 92618\",\"-\",\"**\",\n",
+        "\"3100000US31080\",\"Los Angeles-Long Beach-Anaheim, CA Metro Area\",\"abc\",\"900\",\n",
+        "\"1400000US06059001101\",\"Census Tract 11.01\",\"90000\",\"100\",\n",
+    );
+    let acs_dir = write_dir(
+        "acs_incomes",
+        &[
+            ("ACSDT5Y2022.B19013-Data.csv", data),
+            (
+                "ACSDT5Y2022.B19013-Column-Metadata.csv",
+                "GEO_ID,Geography\n",
+            ),
+            ("ACSDT5Y2022.B19001-Data.csv", data),
+        ],
+    );
+    let mut report = ImportReport::default();
+    let income_config = IncomeConfig::new(Some(acs_dir));
+    let incomes = read_census_incomes(&income_config, &mut report).unwrap();
+    let incomes = incomes.incomes();
+
+    assert_eq!(incomes.len(), 3);
+    assert_eq!(incomes[0].region_type(), &RegionType::County);
+    assert_eq!(incomes[0].region_id(), "06059");
+    assert_eq!(incomes[0].region_name(), "Orange County");
+    assert_eq!(incomes[0].year(), 2022);
+    assert_eq!(incomes[0].median_income(), Some(109_361.0));
+    assert_eq!(incomes[1].region_type(), &RegionType::City);
+    assert_eq!(incomes[1].region_name(), "Irvine");
+    // Top coded estimates keep their bound, suppressed margins are missing
+    assert_eq!(incomes[1].median_income(), Some(250_000.0));
+    assert_eq!(incomes[1].margin_of_error(), None);
+    assert_eq!(incomes[2].region_type(), &RegionType::FiveZip);
+    assert_eq!(incomes[2].median_income(), None);
+
+    // The invalid estimate and the tract are rejected, the other table is flagged
+    assert_eq!(report.rows_rejected(), 2);
+    assert_eq!(report.errors()[0].line(), 0);
+    assert_eq!(report.errors()[1].column(), Some("B19013_001E"));
+    assert_eq!(report.errors()[2].column(), Some("GEO_ID"));
+
+    assert_eq!(
+        parse_acs_filename("ACSDT1Y2019.B19013-Data.csv"),
+        Some(2019)
+    );
+    assert_eq!(parse_acs_filename("ACSST5Y2022.S1903-Data.csv"), None);
+}
+
+#[test]
+fn test_importer_reads_regions_by_state() {
+    let zip_county_path = write_csv(
+        "zip_county.csv",
+        concat!(
+            "ZIP,COUNTY,USPS_ZIP_PREF_CITY,USPS_ZIP_PREF_STATE,RES_RATIO,BUS_RATIO,OTH_RATIO,\
+             TOT_RATIO\n",
+            "92602,06059,IRVINE,CA,1.0,1.0,1.0,1.0\n",
+            "89439,32031,VERDI,NV,0.2,0.2,0.2,0.2\n",
+            "89439,06061,VERDI,NV,0.8,0.8,0.8,0.8\n",
+            "10001,36061,NEW YORK,NY,1.0,1.0,1.0,1.0\n",
+        ),
+    );
+
+    let states = Some(vec!["CA".to_string(), "NV".to_string()]);
+    let region_config = RegionConfig::new(None, Some(zip_county_path.clone()), states);
+    let regions = read_huduser_regions(&region_config, &mut ImportReport::default()).unwrap();
+    let regions = regions.regions();
+    assert_eq!(regions.len(), 3);
+    assert_eq!(regions[0].county_fips(), "06059");
+    // A zip spanning counties is kept once per county with its share of addresses
+    assert_eq!(regions[2].city(), "verdi");
+    assert_eq!(regions[2].state(), "NV");
+    assert_eq!(regions[2].county_fips(), "06061");
+    assert_eq!(regions[2].res_ratio(), 0.8);
+
+    let region_config = RegionConfig::new(None, Some(zip_county_path), None);
+    let regions = read_huduser_regions(&region_config, &mut ImportReport::default()).unwrap();
+    assert_eq!(regions.regions().len(), 4);
+}
+
+#[test]
+fn test_importer_parses_zillow_filenames() {
+    use crate::domain::zhvi::parse_zhvi_filename;
+
+    assert_eq!(
+        parse_zhvi_filename("City_zhvi_uc_sfrcondo_tier_0.0_0.33_sm_sa_month.csv"),
+        Some((HomeType::AllHomes, RegionType::City, Percentile::Bottom))
+    );
+    assert_eq!(
+        parse_zhvi_filename("Zip_zhvi_uc_sfr_tier_0.33_0.67_sm_sa_month.csv"),
+        Some((
+            HomeType::SingleFamilyHomes,
+            RegionType::FiveZip,
+            Percentile::Middle
+        ))
+    );
+    assert_eq!(
+        parse_zhvi_filename("City_zhvi_bdrmcnt_2_uc_sfrcondo_tier_0.33_0.67_sm_sa_month.csv"),
+        None
+    );
+}
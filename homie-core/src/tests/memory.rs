@@ -0,0 +1,377 @@
+use super::date;
+use crate::adapter::repository::database::memory::InMemoryClient;
+use crate::domain::common::{DateInterval, RegionType};
+use crate::domain::hpi::{Hpi, HpiQuery};
+use crate::domain::mortgage_rate::{MortgageRate, MortgageRateQuery, MortgageTerm};
+use crate::domain::region::{Region, RegionPersist, RegionQuery};
+use crate::domain::resample::Resampling;
+use crate::domain::t_yield::{TYield, TYieldQuery, Term};
+use crate::domain::zhvi::{HomeType, Percentile, Zhvi, ZhviPrice, ZhviQuery};
+
+fn irvine_zhvi() -> Zhvi {
+    let prices = (1..=12)
+        .flat_map(|month| {
+            [2022, 2023].map(|year| ZhviPrice {
+                date: date(year, month, 28),
+                value: (year * 100 + month as i32) as f64,
+                filled: false,
+            })
+        })
+        .collect();
+    Zhvi {
+        region_name: "Irvine".to_string(),
+        region_type: RegionType::City,
+        state: None,
+        home_type: HomeType::AllHomes,
+        percentile: Percentile::Middle,
+        prices,
+    }
+}
+
+#[tokio::test]
+async fn test_memory_hpi_crud_and_query() {
+    let client = InMemoryClient::new();
+    for year in 2000..2010 {
+        let hpi = Hpi {
+            region_type: RegionType::FiveZip,
+            region_id: "92841".to_string(),
+            year,
+            hpi: Some(year as f32),
+            ..Default::default()
+        };
+        hpi.create(&client).await.expect("Failed to create");
+    }
+    let duplicate = Hpi {
+        region_type: RegionType::FiveZip,
+        region_id: "92841".to_string(),
+        year: 2000,
+        ..Default::default()
+    };
+    assert!(duplicate.create(&client).await.is_err());
+
+    let query = HpiQuery::new(RegionType::FiveZip, "92841".to_string(), 2003, 2005);
+    let hpis = Hpi::read_by_query(&client, &query).await.unwrap();
+    let years: Vec<i32> = hpis.iter().map(|hpi| hpi.year()).collect();
+    assert_eq!(years, vec![2003, 2004, 2005]);
+
+    let id = (&RegionType::FiveZip, "92841", 2004);
+    Hpi::delete(&client, id).await.unwrap();
+    assert!(Hpi::read(&client, id).await.is_err());
+
+    // Same-named counties in different states no longer collide
+    for (state, fips) in [("CA", "06059"), ("FL", "12095")] {
+        let hpi = Hpi {
+            region_type: RegionType::County,
+            region_id: fips.to_string(),
+            region_name: "Orange".to_string(),
+            state: Some(state.to_string()),
+            year: 2020,
+            ..Default::default()
+        };
+        hpi.create(&client).await.unwrap();
+    }
+    let query = HpiQuery::new(RegionType::County, "12095".to_string(), 2020, 2020);
+    let hpis = Hpi::read_by_query(&client, &query).await.unwrap();
+    assert_eq!(hpis.len(), 1);
+    assert_eq!(hpis[0].state(), Some("FL"));
+}
+
+#[tokio::test]
+async fn test_memory_region_query() {
+    let client = InMemoryClient::new();
+    for (city, zipcode) in [
+        ("irvine", "92602"),
+        ("irvine", "92618"),
+        ("tustin", "92780"),
+    ] {
+        let region = Region {
+            city: city.to_string(),
+            zipcode: zipcode.to_string(),
+            state: "CA".to_string(),
+            county_fips: "06059".to_string(),
+            ..Default::default()
+        };
+        region.create(&client).await.unwrap();
+    }
+
+    let query = RegionQuery::new(
+        vec!["irvine".to_string()],
+        vec!["92780".to_string()],
+        vec![],
+        vec![],
+    );
+    assert_eq!(
+        Region::read_by_query(&client, &query).await.unwrap().len(),
+        3
+    );
+
+    let query = RegionQuery::new(vec![], vec!["92618".to_string()], vec![], vec![]);
+    let regions = Region::read_by_query(&client, &query).await.unwrap();
+    assert_eq!(regions.len(), 1);
+    assert_eq!(regions[0].city(), "irvine");
+
+    assert_eq!(Region::read(&client, "irvine").await.unwrap().len(), 2);
+
+    let region = Region {
+        city: "verdi".to_string(),
+        zipcode: "89439".to_string(),
+        state: "NV".to_string(),
+        county_fips: "32031".to_string(),
+        ..Default::default()
+    };
+    region.create(&client).await.unwrap();
+    let query = RegionQuery::new(vec![], vec![], vec![], vec!["NV".to_string()]);
+    assert_eq!(
+        Region::read_by_query(&client, &query).await.unwrap().len(),
+        1
+    );
+    let query = RegionQuery::new(
+        vec![],
+        vec!["89439".to_string()],
+        vec!["06059".to_string()],
+        vec!["CA".to_string()],
+    );
+    assert_eq!(
+        Region::read_by_query(&client, &query).await.unwrap().len(),
+        3
+    );
+}
+
+#[tokio::test]
+async fn test_memory_region_crosswalk() {
+    let client = InMemoryClient::new();
+    for (county_fips, ratio) in [("32031", 0.2), ("06061", 0.8)] {
+        let region = Region {
+            city: "verdi".to_string(),
+            zipcode: "89439".to_string(),
+            state: "NV".to_string(),
+            county_fips: county_fips.to_string(),
+            res_ratio: ratio,
+            bus_ratio: ratio,
+            tot_ratio: ratio,
+        };
+        region.create(&client).await.unwrap();
+    }
+
+    let query = RegionQuery::new(vec![], vec!["89439".to_string()], vec![], vec![]);
+    assert_eq!(
+        Region::read_by_query(&client, &query).await.unwrap().len(),
+        2
+    );
+    let primary = client.read_region_by_id("89439").await.unwrap();
+    assert_eq!(primary.county_fips(), "06061");
+
+    Region::delete(&client, "89439").await.unwrap();
+    assert!(Region::read_by_query(&client, &query)
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn test_memory_t_yield_interval_average() {
+    let client = InMemoryClient::new();
+    for (day, value) in [(1, 4.0), (15, 5.0)] {
+        for month in [1, 2] {
+            let t_yield = TYield {
+                term: Term::TenYear,
+                date: date(2023, month, day),
+                yield_return: Some(value * month as f32),
+            };
+            t_yield.create(&client).await.unwrap();
+        }
+    }
+    let t_yield = TYield {
+        term: Term::TwoYear,
+        date: date(2023, 1, 1),
+        yield_return: Some(1.0),
+    };
+    t_yield.create(&client).await.unwrap();
+
+    let query = TYieldQuery::new(
+        Some(Term::TenYear),
+        date(2023, 1, 1),
+        date(2023, 12, 31),
+        DateInterval::Month,
+    );
+    let t_yields = TYield::read_by_query(&client, &query).await.unwrap();
+    assert_eq!(t_yields.len(), 2);
+    assert_eq!(t_yields[0].date(), &date(2023, 1, 1));
+    assert_eq!(t_yields[0].yield_return(), &Some(4.5));
+    assert_eq!(t_yields[1].yield_return(), &Some(9.0));
+
+    let query = TYieldQuery::new(
+        Some(Term::TenYear),
+        date(2023, 1, 1),
+        date(2023, 12, 31),
+        DateInterval::Year,
+    );
+    let t_yields = TYield::read_by_query(&client, &query).await.unwrap();
+    assert_eq!(t_yields.len(), 1);
+    assert_eq!(t_yields[0].yield_return(), &Some(6.75));
+
+    let query = TYieldQuery::new(
+        None,
+        date(2023, 1, 1),
+        date(2023, 12, 31),
+        DateInterval::Year,
+    );
+    let t_yields = TYield::read_by_query(&client, &query).await.unwrap();
+    assert_eq!(t_yields.len(), 2);
+}
+
+#[tokio::test]
+async fn test_memory_mortgage_rate_query() {
+    let client = InMemoryClient::new();
+    let mortgage_rates: Vec<MortgageRate> = [
+        (MortgageTerm::ThirtyYear, 4, Some(6.0), Some(0.6)),
+        (MortgageTerm::ThirtyYear, 11, Some(7.0), None),
+        (MortgageTerm::FifteenYear, 4, Some(5.5), Some(0.5)),
+    ]
+    .map(|(term, day, rate, points)| MortgageRate {
+        term,
+        date: date(2024, 1, day),
+        rate,
+        points,
+    })
+    .into();
+    MortgageRate::create_many(&client, &mortgage_rates)
+        .await
+        .unwrap();
+
+    let query = MortgageRateQuery::new(
+        Some(MortgageTerm::ThirtyYear),
+        date(2024, 1, 1),
+        date(2024, 12, 31),
+        DateInterval::Month,
+    );
+    let monthly = MortgageRate::read_by_query(&client, &query).await.unwrap();
+    assert_eq!(monthly.len(), 1);
+    assert_eq!(monthly[0].date(), &date(2024, 1, 1));
+    assert_eq!(monthly[0].rate(), &Some(6.5));
+    assert_eq!(monthly[0].points(), &Some(0.6));
+
+    let query = MortgageRateQuery::new(None, date(2024, 1, 1), date(2024, 1, 7), DateInterval::Day);
+    let weekly = MortgageRate::read_by_query(&client, &query).await.unwrap();
+    assert_eq!(weekly.len(), 2);
+
+    MortgageRate::delete(&client, (&MortgageTerm::FifteenYear, &date(2024, 1, 4)))
+        .await
+        .unwrap();
+    assert!(
+        MortgageRate::read(&client, (&MortgageTerm::FifteenYear, &date(2024, 1, 4)))
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn test_memory_zhvi_query() {
+    let client = InMemoryClient::new();
+    irvine_zhvi().create(&client).await.unwrap();
+
+    let query = ZhviQuery::new(
+        date(2023, 1, 1),
+        date(2023, 12, 31),
+        DateInterval::Month,
+        "Irvine".to_string(),
+        RegionType::City,
+        HomeType::AllHomes,
+        Percentile::Middle,
+    );
+    let zhvis = Zhvi::read_by_query(&client, &query).await.unwrap();
+    assert_eq!(zhvis.len(), 1);
+    assert_eq!(zhvis[0].prices().len(), 12);
+
+    let query = ZhviQuery::new(
+        date(2022, 1, 1),
+        date(2023, 12, 31),
+        DateInterval::Year,
+        "Irvine".to_string(),
+        RegionType::City,
+        HomeType::AllHomes,
+        Percentile::Middle,
+    )
+    .with_resampling(Resampling::First);
+    let zhvis = Zhvi::read_by_query(&client, &query).await.unwrap();
+    let values: Vec<f64> = zhvis[0].prices().iter().map(|p| p.value).collect();
+    assert_eq!(values, vec![202201.0, 202301.0]);
+    assert_eq!(zhvis[0].prices()[1].date, date(2023, 1, 1));
+
+    let id = ("Irvine", "city", "allhomes", "middle", None);
+    Zhvi::delete(&client, id).await.unwrap();
+    assert!(Zhvi::read_by_query(&client, &query)
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn test_memory_zhvis_keyed_by_state() {
+    let client = InMemoryClient::new();
+    let zhvis: Vec<Zhvi> = [("CA", 900000.0), ("FL", 400000.0)]
+        .map(|(state, value)| Zhvi {
+            region_name: "Orange County".to_string(),
+            region_type: RegionType::County,
+            state: Some(state.to_string()),
+            home_type: HomeType::AllHomes,
+            percentile: Percentile::Middle,
+            prices: vec![ZhviPrice {
+                date: date(2023, 1, 31),
+                value,
+                filled: false,
+            }],
+        })
+        .into();
+    Zhvi::create_many(&client, &zhvis).await.unwrap();
+
+    let query = ZhviQuery::new(
+        date(2023, 1, 1),
+        date(2023, 12, 31),
+        DateInterval::Month,
+        "Orange County".to_string(),
+        RegionType::County,
+        HomeType::AllHomes,
+        Percentile::Middle,
+    );
+    let zhvis = Zhvi::read_by_query(&client, &query).await.unwrap();
+    assert_eq!(zhvis.len(), 2);
+
+    let zhvis = Zhvi::read_by_query(&client, &query.with_state("FL".to_string()))
+        .await
+        .unwrap();
+    assert_eq!(zhvis.len(), 1);
+    assert_eq!(zhvis[0].prices()[0].value, 400000.0);
+
+    let id = ("Orange County", "county", "allhomes", "middle", Some("CA"));
+    assert_eq!(Zhvi::read(&client, id).await.unwrap().state(), Some("CA"));
+}
+
+#[tokio::test]
+async fn test_memory_create_many_skips_existing() {
+    let client = InMemoryClient::new();
+    let mut zhvi = irvine_zhvi();
+    zhvi.prices.truncate(4);
+    zhvi.create(&client).await.unwrap();
+
+    let zhvis = vec![irvine_zhvi(), irvine_zhvi()];
+    Zhvi::create_many(&client, &zhvis).await.unwrap();
+
+    let id = ("Irvine", "city", "allhomes", "middle", None);
+    let zhvi = Zhvi::read(&client, id).await.unwrap();
+    assert_eq!(zhvi.prices().len(), 24);
+    assert_eq!(zhvi.prices()[0].value, 202201.0);
+
+    let t_yields: Vec<TYield> = [4.0, 5.0]
+        .map(|value| TYield {
+            term: Term::TenYear,
+            date: date(2023, 1, 3),
+            yield_return: Some(value),
+        })
+        .into();
+    TYield::create_many(&client, &t_yields).await.unwrap();
+    let t_yield = TYield::read(&client, (&Term::TenYear, &date(2023, 1, 3)))
+        .await
+        .unwrap();
+    assert_eq!(t_yield.yield_return(), &Some(4.0));
+}
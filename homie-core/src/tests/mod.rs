@@ -1,7 +1,32 @@
 use std::fmt::Debug;
 
+use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 
+mod affordability;
+mod aggregate;
+mod analytics;
+mod cache;
+mod cpi;
+mod fair_value;
+mod forecast;
+mod gap_fill;
+mod http;
+mod importer;
+mod memory;
+mod mortgage;
+#[cfg(feature = "postgres")]
+mod postgres;
+mod resample;
+mod spread;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+
+/// Builds a fixture date, panicking on an invalid one
+pub(super) fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
 /// Test object that mocks calling different persistences
 #[derive(Debug, Serialize, Deserialize)]
 struct TestObject {}
@@ -68,6 +93,7 @@ impl TestPersist for OtherTestStorage {
 
 #[test]
 /// Instantiates an object and calls on different persistences
+#[allow(non_local_definitions)]
 fn test_persistence() {
     println!("\nCreating TestObject");
     let my_object = TestObject {};
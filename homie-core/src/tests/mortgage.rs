@@ -0,0 +1,85 @@
+use super::date;
+use crate::adapter::repository::database::memory::InMemoryClient;
+use crate::domain::mortgage::{read_rate, Mortgage, RateSource};
+use crate::domain::mortgage_rate::{MortgageRate, MortgageTerm};
+use crate::domain::t_yield::{TYield, Term};
+use crate::error::DomainError;
+
+#[test]
+fn test_mortgage_payment_and_schedule() {
+    let mortgage = Mortgage::new(375_000.0, 75_000.0, 6.0, 30).unwrap();
+    assert_eq!(mortgage.principal(), 300_000.0);
+    assert!((mortgage.monthly_payment() - 1798.65).abs() < 0.01);
+    assert!((mortgage.total_interest() - 347_514.57).abs() < 0.1);
+
+    let schedule = mortgage.schedule();
+    assert_eq!(schedule.len(), 360);
+    assert!((schedule[0].interest - 1500.0).abs() < 1e-9);
+    assert_eq!(schedule[359].balance, 0.0);
+    let repaid: f64 = schedule.iter().map(|i| i.principal).sum();
+    assert!((repaid - 300_000.0).abs() < 1e-6);
+    assert!((schedule[59].balance - mortgage.remaining_balance(60)).abs() < 1e-6);
+
+    let summary = mortgage.summary(Some(360));
+    assert_eq!(summary.remaining_balance, Some(0.0));
+
+    let interest_free = Mortgage::new(120_000.0, 0.0, 0.0, 10).unwrap();
+    assert_eq!(interest_free.monthly_payment(), 1000.0);
+    assert_eq!(interest_free.remaining_balance(60), 60_000.0);
+
+    assert!(Mortgage::new(100_000.0, 200_000.0, 6.0, 30).is_err());
+    assert!(Mortgage::new(100_000.0, 0.0, 6.0, 0).is_err());
+    assert!(Mortgage::new(100_000.0, 0.0, 6.0, 51).is_err());
+    assert!(Mortgage::new(100_000.0, 0.0, 6.0, u32::MAX).is_err());
+}
+
+#[tokio::test]
+async fn test_mortgage_reads_latest_rate() {
+    let client = InMemoryClient::new();
+    let mortgage_rates: Vec<MortgageRate> = [(4, 6.62), (11, 6.66)]
+        .map(|(day, rate)| MortgageRate {
+            term: MortgageTerm::ThirtyYear,
+            date: date(2024, 1, day),
+            rate: Some(rate),
+            points: None,
+        })
+        .into();
+    MortgageRate::create_many(&client, &mortgage_rates)
+        .await
+        .unwrap();
+    let t_yield = TYield {
+        term: Term::TenYear,
+        date: date(2024, 1, 1),
+        yield_return: Some(4.0),
+    };
+    t_yield.create(&client).await.unwrap();
+
+    let rate = read_rate(&client, &RateSource::MortgageRate, 30, date(2024, 1, 8))
+        .await
+        .unwrap();
+    assert!((rate - 6.62).abs() < 1e-6);
+
+    let source = RateSource::TYield {
+        term: Term::TenYear,
+        spread: 1.75,
+    };
+    let rate = read_rate(&client, &source, 30, date(2024, 1, 8))
+        .await
+        .unwrap();
+    assert_eq!(rate, 5.75);
+
+    assert!(
+        read_rate(&client, &RateSource::MortgageRate, 15, date(2024, 1, 8))
+            .await
+            .is_err()
+    );
+    assert!(
+        read_rate(&client, &RateSource::MortgageRate, 20, date(2024, 1, 8))
+            .await
+            .is_err()
+    );
+    assert!(matches!(
+        read_rate(&client, &RateSource::MortgageRate, 30, date(2020, 1, 8)).await,
+        Err(DomainError::ConvertDomain(_))
+    ));
+}
@@ -0,0 +1,25 @@
+use crate::adapter::repository::database::postgres::{enum_drift, rust_enum_labels};
+
+#[test]
+fn test_postgres_enum_drift() {
+    let expected = rust_enum_labels();
+    let mut actual: Vec<(String, String)> = expected
+        .iter()
+        .flat_map(|(name, labels)| labels.iter().map(|l| (name.to_string(), l.clone())))
+        .collect();
+    assert!(actual.contains(&("home_type".to_string(), "condocoops".to_string())));
+    assert!(enum_drift(&expected, &actual).is_empty());
+
+    for (name, label) in actual.iter_mut() {
+        if name == "home_type" && label == "condocoops" {
+            *label = "condococops".to_string();
+        }
+    }
+    assert_eq!(
+        enum_drift(&expected, &actual),
+        vec![
+            "home_type is missing 'condocoops'".to_string(),
+            "home_type has unknown 'condococops'".to_string(),
+        ]
+    );
+}
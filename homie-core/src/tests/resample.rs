@@ -0,0 +1,182 @@
+use chrono::NaiveDate;
+
+use super::date;
+use crate::adapter::repository::database::memory::InMemoryClient;
+use crate::domain::common::{DateInterval, RegionType};
+use crate::domain::mortgage_rate::{MortgageRate, MortgageRateQuery, MortgageTerm};
+use crate::domain::resample::{interval_start, resample, Resampling};
+use crate::domain::t_yield::{TYield, TYieldQuery, Term};
+use crate::domain::zhvi::{HomeType, Percentile, Zhvi, ZhviPrice, ZhviQuery};
+
+#[test]
+fn test_resample_interval_start() {
+    // A Sunday
+    let sunday = date(2024, 3, 17);
+    assert_eq!(interval_start(sunday, &DateInterval::Day), sunday);
+    assert_eq!(
+        interval_start(sunday, &DateInterval::Week),
+        date(2024, 3, 11)
+    );
+    assert_eq!(
+        interval_start(date(2024, 3, 11), &DateInterval::Week),
+        date(2024, 3, 11)
+    );
+    assert_eq!(
+        interval_start(sunday, &DateInterval::Month),
+        date(2024, 3, 1)
+    );
+    assert_eq!(
+        interval_start(sunday, &DateInterval::Quarter),
+        date(2024, 1, 1)
+    );
+    assert_eq!(
+        interval_start(date(2024, 12, 31), &DateInterval::Quarter),
+        date(2024, 10, 1)
+    );
+    assert_eq!(
+        interval_start(sunday, &DateInterval::Year),
+        date(2024, 1, 1)
+    );
+}
+
+#[test]
+fn test_resample_aggregations() {
+    let prices: Vec<ZhviPrice> = [(3, 3.0), (1, 4.0), (2, 1.0), (4, 10.0)]
+        .map(|(month, value)| ZhviPrice {
+            date: date(2024, month, 28),
+            value,
+            filled: false,
+        })
+        .into();
+    let quarterly = |resampling: Resampling| -> Vec<(NaiveDate, f64)> {
+        resample(&prices, &DateInterval::Quarter, &resampling)
+            .into_iter()
+            .map(|price| (price.date, price.value))
+            .collect()
+    };
+    assert_eq!(
+        quarterly(Resampling::First),
+        vec![(date(2024, 1, 1), 4.0), (date(2024, 4, 1), 10.0)]
+    );
+    assert_eq!(quarterly(Resampling::Last)[0].1, 3.0);
+    assert_eq!(quarterly(Resampling::Mean)[0].1, 8.0 / 3.0);
+    assert_eq!(quarterly(Resampling::Median)[0].1, 3.0);
+    assert_eq!(quarterly(Resampling::Min)[0].1, 1.0);
+    assert_eq!(quarterly(Resampling::Max)[0].1, 4.0);
+
+    let monthly = resample(&prices[..2], &DateInterval::Month, &Resampling::Median);
+    assert_eq!(monthly.len(), 2);
+    assert_eq!(monthly[0].date, date(2024, 1, 1));
+}
+
+#[test]
+fn test_resample_keeps_series_and_missing_values_apart() {
+    let mortgage_rates: Vec<MortgageRate> = [
+        (MortgageTerm::ThirtyYear, 4, Some(6.0), None),
+        (MortgageTerm::ThirtyYear, 11, Some(7.0), None),
+        (MortgageTerm::FifteenYear, 4, Some(5.5), Some(0.5)),
+        (MortgageTerm::FifteenYear, 11, None, Some(0.7)),
+    ]
+    .map(|(term, day, rate, points)| MortgageRate {
+        term,
+        date: date(2024, 1, day),
+        rate,
+        points,
+    })
+    .into();
+    let weekly = resample(&mortgage_rates, &DateInterval::Week, &Resampling::Last);
+    assert_eq!(weekly.len(), 4);
+    let monthly = resample(&mortgage_rates, &DateInterval::Month, &Resampling::Last);
+    assert_eq!(monthly.len(), 2);
+    assert_eq!(monthly[0].term(), &MortgageTerm::FifteenYear);
+    assert_eq!(monthly[0].rate(), &Some(5.5));
+    assert_eq!(monthly[0].points(), &Some(0.7));
+    assert_eq!(monthly[1].rate(), &Some(7.0));
+    assert_eq!(monthly[1].points(), &None);
+}
+
+#[tokio::test]
+async fn test_resample_applies_to_every_query() {
+    let client = InMemoryClient::new();
+    let zhvi = Zhvi {
+        region_name: "Irvine".to_string(),
+        region_type: RegionType::City,
+        state: None,
+        home_type: HomeType::AllHomes,
+        percentile: Percentile::Middle,
+        prices: (1..=6)
+            .map(|month| ZhviPrice {
+                date: date(2024, month, 28),
+                value: month as f64,
+                filled: false,
+            })
+            .collect(),
+    };
+    zhvi.create(&client).await.unwrap();
+    let t_yields: Vec<TYield> = [(2, 4.0), (3, 4.2), (9, 4.4)]
+        .map(|(day, value)| TYield {
+            term: Term::TenYear,
+            date: date(2024, 1, day),
+            yield_return: Some(value),
+        })
+        .into();
+    TYield::create_many(&client, &t_yields).await.unwrap();
+    let mortgage_rate = MortgageRate {
+        term: MortgageTerm::ThirtyYear,
+        date: date(2024, 1, 4),
+        rate: Some(6.6),
+        points: None,
+    };
+    mortgage_rate.create(&client).await.unwrap();
+
+    let query = ZhviQuery::new(
+        date(2024, 1, 1),
+        date(2024, 12, 31),
+        DateInterval::Quarter,
+        "Irvine".to_string(),
+        RegionType::City,
+        HomeType::AllHomes,
+        Percentile::Middle,
+    )
+    .with_resampling(Resampling::Max);
+    let zhvis = Zhvi::read_by_query(&client, &query).await.unwrap();
+    let values: Vec<f64> = zhvis[0].prices().iter().map(|p| p.value).collect();
+    assert_eq!(values, vec![3.0, 6.0]);
+
+    // Daily ZHVIs are the stored prices
+    let query = ZhviQuery::new(
+        date(2024, 1, 1),
+        date(2024, 12, 31),
+        DateInterval::Day,
+        "Irvine".to_string(),
+        RegionType::City,
+        HomeType::AllHomes,
+        Percentile::Middle,
+    );
+    let zhvis = Zhvi::read_by_query(&client, &query).await.unwrap();
+    assert_eq!(zhvis[0].prices().len(), 6);
+    assert_eq!(zhvis[0].prices()[0].date, date(2024, 1, 28));
+
+    let query = TYieldQuery::new(
+        None,
+        date(2024, 1, 1),
+        date(2024, 1, 31),
+        DateInterval::Week,
+    )
+    .with_resampling(Resampling::First);
+    let t_yields = TYield::read_by_query(&client, &query).await.unwrap();
+    assert_eq!(t_yields.len(), 2);
+    assert_eq!(t_yields[0].date(), &date(2024, 1, 1));
+    assert_eq!(t_yields[0].yield_return(), &Some(4.0));
+    assert_eq!(t_yields[1].date(), &date(2024, 1, 8));
+
+    let query = MortgageRateQuery::new(
+        None,
+        date(2024, 1, 1),
+        date(2024, 12, 31),
+        DateInterval::Quarter,
+    );
+    let mortgage_rates = MortgageRate::read_by_query(&client, &query).await.unwrap();
+    assert_eq!(mortgage_rates.len(), 1);
+    assert_eq!(mortgage_rates[0].rate(), &Some(6.6));
+}
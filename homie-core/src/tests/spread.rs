@@ -0,0 +1,93 @@
+use super::date;
+use crate::adapter::repository::database::memory::InMemoryClient;
+use crate::domain::common::DateInterval;
+use crate::domain::mortgage_rate::{MortgageRate, MortgageTerm};
+use crate::domain::spread::{spreads, summarize, SpreadAnalysis, SpreadQuery};
+use crate::domain::t_yield::{TYield, Term};
+
+fn mortgage_rate(month: u32, day: u32, rate: f32) -> MortgageRate {
+    MortgageRate {
+        term: MortgageTerm::ThirtyYear,
+        date: date(2024, month, day),
+        rate: Some(rate),
+        points: None,
+    }
+}
+
+fn t_yield(term: Term, month: u32, day: u32, value: f32) -> TYield {
+    TYield {
+        term,
+        date: date(2024, month, day),
+        yield_return: Some(value),
+    }
+}
+
+#[test]
+fn test_spread_aligns_by_date() {
+    let mortgage_rates = vec![
+        mortgage_rate(1, 4, 6.5),
+        mortgage_rate(1, 11, 6.6),
+        // No yield on this date
+        mortgage_rate(1, 18, 6.7),
+    ];
+    let t_yields = vec![
+        t_yield(Term::TenYear, 1, 11, 4.1),
+        t_yield(Term::TenYear, 1, 4, 4.0),
+        // Only the 10-year yield is used
+        t_yield(Term::TwoYear, 1, 18, 4.3),
+    ];
+
+    let spreads = spreads(&mortgage_rates, &t_yields);
+    assert_eq!(spreads.len(), 2);
+    assert_eq!(spreads[0].date, date(2024, 1, 4));
+    assert!((spreads[0].spread - 2.5).abs() < 1e-6);
+    assert_eq!(spreads[1].t_yield, 4.1);
+}
+
+#[test]
+fn test_spread_summary() {
+    let mortgage_rates: Vec<MortgageRate> = [(1, 6.0), (2, 7.0), (3, 8.0), (4, 9.0)]
+        .map(|(month, rate)| mortgage_rate(month, 1, rate))
+        .into();
+    let t_yields: Vec<TYield> = [1, 2, 3, 4]
+        .map(|month| t_yield(Term::TenYear, month, 1, 5.0))
+        .into();
+
+    let summary = summarize(&spreads(&mortgage_rates, &t_yields)).unwrap();
+    assert_eq!(summary.mean, 2.5);
+    assert_eq!((summary.min, summary.max), (1.0, 4.0));
+    assert_eq!(summary.current.spread, 4.0);
+    assert_eq!(summary.percentile, 100.0);
+    assert!((summary.z_score.unwrap() - 1.5 / 1.25_f32.sqrt()).abs() < 1e-5);
+    assert_eq!(summary.fair_rate, 7.5);
+
+    assert!(summarize(&[]).is_none());
+}
+
+#[tokio::test]
+async fn test_spread_reads_by_interval() {
+    let client = InMemoryClient::new();
+    MortgageRate::create_many(
+        &client,
+        &[mortgage_rate(1, 4, 6.0), mortgage_rate(1, 25, 7.0)],
+    )
+    .await
+    .unwrap();
+    TYield::create_many(&client, &[t_yield(Term::TenYear, 1, 2, 4.0)])
+        .await
+        .unwrap();
+
+    let query = SpreadQuery::new(
+        MortgageTerm::ThirtyYear,
+        date(2024, 1, 1),
+        date(2024, 12, 31),
+        DateInterval::Month,
+    );
+    let analysis = SpreadAnalysis::read_by_query(&client, &query)
+        .await
+        .unwrap();
+    assert_eq!(analysis.spreads.len(), 1);
+    assert_eq!(analysis.spreads[0].date, date(2024, 1, 1));
+    assert_eq!(analysis.spreads[0].spread, 2.5);
+    assert_eq!(analysis.summary.unwrap().z_score, None);
+}
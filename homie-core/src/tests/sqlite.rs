@@ -0,0 +1,170 @@
+use super::date;
+use crate::adapter::repository::database::sqlite::SqliteClient;
+use crate::domain::common::{DateInterval, RegionType};
+use crate::domain::cpi::{Cpi, CpiQuery};
+use crate::domain::hpi::{Hpi, HpiQuery};
+use crate::domain::income::{Income, IncomeQuery};
+use crate::domain::mortgage_rate::{MortgageRate, MortgageRateQuery, MortgageTerm};
+use crate::domain::region::{Region, RegionQuery};
+use crate::domain::t_yield::{TYield, TYieldQuery, Term};
+use crate::domain::zhvi::{HomeType, Percentile, Zhvi, ZhviPrice, ZhviQuery};
+
+async fn sqlite_client(name: &str) -> SqliteClient {
+    let path = std::env::temp_dir().join(format!("homie-{}-{}.db", name, std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    SqliteClient::connect(&format!("sqlite://{}", path.display()))
+        .await
+        .expect("Failed to connect to sqlite")
+}
+
+#[tokio::test]
+async fn test_sqlite_round_trip() {
+    let client = sqlite_client("round-trip").await;
+
+    let hpi = Hpi {
+        region_type: RegionType::County,
+        region_id: "06059".to_string(),
+        region_name: "Orange".to_string(),
+        state: Some("CA".to_string()),
+        year: 2020,
+        hpi: Some(250.5),
+        ..Default::default()
+    };
+    hpi.create(&client).await.unwrap();
+    let query = HpiQuery::new(RegionType::County, "06059".to_string(), 2019, 2021);
+    let hpis = Hpi::read_by_query(&client, &query).await.unwrap();
+    assert_eq!(hpis.len(), 1);
+    assert_eq!(hpis[0].hpi(), Some(250.5));
+    assert_eq!(hpis[0].state(), Some("CA"));
+
+    for (month, value) in [(1, 308.417), (2, 310.326)] {
+        let cpi = Cpi {
+            date: date(2024, month, 1),
+            value,
+        };
+        cpi.create(&client).await.unwrap();
+    }
+    let query = CpiQuery::new(date(2024, 2, 1), date(2024, 12, 31));
+    let cpis = Cpi::read_by_query(&client, &query).await.unwrap();
+    assert_eq!(cpis.len(), 1);
+    assert_eq!(cpis[0].date(), &date(2024, 2, 1));
+    assert_eq!(cpis[0].value(), 310.326);
+
+    let income = Income {
+        region_type: RegionType::City,
+        region_id: "0636770".to_string(),
+        region_name: "Irvine".to_string(),
+        year: 2022,
+        median_income: Some(122_948.0),
+        margin_of_error: None,
+    };
+    income.create(&client).await.unwrap();
+    for region in ["Irvine", "0636770"] {
+        let query = IncomeQuery::new(RegionType::City, region.to_string(), 2020, 2022);
+        let incomes = Income::read_by_query(&client, &query).await.unwrap();
+        assert_eq!(incomes.len(), 1);
+        assert_eq!(incomes[0].median_income(), Some(122_948.0));
+    }
+
+    for (city, zipcode) in [("irvine", "92602"), ("tustin", "92780")] {
+        let region = Region {
+            city: city.to_string(),
+            zipcode: zipcode.to_string(),
+            state: "CA".to_string(),
+            county_fips: "06059".to_string(),
+            ..Default::default()
+        };
+        region.create(&client).await.unwrap();
+    }
+    let query = RegionQuery::new(
+        vec!["irvine".to_string()],
+        vec!["92780".to_string()],
+        vec![],
+        vec![],
+    );
+    assert_eq!(
+        Region::read_by_query(&client, &query).await.unwrap().len(),
+        2
+    );
+
+    for (day, value) in [(1, 4.0), (15, 5.0)] {
+        let t_yield = TYield {
+            term: Term::TenYear,
+            date: date(2023, 3, day),
+            yield_return: Some(value),
+        };
+        t_yield.create(&client).await.unwrap();
+    }
+    let query = TYieldQuery::new(
+        Some(Term::TenYear),
+        date(2023, 1, 1),
+        date(2023, 12, 31),
+        DateInterval::Month,
+    );
+    let t_yields = TYield::read_by_query(&client, &query).await.unwrap();
+    assert_eq!(t_yields.len(), 1);
+    assert_eq!(t_yields[0].date(), &date(2023, 3, 1));
+    assert_eq!(t_yields[0].yield_return(), &Some(4.5));
+
+    let mortgage_rate = MortgageRate {
+        term: MortgageTerm::FifteenYear,
+        date: date(2024, 1, 4),
+        rate: Some(5.89),
+        points: Some(0.6),
+    };
+    mortgage_rate.create(&client).await.unwrap();
+    let query = MortgageRateQuery::new(
+        Some(MortgageTerm::FifteenYear),
+        date(2024, 1, 1),
+        date(2024, 12, 31),
+        DateInterval::Year,
+    );
+    let mortgage_rates = MortgageRate::read_by_query(&client, &query).await.unwrap();
+    assert_eq!(mortgage_rates.len(), 1);
+    assert_eq!(mortgage_rates[0].date(), &date(2024, 1, 1));
+    assert_eq!(mortgage_rates[0].rate(), &Some(5.89));
+
+    let zhvi = Zhvi {
+        region_name: "Irvine".to_string(),
+        region_type: RegionType::City,
+        state: None,
+        home_type: HomeType::CondoCoOps,
+        percentile: Percentile::Middle,
+        prices: (1..=12)
+            .map(|month| ZhviPrice {
+                date: date(2023, month, 28),
+                value: month as f64,
+                filled: false,
+            })
+            .collect(),
+    };
+    zhvi.create(&client).await.unwrap();
+    let query = ZhviQuery::new(
+        date(2023, 1, 1),
+        date(2023, 12, 31),
+        DateInterval::Year,
+        "Irvine".to_string(),
+        RegionType::City,
+        HomeType::CondoCoOps,
+        Percentile::Middle,
+    );
+    let zhvis = Zhvi::read_by_query(&client, &query).await.unwrap();
+    assert_eq!(zhvis.len(), 1);
+    assert_eq!(zhvis[0].prices().len(), 1);
+    assert_eq!(zhvis[0].prices()[0].value, 6.5);
+
+    // A same-named city in another state is stored alongside it
+    let other = Zhvi {
+        state: Some("TX".to_string()),
+        ..zhvi.clone()
+    };
+    Zhvi::create_many(&client, &[other]).await.unwrap();
+    let zhvis = Zhvi::read_by_query(&client, &query).await.unwrap();
+    assert_eq!(zhvis.len(), 2);
+    let zhvis = Zhvi::read_by_query(&client, &query.with_state("TX".to_string()))
+        .await
+        .unwrap();
+    assert_eq!(zhvis.len(), 1);
+    assert_eq!(zhvis[0].state(), Some("TX"));
+    assert_eq!(zhvis[0].prices()[0].value, 6.5);
+}
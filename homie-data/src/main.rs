@@ -15,7 +15,17 @@ mod util;
 
 #[tokio::main]
 async fn main() -> Result<(), DomainError> {
-    let config = CONFIG.get_or_init(Config::load_config);
+    let config = Config::load_config()?;
+    let config = CONFIG.get_or_init(|| config);
+    match std::env::args().nth(1).as_deref() {
+        Some("migrate") => return Repository::migrate(config).await,
+        Some("manifest") => {
+            print!("{}", Importer::new(config).zhvi_manifest()?);
+            return Ok(());
+        }
+        _ => {}
+    }
+
     let importer = Importer::new(config);
     let repository = Repository::new(config).await?;
 
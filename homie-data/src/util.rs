@@ -1,13 +1,24 @@
-use homie_core::adapter::importer::Importer;
+use homie_core::adapter::importer::{ImportMode, ImportReport, Importer};
 use homie_core::adapter::repository::Repository;
+use homie_core::domain::cpi::Cpi;
+use homie_core::domain::hpi::Hpi;
+use homie_core::domain::income::Income;
+use homie_core::domain::mortgage_rate::MortgageRate;
+use homie_core::domain::t_yield::TYield;
+use homie_core::domain::zhvi::Zhvi;
 use homie_core::error::DomainError;
 
+const MAX_REPORTED_ERRORS: usize = 20;
+
 pub(crate) async fn read_and_write_datasets(
     importer: &Importer,
     repo: &Repository,
 ) -> Result<(), DomainError> {
     read_and_write_t_yields(importer, repo).await?;
+    read_and_write_mortgage_rates(importer, repo).await?;
+    read_and_write_cpis(importer, repo).await?;
     read_and_write_hpi(importer, repo).await?;
+    read_and_write_incomes(importer, repo).await?;
     read_and_write_region(importer, repo).await?;
     read_and_write_zhvi(importer, repo).await?;
 
@@ -18,33 +29,52 @@ async fn read_and_write_t_yields(
     importer: &Importer,
     repo: &Repository,
 ) -> Result<(), DomainError> {
-    let t_yield_data = importer.read_fed_yields()?;
-    for t_yield in t_yield_data.ten_year_yields() {
-        t_yield.create(repo.session()).await?;
-    }
+    let (t_yield_data, report) = importer.read_fed_yields()?;
+    check_report(importer, "TYield", &report)?;
+    TYield::create_many(repo.session(), t_yield_data.t_yields()).await?;
     Ok(())
 }
 
-async fn read_and_write_hpi(importer: &Importer, repo: &Repository) -> Result<(), DomainError> {
-    let hpi_data = importer.read_fhfa_hpis()?;
+async fn read_and_write_mortgage_rates(
+    importer: &Importer,
+    repo: &Repository,
+) -> Result<(), DomainError> {
+    let (mortgage_rate_data, report) = importer.read_freddie_mac_rates()?;
+    check_report(importer, "MortgageRate", &report)?;
+    MortgageRate::create_many(repo.session(), mortgage_rate_data.mortgage_rates()).await?;
+    Ok(())
+}
 
-    for hpi in hpi_data.three_zip_hpis() {
-        hpi.create(repo.session()).await?;
-    }
+async fn read_and_write_cpis(importer: &Importer, repo: &Repository) -> Result<(), DomainError> {
+    let (cpi_data, report) = importer.read_bls_cpis()?;
+    check_report(importer, "Cpi", &report)?;
+    Cpi::create_many(repo.session(), cpi_data.cpis()).await?;
+    Ok(())
+}
 
-    for hpi in hpi_data.five_zip_hpis() {
-        hpi.create(repo.session()).await?;
-    }
+async fn read_and_write_hpi(importer: &Importer, repo: &Repository) -> Result<(), DomainError> {
+    let (hpi_data, report) = importer.read_fhfa_hpis()?;
+    check_report(importer, "Hpi", &report)?;
+    Hpi::create_many(repo.session(), hpi_data.three_zip_hpis()).await?;
+    Hpi::create_many(repo.session(), hpi_data.five_zip_hpis()).await?;
+    Hpi::create_many(repo.session(), hpi_data.county_hpis()).await?;
+    Hpi::create_many(repo.session(), hpi_data.state_hpis()).await?;
+    Hpi::create_many(repo.session(), hpi_data.msa_hpis()).await?;
+    Hpi::create_many(repo.session(), hpi_data.national_hpis()).await?;
 
-    for hpi in hpi_data.county_hpis() {
-        hpi.create(repo.session()).await?;
-    }
+    Ok(())
+}
 
+async fn read_and_write_incomes(importer: &Importer, repo: &Repository) -> Result<(), DomainError> {
+    let (income_data, report) = importer.read_census_incomes()?;
+    check_report(importer, "Income", &report)?;
+    Income::create_many(repo.session(), income_data.incomes()).await?;
     Ok(())
 }
 
 async fn read_and_write_region(importer: &Importer, repo: &Repository) -> Result<(), DomainError> {
-    let region_data = importer.read_huduser_regions()?;
+    let (region_data, report) = importer.read_huduser_regions()?;
+    check_report(importer, "Region", &report)?;
     for region in region_data.regions() {
         region.create(repo.session()).await?;
     }
@@ -52,18 +82,23 @@ async fn read_and_write_region(importer: &Importer, repo: &Repository) -> Result
 }
 
 async fn read_and_write_zhvi(importer: &Importer, repo: &Repository) -> Result<(), DomainError> {
-    let zhvi_data = importer.read_zillow_zhvis()?;
-
-    for zhvi in zhvi_data.all_homes_zhvis() {
-        zhvi.create(repo.session()).await?;
-    }
-
-    for zhvi in zhvi_data.condo_coops_zhvis() {
-        zhvi.create(repo.session()).await?;
-    }
+    let (zhvi_data, report) = importer.read_zillow_zhvis()?;
+    check_report(importer, "Zhvi", &report)?;
+    Zhvi::create_many(repo.session(), zhvi_data.all_homes_zhvis()).await?;
+    Zhvi::create_many(repo.session(), zhvi_data.condo_coops_zhvis()).await?;
+    Zhvi::create_many(repo.session(), zhvi_data.single_family_homes_zhvis()).await?;
+    Ok(())
+}
 
-    for zhvi in zhvi_data.single_family_homes_zhvis() {
-        zhvi.create(repo.session()).await?;
+// Strict mode refuses to write a dataset that had any rejected rows
+fn check_report(importer: &Importer, name: &str, report: &ImportReport) -> Result<(), DomainError> {
+    println!("{}: {}", name, report.summary(MAX_REPORTED_ERRORS));
+    if importer.mode() == &ImportMode::Strict && !report.is_clean() {
+        return Err(DomainError::Parse(format!(
+            "{} import rejected {} rows in strict mode",
+            name,
+            report.rows_rejected()
+        )));
     }
     Ok(())
 }
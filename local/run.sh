@@ -142,9 +142,8 @@ until docker exec postgres_db pg_isready -U postgres &> /dev/null; do
 done
 tput rc; tput el; echo -e "(${GREEN}done${NC})"
 
-# Initialize Tables
+# Tables are created by homie-data's embedded migrations (RUN_MIGRATIONS=true)
 DB_NAME=homie
-docker exec -i postgres_db psql -U postgres -d "$DB_NAME" < migrations/20240424014039_init_tables.sql > /dev/null 2>&1
 
 # Write datasets to Postgres
 echo -n "Running homie-data and importing datasets... "
@@ -12,7 +12,10 @@ curl -s -X GET 'http://127.0.0.1:8080/health'
 echo >> tmp.txt
 
 echo "Testing /hpis" >> tmp.txt
-curl -s -X GET 'http://127.0.0.1:8080/hpis?region_name=92841&start_date=2023-1-1&end_date=2024-12-31' | jq . >> tmp.txt
+curl -s -X GET 'http://127.0.0.1:8080/hpis?region_type=fivezip&region_id=92841&start_date=2023-1-1&end_date=2024-12-31' | jq . >> tmp.txt
+curl -s -X GET 'http://127.0.0.1:8080/hpis?region_type=fivezip&region_id=92841&start_date=2000-1-1&end_date=2024-12-31&transform=drawdown' | jq '.[].drawdown' >> tmp.txt
+curl -s -X GET 'http://127.0.0.1:8080/hpis?region_type=fivezip&region_id=92841&start_date=2015-1-1&end_date=2024-12-31&real=true' | jq . >> tmp.txt
+curl -s -X GET 'http://127.0.0.1:8080/hpis/derived?region_type=county&region_id=06059&start_date=2015-1-1&end_date=2024-12-31&zip_aggregation=median' | jq '.[] | {state, zipcodes: (.zipcodes | length), series: .series[-3:]}' >> tmp.txt
 echo >> tmp.txt
 
 echo "Testing /regions" >> tmp.txt
@@ -24,10 +27,43 @@ echo >> tmp.txt
 
 echo "Testing /tyields" >> tmp.txt
 curl -s -X GET 'http://127.0.0.1:8080/tyields?start_date=2023-1-1&end_date=2024-12-31&date_interval=Year' | jq . >> tmp.txt
+curl -s -X GET 'http://127.0.0.1:8080/tyields?start_date=2023-1-1&end_date=2024-12-31&date_interval=Year&term=10y' | jq . >> tmp.txt
+curl -s -X GET 'http://127.0.0.1:8080/tyields?start_date=2024-1-1&end_date=2024-12-31&date_interval=Quarter&term=10y&aggregation=last' | jq . >> tmp.txt
+curl -s -X GET 'http://127.0.0.1:8080/tyields?start_date=2023-1-1&end_date=2024-12-31&date_interval=Day&term=10y&transform=rollingvolatility&window=20' | jq . >> tmp.txt
+echo >> tmp.txt
+
+echo "Testing /affordability" >> tmp.txt
+curl -s -X GET 'http://127.0.0.1:8080/affordability?start_date=2023-1-1&end_date=2024-12-31&home_type=AllHomes&region_type=City&region_name=Irvine&percentile=Middle' | jq . >> tmp.txt
+echo >> tmp.txt
+
+echo "Testing /fair-value" >> tmp.txt
+curl -s -X GET 'http://127.0.0.1:8080/fair-value?start_date=2015-1-1&end_date=2024-12-31&home_type=AllHomes&region_type=City&region_name=Irvine&percentile=Middle&model=RateAdjusted' | jq '.[].fit' >> tmp.txt
+echo >> tmp.txt
+
+echo "Testing /mortgage" >> tmp.txt
+curl -s -X GET 'http://127.0.0.1:8080/mortgage/payment?price=900000&down_payment=180000&rate=6.5&months_paid=60' | jq . >> tmp.txt
+curl -s -X GET 'http://127.0.0.1:8080/mortgage/schedule?price=900000&down_payment=180000&date=2024-01-01&rate_source=tyield&spread=1.7' | jq .summary >> tmp.txt
+echo >> tmp.txt
+
+echo "Testing /mortgage-rates" >> tmp.txt
+curl -s -X GET 'http://127.0.0.1:8080/mortgage-rates?start_date=2023-1-1&end_date=2024-12-31&date_interval=Month&term=30y' | jq . >> tmp.txt
+echo >> tmp.txt
+
+echo "Testing /spreads" >> tmp.txt
+curl -s -X GET 'http://127.0.0.1:8080/spreads?start_date=2000-1-1&end_date=2024-12-31&date_interval=Month&term=30y' | jq .summary >> tmp.txt
 echo >> tmp.txt
 
 echo "Testing /zhvis" >> tmp.txt
 curl -s -X GET 'http://127.0.0.1:8080/zhvis?start_date=2023-1-1&end_date=2024-12-31&date_interval=month&home_type=AllHomes&region_type=City&region_name=Irvine&percentile=Middle' | jq . >> tmp.txt
+curl -s -X GET 'http://127.0.0.1:8080/zhvis?start_date=2015-1-1&end_date=2024-12-31&date_interval=month&home_type=AllHomes&region_type=City&region_name=Irvine&percentile=Middle&transform=yoy' | jq . >> tmp.txt
+curl -s -X GET 'http://127.0.0.1:8080/zhvis?start_date=2015-1-1&end_date=2024-12-31&date_interval=year&home_type=AllHomes&region_type=City&region_name=Irvine&percentile=Middle&real=true&base_month=2024-01' | jq . >> tmp.txt
+curl -s -X GET 'http://127.0.0.1:8080/zhvis?start_date=2020-1-1&end_date=2024-12-31&date_interval=Month&home_type=AllHomes&region_type=City&region_name=Irvine&percentile=Middle&fill=linear' | jq . >> tmp.txt
+curl -s -X GET 'http://127.0.0.1:8080/zhvis?start_date=2024-1-1&end_date=2024-12-31&date_interval=Month&home_type=AllHomes&region_type=County&region_name=Orange%20County&state=CA&percentile=Middle' | jq . >> tmp.txt
+curl -s -X GET 'http://127.0.0.1:8080/zhvis/derived?start_date=2023-1-1&end_date=2024-12-31&date_interval=month&home_type=AllHomes&region_type=city&region_name=Irvine&state=CA&percentile=Middle' | jq '.[] | {aggregation, zipcodes, prices: .series.prices[-3:]}' >> tmp.txt
+echo >> tmp.txt
+
+echo "Testing /zhvis/forecast" >> tmp.txt
+curl -s -X GET 'http://127.0.0.1:8080/zhvis/forecast?start_date=2015-1-1&end_date=2024-12-31&date_interval=month&home_type=AllHomes&region_type=City&region_name=Irvine&percentile=Middle&fill=linear&horizon=12' | jq '.[] | {fit, backtests, forecast: [.points[] | select(.forecast)]}' >> tmp.txt
 echo >> tmp.txt
 
 echo "Output saved to homie/local/tmp.txt"